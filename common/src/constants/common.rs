@@ -7,9 +7,28 @@ pub const D18_U256: U256 = U256([1_000_000_000_000_000_000, 0, 0, 0]); // 1e18 (
 pub const D9_U128: u128 = 1_000_000_000; // 1e9 (D9)
 pub const D18_U128: u128 = 1_000_000_000_000_000_000; // 1e18 (D18)
 
+/// MAX_OUTCOMES is the largest number of outcomes a market can have. Defaults to 16.
+///
+/// It sizes every per-outcome array on the zero copy Market account, so raising it grows the
+/// rent of every market (40 bytes per extra outcome) and the compute spent by loops over
+/// outcomes. `init_market` also takes one remaining account per outcome mint, so markets with
+/// more than ~20 outcomes need an address lookup table to fit in a single transaction.
+/// The invariant is the product of all reserves in a U256, so more outcomes also lowers the
+/// reserve size a market can reach before trades fail with MathOverflow.
+///
+/// Build with the `max-outcomes-32` feature for a larger bound.
+#[cfg(not(feature = "max-outcomes-32"))]
 pub const MAX_OUTCOMES: usize = 16;
+#[cfg(feature = "max-outcomes-32")]
+pub const MAX_OUTCOMES: usize = 32;
 pub const OUTCOME_MINT_DECIMALS: u8 = 9;
 
+/// NO_OUTCOME is the sentinel stored in optional outcome index fields on the zero copy Market account.
+pub const NO_OUTCOME: u8 = u8::MAX;
+
+// Outcome indices are stored and seeded as u8 with NO_OUTCOME reserved, so every index must fit below it
+const _: () = assert!(MAX_OUTCOMES <= NO_OUTCOME as usize);
+
 /// MAX_TVL_FEE is the maximum fee that can be set for the TVL fee, D18{1/year} -> 10% annually in D18.
 pub const MAX_TVL_FEE: u128 = 100_000_000_000_000_000;
 
@@ -22,8 +41,28 @@ pub const YEAR_IN_SECONDS: u64 = 365 * DAY_IN_SECONDS;
 /// LN_2 is the natural logarithm of 2, 693147180559945309. Used in reward token calculations. In D18.
 pub const LN_2: u128 = 693_147_180_559_945_309;
 
+/// BPS_DENOMINATOR is the basis point scale, 10_000 bps = 100%.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
 // Tunables (adjust or move to Market)
+/// Sell fee for markets that don't set `MarketConfig::fee_bps`
 pub const FEE_BPS: u64 = 10; // 0.1%
 pub const MAX_WITHDRAW_BPS: u64 = 50_00; // 50% of outcome reserve allowed per tx (in basis points; 10000 = 100%)
 
 pub const MIN_MARKET_DURATION: i64 = 1;
+
+/// Most legs a single `batch_buy` may carry. Each leg costs about as much compute as a `buy`,
+/// so this keeps a full batch well inside the transaction compute limit.
+pub const MAX_BATCH_BUYS: usize = 8;
+
+/// Most markets a single `batch_claim` may claim from. Each one costs about as much compute as a
+/// `redeem` and brings four accounts, so this keeps a full batch inside both limits.
+pub const MAX_BATCH_CLAIMS: usize = 6;
+
+/// Most markets a single `init_market_batch` may create. Each one creates its market, vault and
+/// every outcome mint, so the batch is bounded by compute and the transaction's account limit.
+pub const MAX_MARKETS_PER_BATCH: usize = 4;
+
+/// Outcome supply, in base units, that `close_market` treats as abandoned dust. 1_000 base units
+/// of a 9 decimal outcome token is 0.000001 tokens.
+pub const CLOSE_DUST_SUPPLY: u64 = 1_000;
@@ -7,5 +7,17 @@ pub const MARKET_SEED: &[u8] = b"market";
 #[constant]
 pub const VAULT_SEED: &[u8] = b"vault";
 
+/// Seed to derive the SPL token account holding a token-collateral market's reserves
+#[constant]
+pub const COLLATERAL_VAULT_SEED: &[u8] = b"collateral_vault";
+
 #[constant]
 pub const OUTCOME_MINT_SEED: &[u8] = b"mint";
+
+/// Seed to derive a user's `ClaimEscrow` PDA for a market
+#[constant]
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+/// Seed to derive the program-wide `ProgramConfig` PDA
+#[constant]
+pub const PROGRAM_CONFIG_SEED: &[u8] = b"program_config";
@@ -81,6 +81,135 @@ pub enum ErrorCode {
 
     #[msg("Supply is zero")]
     SupplyIsZero,
+
+    #[msg("Market liquidity is below the minimum required to resolve")]
+    InsufficientResolveLiquidity,
+
+    #[msg("TWAP window must end after it starts")]
+    InvalidTwapWindow,
+
+    #[msg("Fee basis points must not exceed 10_000")]
+    InvalidFeeBps,
+
+    #[msg("Market vault cannot cover its liabilities")]
+    MarketInsolvent,
+
+    #[msg("Trade is below the market's minimum size")]
+    TradeTooSmall,
+
+    #[msg("Basis points must not exceed 10_000")]
+    InvalidBps,
+
+    #[msg("Signer is not the market admin")]
+    Unauthorized,
+
+    #[msg("Withdrawal would drain lamports backing the reserves")]
+    WouldDrainReserves,
+
+    #[msg("Burn amount is too small to refund any lamports")]
+    BurnProducesNoRefund,
+
+    #[msg("Outcome supply would exceed the u64 range")]
+    SupplyTooLarge,
+
+    #[msg("No one holds the outcome the market would resolve to")]
+    NoWinnersForOutcome,
+
+    #[msg("Market must be seeded by the admin before public buys")]
+    MarketNotSeeded,
+
+    #[msg("Market must resolve after it is initialized")]
+    InvalidResolveTime,
+
+    #[msg("Lamports cannot be paid out to the market or its vault")]
+    InvalidRecipient,
+
+    #[msg("Market has already been resolved")]
+    MarketResolved,
+
+    #[msg("Market cannot be resolved before resolve_at")]
+    MarketNotExpired,
+
+    #[msg("Market has not been resolved")]
+    MarketNotResolved,
+
+    #[msg("Outcome did not win and cannot be redeemed")]
+    NotWinningOutcome,
+
+    #[msg("Account does not match the market's fee recipient")]
+    InvalidFeeRecipient,
+
+    #[msg("Trade output is below the minimum amount out")]
+    SlippageExceeded,
+
+    #[msg("Market creation is paused")]
+    MarketCreationPaused,
+
+    #[msg("Trade would exceed the market's reserve or supply caps")]
+    MarketCapExceeded,
+
+    #[msg("Caps cannot be set below the market's current reserves or supplies")]
+    InvalidLimits,
+
+    #[msg("Collateral accounts are missing or don't match the market's collateral mint")]
+    InvalidCollateral,
+
+    #[msg("Burning the outcome's whole supply would not reach the requested payout")]
+    PayoutUnreachable,
+
+    #[msg("Signer is not the market's pending admin")]
+    NotPendingAdmin,
+
+    #[msg("Market still has redeemable outcome tokens or unclaimed fees")]
+    MarketNotEmpty,
+
+    #[msg("Market has too few trades to be resolved")]
+    InsufficientResolveActivity,
+
+    #[msg("Label must be printable ASCII")]
+    InvalidLabelCharacters,
+
+    #[msg("A market needs at least two outcomes")]
+    TooFewOutcomes,
+
+    #[msg("Trading is paused by the market admin")]
+    MarketPaused,
+
+    #[msg("Buy would push the outcome's price above the market's maximum")]
+    OutcomePriceTooHigh,
+
+    #[msg("Market already holds reserves and can't be seeded")]
+    MarketAlreadySeeded,
+
+    #[msg("Batch is empty or has too many entries")]
+    InvalidBatchLength,
+
+    #[msg("Outcome mint decimals don't match the market's")]
+    DecimalsMismatch,
+
+    #[msg("Two markets in the batch share a label")]
+    DuplicateMarketLabel,
+
+    #[msg("Market or market vault doesn't match the PDA derived from its label")]
+    InvalidMarketSeed,
+
+    #[msg("Reserve needed to restore the invariant doesn't fit in u64")]
+    DeltaTooLarge,
+
+    #[msg("Market has no reserves yet, open it with buy or seed_market")]
+    MarketNotOpen,
+
+    #[msg("Stored invariant no longer matches the product of the reserves")]
+    InvariantDrift,
+
+    #[msg("Market vests its winnings, claim them with claim_to_escrow")]
+    ClaimMustBeEscrowed,
+
+    #[msg("Market pays claims directly, it has no claim vesting")]
+    EscrowNotEnabled,
+
+    #[msg("Escrowed winnings are locked until the market's claim vesting cliff")]
+    EscrowLocked,
 }
 
 /// Check a condition and return an error if it is not met.
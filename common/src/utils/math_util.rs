@@ -58,7 +58,7 @@ impl Eq for Decimal {}
 /// Implementation of the PartialOrd trait for Decimal
 impl PartialOrd for Decimal {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
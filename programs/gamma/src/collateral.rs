@@ -0,0 +1,192 @@
+//! Moving collateral in and out of a market's vault.
+//!
+//! Native SOL markets hold their reserves as lamports on the `market_vault` PDA. Markets created
+//! with a [`MarketConfig::collateral_mint`](crate::types::MarketConfig) hold them in an SPL token
+//! account at the [`COLLATERAL_VAULT_SEED`](common::constants::COLLATERAL_VAULT_SEED) PDA instead,
+//! owned by the market. Instructions resolve a [`CollateralVault`] once and go through it, so the
+//! market math never needs to know which kind of collateral it is backed by.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, TokenAccount, Transfer};
+use common::check_condition;
+use common::errors::ErrorCode;
+
+use crate::state::Market;
+
+/// Where a market's collateral is held
+pub enum CollateralVault<'a, 'info> {
+    /// Lamports on the `market_vault` PDA
+    Sol(AccountInfo<'info>),
+    /// Tokens in the market's collateral token account
+    Token(&'a Account<'info, TokenAccount>),
+}
+
+impl<'a, 'info> CollateralVault<'a, 'info> {
+    /// Pick the vault for `market`. Token markets must pass their collateral token account,
+    /// SOL markets must not pass one.
+    pub fn resolve(
+        market: &Market,
+        market_vault: AccountInfo<'info>,
+        collateral_vault: Option<&'a Account<'info, TokenAccount>>,
+    ) -> Result<Self> {
+        match (market.collateral_mint(), collateral_vault) {
+            (None, None) => Ok(Self::Sol(market_vault)),
+            (Some(mint), Some(vault)) => {
+                check_condition!(vault.mint == mint, InvalidCollateral);
+                Ok(Self::Token(vault))
+            }
+            _ => err!(ErrorCode::InvalidCollateral),
+        }
+    }
+
+    /// Collateral the vault holds, in lamports or the mint's base units
+    pub fn balance(&self) -> u64 {
+        match self {
+            Self::Sol(vault) => vault.lamports(),
+            Self::Token(vault) => vault.amount,
+        }
+    }
+
+    /// Part of [`CollateralVault::balance`] that must stay behind to keep the vault alive.
+    /// A token account's rent is paid in lamports outside its token balance, so this is 0 for
+    /// token vaults.
+    pub fn rent_min(&self) -> Result<u64> {
+        match self {
+            Self::Sol(_) => Ok(Rent::get()?.minimum_balance(0)),
+            Self::Token(_) => Ok(0),
+        }
+    }
+
+    /// Move `amount` from the signing `user` into the vault. Token markets debit
+    /// `user_collateral`, which must be owned by `user`.
+    pub fn deposit(
+        &self,
+        user: AccountInfo<'info>,
+        user_collateral: Option<&Account<'info, TokenAccount>>,
+        token_program: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        match self {
+            Self::Sol(vault) => anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    system_program,
+                    anchor_lang::system_program::Transfer {
+                        from: user,
+                        to: vault.clone(),
+                    },
+                ),
+                amount,
+            )
+            .map_err(|_| error!(ErrorCode::TransferFailed)),
+            Self::Token(vault) => {
+                let source = user_collateral.ok_or(error!(ErrorCode::InvalidCollateral))?;
+                check_condition!(source.owner == user.key(), InvalidCollateral);
+                token::transfer(
+                    CpiContext::new(
+                        token_program,
+                        Transfer {
+                            from: source.to_account_info(),
+                            to: vault.to_account_info(),
+                            authority: user,
+                        },
+                    ),
+                    amount,
+                )
+            }
+        }
+    }
+
+    /// Pay `amount` out of the vault to `recipient`. Token markets credit
+    /// `recipient_collateral`, which must be owned by `recipient`, with the transfer signed by
+    /// the `market` PDA. `token_program` is only needed for token markets.
+    ///
+    /// The market account must not be borrowed, since it signs the token transfer CPI.
+    pub fn withdraw(
+        &self,
+        recipient: AccountInfo<'info>,
+        recipient_collateral: Option<&Account<'info, TokenAccount>>,
+        market: AccountInfo<'info>,
+        market_signer_seeds: &[&[&[u8]]],
+        token_program: Option<AccountInfo<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        match self {
+            Self::Sol(vault) => {
+                vault.sub_lamports(amount)?;
+                recipient.add_lamports(amount)?;
+                Ok(())
+            }
+            Self::Token(vault) => {
+                let (Some(destination), Some(token_program)) =
+                    (recipient_collateral, token_program)
+                else {
+                    return err!(ErrorCode::InvalidCollateral);
+                };
+                check_condition!(
+                    destination.owner == recipient.key() && destination.key() != vault.key(),
+                    InvalidRecipient
+                );
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        token_program,
+                        Transfer {
+                            from: vault.to_account_info(),
+                            to: destination.to_account_info(),
+                            authority: market,
+                        },
+                        market_signer_seeds,
+                    ),
+                    amount,
+                )
+            }
+        }
+    }
+
+    /// Pay out everything the vault holds to `recipient` and close it. A token vault's remaining
+    /// tokens go to `recipient_collateral` and its rent lamports to `recipient`.
+    ///
+    /// Like [`CollateralVault::withdraw`], the market account must not be borrowed.
+    pub fn close(
+        &self,
+        recipient: AccountInfo<'info>,
+        recipient_collateral: Option<&Account<'info, TokenAccount>>,
+        market: AccountInfo<'info>,
+        market_signer_seeds: &[&[&[u8]]],
+        token_program: Option<AccountInfo<'info>>,
+    ) -> Result<()> {
+        let balance = self.balance();
+        match self {
+            Self::Sol(_) => self.withdraw(
+                recipient,
+                recipient_collateral,
+                market,
+                market_signer_seeds,
+                token_program,
+                balance,
+            ),
+            Self::Token(vault) => {
+                let token_program = token_program.ok_or(error!(ErrorCode::InvalidCollateral))?;
+                if balance > 0 {
+                    self.withdraw(
+                        recipient.clone(),
+                        recipient_collateral,
+                        market.clone(),
+                        market_signer_seeds,
+                        Some(token_program.clone()),
+                        balance,
+                    )?;
+                }
+                token::close_account(CpiContext::new_with_signer(
+                    token_program,
+                    CloseAccount {
+                        account: vault.to_account_info(),
+                        destination: recipient,
+                        authority: market,
+                    },
+                    market_signer_seeds,
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Off-chain representation of a [`Market`], enabled with the `dto` feature.
+//!
+//! The zero copy layout of [`Market`] is tied to the account and changes as fields are added.
+//! Indexers and SDKs should consume [`MarketDto`] instead, which keeps a stable, readable shape.
+
+use common::constants::NO_OUTCOME;
+use serde::Serialize;
+
+use crate::state::Market;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MarketDto {
+    pub label: String,
+    pub nonce: u64,
+    pub admin: String,
+    pub fee_recipient: String,
+    /// `None` for native SOL markets
+    pub collateral_mint: Option<String>,
+    pub num_outcomes: u8,
+    /// Product of the active reserves, as a decimal string since it can exceed any JSON number
+    pub invariant: String,
+    pub scale: u64,
+    pub resolve_at: i64,
+    /// Per-outcome vectors only contain the market's active outcomes
+    pub reserves: Vec<u64>,
+    pub supplies: Vec<u64>,
+    /// [`Market::outcome_price`] of each outcome, 1e9 = 1.0
+    pub prices: Vec<u64>,
+    /// [`Market::probabilities_bps`] of each outcome, summing to 10_000
+    pub probabilities_bps: Vec<u16>,
+    pub fee_bps: u16,
+    pub buy_fee_bps: u16,
+    pub undistributed_fees: u64,
+    pub undistributed_settlement_fees: u64,
+    pub lifetime_fees: u64,
+    pub trade_count: u64,
+    pub void_outcome: Option<u8>,
+    pub winning_outcome: Option<u8>,
+}
+
+impl From<&Market> for MarketDto {
+    fn from(market: &Market) -> Self {
+        let n = market.num_outcomes as usize;
+        let label_len = market
+            .label
+            .value
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(market.label.value.len());
+        // Computed fields fall back to zeros for a market whose state can't be priced
+        let prices = market.all_marginal_prices().unwrap_or_default();
+        let probabilities_bps = market.probabilities_bps().unwrap_or_default();
+
+        Self {
+            label: String::from_utf8_lossy(&market.label.value[..label_len]).into_owned(),
+            nonce: market.nonce,
+            admin: market.admin.to_string(),
+            fee_recipient: market.fee_recipient.to_string(),
+            collateral_mint: market.collateral_mint().map(|mint| mint.to_string()),
+            num_outcomes: market.num_outcomes,
+            invariant: market.invariant_u256().to_string(),
+            scale: market.scale,
+            resolve_at: market.resolve_at,
+            reserves: market.reserves[..n].to_vec(),
+            supplies: market.supplies[..n].to_vec(),
+            prices: prices[..n].to_vec(),
+            probabilities_bps: probabilities_bps[..n].to_vec(),
+            fee_bps: market.fee_bps,
+            buy_fee_bps: market.buy_fee_bps,
+            undistributed_fees: market.undistributed_fees,
+            undistributed_settlement_fees: market.undistributed_settlement_fees,
+            lifetime_fees: market.lifetime_fees,
+            trade_count: market.trade_count,
+            void_outcome: (market.void_outcome != NO_OUTCOME).then_some(market.void_outcome),
+            winning_outcome: market.winning_outcome().map(|outcome| outcome as u8),
+        }
+    }
+}
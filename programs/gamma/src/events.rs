@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::types::FixedSizeString;
+
+/// Emitted by [`buy`](crate::gamma::buy), and by [`swap_outcome`](crate::gamma::swap_outcome)
+/// for the outcome it buys, so indexers can follow trades without parsing logs
+#[event]
+pub struct BuyExecuted {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub outcome_index: u8,
+    /// Lamports deposited, or the refund a swap reinvests
+    pub amount_in: u64,
+    /// Outcome tokens minted
+    pub amount_out: u64,
+    /// [`Market::outcome_price`](crate::state::Market::outcome_price) after the trade, 1e9 = 1.0
+    pub outcome_price: u64,
+}
+
+/// Emitted by [`sell`](crate::gamma::sell), and by [`swap_outcome`](crate::gamma::swap_outcome)
+/// for the outcome it sells, so indexers can follow trades without parsing logs
+#[event]
+pub struct SellExecuted {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub outcome_index: u8,
+    /// Outcome tokens burned
+    pub burn_amount: u64,
+    /// Lamports paid to the user after the fee, or reinvested by a swap
+    pub net_payout: u64,
+    /// Lamports kept in the vault as trading fees
+    pub fee: u64,
+}
+
+/// Emitted by [`init_market`](crate::gamma::init_market) when a market is created
+#[event]
+pub struct MarketInitialized {
+    pub market: Pubkey,
+    pub admin: Pubkey,
+    pub num_outcomes: u8,
+    pub scale: u64,
+    pub resolve_at: i64,
+    pub label: FixedSizeString,
+}
+
+/// Emitted by [`set_limits`](crate::gamma::set_limits) with the market's new buy limits
+#[event]
+pub struct LimitsUpdated {
+    pub market: Pubkey,
+    pub min_deposit: u64,
+    pub max_reserve: u64,
+    pub max_total_reserves: u64,
+    pub max_supply: u64,
+}
+
+/// Emitted by [`update_resolve_at`](crate::gamma::update_resolve_at) when the admin moves a
+/// market's resolution time
+#[event]
+pub struct ResolveAtUpdated {
+    pub market: Pubkey,
+    pub resolve_at: i64,
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+
+use crate::collateral::CollateralVault;
+use crate::events::BuyExecuted;
+use crate::state::Market;
+use crate::types::BuyLeg;
+use common::check_condition;
+use common::constants::{COLLATERAL_VAULT_SEED, MAX_BATCH_BUYS, OUTCOME_MINT_SEED, VAULT_SEED};
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct BatchBuy<'info> {
+    /// Payer providing SOL, or the collateral token for token-collateral markets
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, receives the deposits for SOL markets
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+
+    /// User's collateral token account paying the deposits, only for token-collateral markets
+    #[account(mut)]
+    pub user_collateral_account: Option<Account<'info, TokenAccount>>,
+    // Remaining accounts: for each leg in order, its outcome mint followed by the user's token
+    // account for it
+}
+
+pub fn batch_buy<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchBuy<'info>>,
+    legs: Vec<BuyLeg>,
+) -> Result<()> {
+    check_condition!(
+        !legs.is_empty() && legs.len() <= MAX_BATCH_BUYS,
+        InvalidBatchLength
+    );
+    let remaining = ctx.remaining_accounts;
+    check_condition!(remaining.len() == 2 * legs.len(), InvalidMintCount);
+
+    let market_key = ctx.accounts.market.key();
+    let mut market = ctx.accounts.market.load_mut()?;
+
+    // Opening a market mints every outcome, which a leg's accounts don't cover
+    check_condition!(!market.is_empty(), MarketNotOpen);
+
+    let now = Clock::get()?.unix_timestamp;
+    let buyer_is_admin = ctx.accounts.user.key() == market.admin;
+
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+
+    // Circuit breaker: stop taking deposits into a vault that can't cover what it already owes
+    if market.enforce_solvency != 0 {
+        market.assert_solvent(vault.balance(), vault.rent_min()?)?;
+    }
+
+    // Every leg trades against the state the previous one left, all inside this transaction
+    market.accumulate_prices(now)?;
+    let mut total_in: u64 = 0;
+    let mut fills = Vec::with_capacity(legs.len());
+    for leg in &legs {
+        let idx = leg.outcome_index as usize;
+        market.check_buy_allowed(idx, now, buyer_is_admin)?;
+        check_condition!(leg.amount_in > 0, DepositIsZero);
+        let amount_out = market.buy_outcome(idx, leg.amount_in)?;
+        check_condition!(amount_out >= leg.min_amount_out, SlippageExceeded);
+        fills.push((amount_out, market.outcome_price(idx)?));
+        total_in = total_in
+            .checked_add(leg.amount_in)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+    }
+
+    vault.deposit(
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.user_collateral_account.as_ref(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        total_in,
+    )?;
+
+    let decimals = market.decimals;
+    let label = market.label;
+    let nonce = market.nonce.to_le_bytes();
+    let bump = [market.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
+
+    // The market PDA signs the mints, so it can't stay borrowed across the CPIs
+    drop(market);
+
+    for ((leg, (amount_out, outcome_price)), pair) in
+        legs.iter().zip(fills).zip(remaining.chunks(2))
+    {
+        let (mint_info, token_account_info) = (&pair[0], &pair[1]);
+
+        let (expected_mint, _) = Pubkey::find_program_address(
+            &[OUTCOME_MINT_SEED, market_key.as_ref(), &[leg.outcome_index]],
+            ctx.program_id,
+        );
+        check_condition!(mint_info.key() == expected_mint, InvalidMintSeed);
+        let mint = Account::<Mint>::try_from(mint_info)?;
+        check_condition!(mint.decimals == decimals, DecimalsMismatch);
+
+        let token_account = Account::<TokenAccount>::try_from(token_account_info)?;
+        check_condition!(
+            token_account.mint == expected_mint && token_account.owner == ctx.accounts.user.key(),
+            InvalidRecipient
+        );
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: mint_info.clone(),
+                    to: token_account_info.clone(),
+                    authority: ctx.accounts.market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount_out,
+        )?;
+
+        emit!(BuyExecuted {
+            market: market_key,
+            user: ctx.accounts.user.key(),
+            outcome_index: leg.outcome_index,
+            amount_in: leg.amount_in,
+            amount_out,
+            outcome_price,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+
+use crate::collateral::CollateralVault;
+use crate::state::Market;
+use crate::types::outcome_index_u8;
+use common::check_condition;
+use common::constants::{MAX_BATCH_CLAIMS, OUTCOME_MINT_SEED, VAULT_SEED};
+use common::errors::ErrorCode;
+
+/// Accounts one market contributes to a [`batch_claim`]
+const ACCOUNTS_PER_CLAIM: usize = 4;
+
+#[derive(Accounts)]
+pub struct BatchClaim<'info> {
+    /// Holder burning winning tokens and receiving SOL from every market
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    // Remaining accounts: for each market in order, the market, its vault, its winning outcome
+    // mint and the user's token account for it
+}
+
+pub fn batch_claim<'info>(ctx: Context<'_, '_, 'info, 'info, BatchClaim<'info>>) -> Result<()> {
+    let remaining = ctx.remaining_accounts;
+    let claims = remaining.len() / ACCOUNTS_PER_CLAIM;
+    check_condition!(
+        claims > 0 && claims * ACCOUNTS_PER_CLAIM == remaining.len(),
+        InvalidMintCount
+    );
+    check_condition!(claims <= MAX_BATCH_CLAIMS, InvalidBatchLength);
+
+    let user_key = ctx.accounts.user.key();
+    let mut payouts = Vec::with_capacity(claims);
+    for group in remaining.chunks(ACCOUNTS_PER_CLAIM) {
+        let (market_info, vault_info, mint_info, token_account_info) =
+            (&group[0], &group[1], &group[2], &group[3]);
+
+        // Checks the owner is this program and the discriminator is a market's
+        let market_loader = AccountLoader::<Market>::try_from(market_info)?;
+        let market_key = market_loader.key();
+        // The payout goes to `user` with add_lamports, so it must never be the market's own accounts
+        check_condition!(
+            user_key != market_key && user_key != vault_info.key(),
+            InvalidRecipient
+        );
+
+        let (expected_vault, _) =
+            Pubkey::find_program_address(&[VAULT_SEED, market_key.as_ref()], ctx.program_id);
+        check_condition!(vault_info.key() == expected_vault, InvalidMarketSeed);
+
+        let mut market = market_loader.load_mut()?;
+        check_condition!(!market.vests_claims(), ClaimMustBeEscrowed);
+        let winner = market
+            .winning_outcome()
+            .ok_or(error!(ErrorCode::MarketNotResolved))?;
+
+        let (expected_mint, _) = Pubkey::find_program_address(
+            &[
+                OUTCOME_MINT_SEED,
+                market_key.as_ref(),
+                &[outcome_index_u8(winner)?],
+            ],
+            ctx.program_id,
+        );
+        check_condition!(mint_info.key() == expected_mint, InvalidMintSeed);
+        let mint = Account::<Mint>::try_from(mint_info)?;
+        check_condition!(mint.decimals == market.decimals, DecimalsMismatch);
+
+        let token_account = Account::<TokenAccount>::try_from(token_account_info)?;
+        check_condition!(
+            token_account.mint == expected_mint && token_account.owner == user_key,
+            InvalidRecipient
+        );
+        let amount = token_account.amount;
+
+        // Token markets need their collateral accounts, which a claim group doesn't carry
+        let vault = CollateralVault::resolve(&market, vault_info.clone(), None)?;
+
+        // Settle the market's books before the burn so a rejected claim keeps the user's tokens
+        let net_payout = market.redeem(winner, amount, vault.balance(), vault.rent_min()?)?;
+        payouts.push(net_payout);
+        drop(market);
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: mint_info.clone(),
+                    from: token_account_info.clone(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+
+    // Pay out only once every burn has gone through, so no CPI runs after a vault's lamports moved
+    for (group, net_payout) in remaining.chunks(ACCOUNTS_PER_CLAIM).zip(payouts) {
+        let (market_info, vault_info) = (&group[0], &group[1]);
+        let market_loader = AccountLoader::<Market>::try_from(market_info)?;
+        let market = market_loader.load()?;
+        let vault = CollateralVault::resolve(&market, vault_info.clone(), None)?;
+
+        let label = market.label;
+        let nonce = market.nonce.to_le_bytes();
+        let bump = [market.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
+        drop(market);
+
+        vault.withdraw(
+            ctx.accounts.user.to_account_info(),
+            None,
+            market_info.clone(),
+            signer_seeds,
+            None,
+            net_payout,
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,14 +1,17 @@
+use crate::collateral::CollateralVault;
+use crate::events::BuyExecuted;
 use crate::state::Market;
+use crate::types::outcome_index_u8;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
 use common::check_condition;
-use common::constants::{MARKET_SEED, OUTCOME_MINT_DECIMALS, OUTCOME_MINT_SEED, VAULT_SEED};
+use common::constants::{COLLATERAL_VAULT_SEED, OUTCOME_MINT_SEED, VAULT_SEED};
 use common::errors::ErrorCode;
 
 #[derive(Accounts)]
-#[instruction(outcome_index: u8, amount_in: u64)]
+#[instruction(outcome_index: u8, amount_in: u64, min_amount_out: u64)]
 pub struct Buy<'info> {
-    /// Payer providing SOL
+    /// Payer providing SOL, or the collateral token for token-collateral markets
     #[account(mut)]
     pub user: Signer<'info>,
 
@@ -26,7 +29,6 @@ pub struct Buy<'info> {
     /// Outcome SPL token to mint to user. Authority must be the market PDA.
     #[account(
         mut,
-        mint::decimals = OUTCOME_MINT_DECIMALS,
         mint::authority = market,
         seeds = [OUTCOME_MINT_SEED, market.key().as_ref(), &[outcome_index]],
         bump,
@@ -43,53 +45,93 @@ pub struct Buy<'info> {
 
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+
+    /// User's collateral token account paying `amount_in`, only for token-collateral markets
+    #[account(mut)]
+    pub user_collateral_account: Option<Account<'info, TokenAccount>>,
+    // Remaining accounts, only for the buy that opens an empty market: for every other outcome
+    // in order, its outcome mint followed by the user's token account for it
 }
 
-pub fn buy(ctx: Context<Buy>, outcome_index: u8, amount_in: u64) -> Result<()> {
+pub fn buy<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Buy<'info>>,
+    outcome_index: u8,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<u64> {
     // Basic validation
     let market_key = ctx.accounts.market.key();
     let mut market = ctx.accounts.market.load_mut()?;
     let idx = outcome_index as usize;
-    let num_outcomes = market.num_outcomes as usize;
 
     let now = Clock::get()?.unix_timestamp;
-    check_condition!(now < market.resolve_at, MarketExpired);
-
+    market.check_buy_allowed(idx, now, ctx.accounts.user.key() == market.admin)?;
     check_condition!(amount_in > 0, DepositIsZero);
-    check_condition!(num_outcomes > 0, OutcomeBelowZero);
-    check_condition!(idx < num_outcomes, InvalidOutcomeIndex);
+
+    // Opening the market mints the buyer every outcome's opening supply
+    let is_opening = market.is_empty();
+    let num_outcomes = market.num_outcomes as usize;
+    let remaining = ctx.remaining_accounts;
+    if is_opening {
+        check_condition!(remaining.len() == 2 * (num_outcomes - 1), InvalidMintCount);
+    }
 
     let (expected_mint_key, _) = Pubkey::find_program_address(
-        &[OUTCOME_MINT_SEED, market_key.as_ref(), &[idx as u8]],
+        &[OUTCOME_MINT_SEED, market_key.as_ref(), &[outcome_index]],
         ctx.program_id,
     );
     check_condition!(
         ctx.accounts.outcome_mint.key() == expected_mint_key,
         InvalidMintSeed
     );
+    check_condition!(
+        ctx.accounts.outcome_mint.decimals == market.decimals,
+        DecimalsMismatch
+    );
 
-    // Transfer SOL from user -> market vault
-    anchor_lang::system_program::transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            anchor_lang::system_program::Transfer {
-                from: ctx.accounts.user.to_account_info(),
-                to: ctx.accounts.market_vault.to_account_info(),
-            },
-        ),
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+
+    // Circuit breaker: stop taking deposits into a vault that can't cover what it already owes
+    if market.enforce_solvency != 0 {
+        market.assert_solvent(vault.balance(), vault.rent_min()?)?;
+    }
+
+    // Transfer collateral from user -> market vault
+    vault.deposit(
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.user_collateral_account.as_ref(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
         amount_in,
-    )
-    .map_err(|_| error!(ErrorCode::TransferFailed))?;
+    )?;
 
+    market.accumulate_prices(now)?;
     let amount_out = market.buy_outcome(idx, amount_in)?;
+    // Protects the user from the price moving between their quote and execution
+    check_condition!(amount_out >= min_amount_out, SlippageExceeded);
+    let outcome_price = market.outcome_price(idx)?;
+    let opening_tokens = market.opening_deposit()?;
 
     // --- Mint outcome tokens to user via CPI, signed by market PDA ---
     //
-    // We assume the outcome_mint authority is the market PDA created with seeds: [MARKET_SEED, label.as_bytes()]
-    // and that `market.bump` matches the PDA bump for that seed. Adjust seeds if you used a different mint authority.
+    // The outcome_mint authority is the market PDA, derived in init_market from the padded label bytes.
     //
-    let label = market.label.clone();
-    let signer_seeds: &[&[&[u8]]] = &[&[MARKET_SEED, label.as_bytes(), &[market.bump]]];
+    let label = market.label;
+    let nonce = market.nonce.to_le_bytes();
+    let bump = [market.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
 
     drop(market);
 
@@ -105,11 +147,56 @@ pub fn buy(ctx: Context<Buy>, outcome_index: u8, amount_in: u64) -> Result<()> {
         signer_seeds,
     );
 
-    msg!("amount_out: {}", amount_out);
-
     // minted_u64 may be zero in edge cases — handle it gracefully (still OK to call mint_to with 0).
     // token::mint_to(cpi_ctx, amount_out).map_err(|_| error!(ErrorCode::TokenMintFailed))?;
     token::mint_to(cpi_ctx, amount_out)?;
 
-    Ok(())
+    if is_opening {
+        let others = (0..num_outcomes).filter(|i| *i != idx);
+        for (i, pair) in others.zip(remaining.chunks(2)) {
+            let (mint_info, token_account_info) = (&pair[0], &pair[1]);
+
+            let (expected_mint, _) = Pubkey::find_program_address(
+                &[
+                    OUTCOME_MINT_SEED,
+                    market_key.as_ref(),
+                    &[outcome_index_u8(i)?],
+                ],
+                ctx.program_id,
+            );
+            check_condition!(mint_info.key() == expected_mint, InvalidMintSeed);
+
+            let token_account = Account::<TokenAccount>::try_from(token_account_info)?;
+            check_condition!(
+                token_account.mint == expected_mint
+                    && token_account.owner == ctx.accounts.user.key(),
+                InvalidRecipient
+            );
+
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: mint_info.clone(),
+                        to: token_account_info.clone(),
+                        authority: ctx.accounts.market.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                opening_tokens,
+            )?;
+        }
+    }
+
+    emit!(BuyExecuted {
+        market: market_key,
+        user: ctx.accounts.user.key(),
+        outcome_index,
+        amount_in,
+        amount_out,
+        outcome_price,
+    });
+    msg!("outcome_price: {} {}", outcome_index, outcome_price);
+
+    Ok(outcome_price)
 }
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::collateral::CollateralVault;
+use crate::state::Market;
+use common::check_condition;
+use common::constants::{COLLATERAL_VAULT_SEED, VAULT_SEED};
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+        has_one = fee_recipient @ ErrorCode::InvalidFeeRecipient,
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, lamports are moved out by the program which owns it
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Must be the market's fee recipient, only receives lamports
+    #[account(mut)]
+    pub fee_recipient: UncheckedAccount<'info>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+
+    /// Fee recipient's collateral token account, only for token-collateral markets
+    #[account(mut)]
+    pub fee_recipient_collateral_account: Option<Account<'info, TokenAccount>>,
+
+    /// Token program for the collateral transfer, only for token-collateral markets
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+    // The fees go to `fee_recipient` with add_lamports, so it must never be the market's own accounts
+    let recipient_key = ctx.accounts.fee_recipient.key();
+    check_condition!(
+        recipient_key != ctx.accounts.market.key()
+            && recipient_key != ctx.accounts.market_vault.key(),
+        InvalidRecipient
+    );
+
+    let mut market = ctx.accounts.market.load_mut()?;
+
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+
+    // Trading and settlement fees move together, and the vault keeps its reserves and rent-exempt minimum
+    let fees = market.withdrawable_fees(vault.balance(), vault.rent_min()?)?;
+    market.undistributed_fees = 0;
+    market.undistributed_settlement_fees = 0;
+
+    let label = market.label;
+    let nonce = market.nonce.to_le_bytes();
+    let bump = [market.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
+
+    // The market PDA signs token payouts, so it can't stay borrowed across the CPI
+    drop(market);
+
+    vault.withdraw(
+        ctx.accounts.fee_recipient.to_account_info(),
+        ctx.accounts.fee_recipient_collateral_account.as_ref(),
+        ctx.accounts.market.to_account_info(),
+        signer_seeds,
+        ctx.accounts
+            .token_program
+            .as_ref()
+            .map(|program| program.to_account_info()),
+        fees,
+    )?;
+    msg!("claimed fees: {}", fees);
+
+    Ok(())
+}
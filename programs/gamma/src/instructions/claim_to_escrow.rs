@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+
+use crate::collateral::CollateralVault;
+use crate::state::{ClaimEscrow, Market};
+use common::check_condition;
+use common::constants::seeds::*;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(outcome_index: u8, amount: u64)]
+pub struct ClaimToEscrow<'info> {
+    /// Holder burning outcome tokens, pays for the escrow the first time they claim
+    #[account(
+        mut,
+        constraint = user_outcome_token_account.owner == user.key()
+    )]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, lamports are moved out by the program which owns it
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    /// Outcome SPL token being redeemed. Authority must be the market PDA.
+    #[account(
+        mut,
+        mint::authority = market,
+        seeds = [OUTCOME_MINT_SEED, market.key().as_ref(), &[outcome_index]],
+        bump,
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user,
+        associated_token::token_program = outcome_mint.to_account_info().owner,
+    )]
+    pub user_outcome_token_account: Account<'info, TokenAccount>,
+
+    /// Escrow holding the user's claimed winnings until the market's vesting cliff
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = ClaimEscrow::SIZE,
+        seeds = [ESCROW_SEED, market.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, ClaimEscrow>,
+
+    /// Token program for burn CPI
+    pub token_program: Program<'info, Token>,
+
+    /// System program for creating the escrow
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_to_escrow(ctx: Context<ClaimToEscrow>, outcome_index: u8, amount: u64) -> Result<()> {
+    check_condition!(
+        ctx.accounts.user_outcome_token_account.amount >= amount,
+        InsufficientFunds
+    );
+
+    let mut market = ctx.accounts.market.load_mut()?;
+    check_condition!(market.vests_claims(), EscrowNotEnabled);
+    check_condition!(
+        ctx.accounts.outcome_mint.decimals == market.decimals,
+        DecimalsMismatch
+    );
+
+    // The escrow holds lamports, so token-collateral markets can't vest their claims
+    let vault =
+        CollateralVault::resolve(&market, ctx.accounts.market_vault.to_account_info(), None)?;
+
+    // Settle the market's books before the burn so a rejected claim keeps the user's tokens
+    let net_payout = market.redeem(
+        outcome_index as usize,
+        amount,
+        vault.balance(),
+        vault.rent_min()?,
+    )?;
+    let release_at = market.escrow_release_at()?;
+
+    let label = market.label;
+    let nonce = market.nonce.to_le_bytes();
+    let bump = [market.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
+    drop(market);
+
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.outcome_mint.to_account_info(),
+                from: ctx.accounts.user_outcome_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    vault.withdraw(
+        ctx.accounts.escrow.to_account_info(),
+        None,
+        ctx.accounts.market.to_account_info(),
+        signer_seeds,
+        None,
+        net_payout,
+    )?;
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.market = ctx.accounts.market.key();
+    escrow.user = ctx.accounts.user.key();
+    escrow.amount = escrow
+        .amount
+        .checked_add(net_payout)
+        .ok_or(error!(ErrorCode::MathOverflow))?;
+    escrow.release_at = release_at;
+    escrow.bump = ctx.bumps.escrow;
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::collateral::CollateralVault;
+use crate::state::Market;
+use common::constants::{COLLATERAL_VAULT_SEED, VAULT_SEED};
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        close = admin,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, lamports are moved out by the program which owns it
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+
+    /// Admin's collateral token account for leftover tokens, only for token-collateral markets
+    #[account(mut)]
+    pub admin_collateral_account: Option<Account<'info, TokenAccount>>,
+
+    /// Token program to close the collateral vault, only for token-collateral markets
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+    let market = ctx.accounts.market.load()?;
+    market.check_closable()?;
+
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+
+    let label = market.label;
+    let nonce = market.nonce.to_le_bytes();
+    let bump = [market.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
+
+    // The market PDA signs the token vault's close, so it can't stay borrowed across the CPI
+    drop(market);
+
+    // Whatever is left (rent, dust, settlement fees, unbacked reserves) goes to the admin
+    vault.close(
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.admin_collateral_account.as_ref(),
+        ctx.accounts.market.to_account_info(),
+        signer_seeds,
+        ctx.accounts
+            .token_program
+            .as_ref()
+            .map(|program| program.to_account_info()),
+    )?;
+
+    // Token markets still hold the SOL vault's rent
+    let market_vault = ctx.accounts.market_vault.to_account_info();
+    let rent = market_vault.lamports();
+    if rent > 0 {
+        market_vault.sub_lamports(rent)?;
+        ctx.accounts.admin.add_lamports(rent)?;
+    }
+    msg!("closed market: {}", ctx.accounts.market.key());
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Market;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct DonateFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+}
+
+pub fn donate_fees(ctx: Context<DonateFees>) -> Result<()> {
+    let mut market = ctx.accounts.market.load_mut()?;
+
+    // Fees already sit in the vault, so donating only moves them into the reserves
+    let donated = market.donate_fees()?;
+    msg!("donated fees: {}", donated);
+
+    Ok(())
+}
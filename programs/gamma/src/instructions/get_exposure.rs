@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Market;
+
+#[derive(Accounts)]
+pub struct GetExposure<'info> {
+    pub market: AccountLoader<'info, Market>,
+}
+
+/// Returns, for each active outcome, the lamports the vault would owe holders if that outcome
+/// won, see [`Market::simulate_resolution`]. The largest value is the worst-case exposure of
+/// whoever backs the market's liquidity.
+pub fn get_exposure(ctx: Context<GetExposure>) -> Result<Vec<u64>> {
+    let market = ctx.accounts.market.load()?;
+    let n = market.num_outcomes as usize;
+
+    (0..n)
+        .map(|winner| market.simulate_resolution(winner))
+        .collect()
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Market;
+
+#[derive(Accounts)]
+pub struct GetProbabilities<'info> {
+    pub market: AccountLoader<'info, Market>,
+}
+
+/// Returns the implied probability of each active outcome in basis points.
+/// The values always sum to 10_000 unless the market holds no reserves yet.
+pub fn get_probabilities(ctx: Context<GetProbabilities>) -> Result<Vec<u16>> {
+    let market = ctx.accounts.market.load()?;
+    let n = market.num_outcomes as usize;
+
+    let probabilities = market.probabilities_bps()?;
+
+    Ok(probabilities[..n].to_vec())
+}
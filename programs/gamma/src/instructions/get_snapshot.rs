@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Market;
+use crate::types::MarketSnapshot;
+
+#[derive(Accounts)]
+pub struct GetSnapshot<'info> {
+    pub market: AccountLoader<'info, Market>,
+}
+
+/// Returns a [`MarketSnapshot`] of the market's tracked state.
+pub fn get_snapshot(ctx: Context<GetSnapshot>) -> Result<MarketSnapshot> {
+    let market = ctx.accounts.market.load()?;
+    market.snapshot()
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::collateral::CollateralVault;
+use crate::state::Market;
+use crate::types::VaultHealth;
+use common::constants::{COLLATERAL_VAULT_SEED, VAULT_SEED};
+
+#[derive(Accounts)]
+pub struct GetVaultHealth<'info> {
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, only its lamport balance is read
+    #[account(
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+}
+
+/// Returns the vault's [`VaultHealth`] so monitors can alert on insolvency in one call.
+pub fn get_vault_health(ctx: Context<GetVaultHealth>) -> Result<VaultHealth> {
+    let market = ctx.accounts.market.load()?;
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+    market.vault_health(vault.balance(), vault.rent_min()?)
+}
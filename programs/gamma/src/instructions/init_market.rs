@@ -1,20 +1,23 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::Token;
+use anchor_spl::token::{Mint, Token};
 use solana_program::program_pack::Pack;
-use spl_math::uint::U256;
 use spl_token::solana_program;
 
-use crate::state::Market;
-use crate::types::{FixedSizeString, MAX_PADDED_STRING_LENGTH};
+use crate::events::MarketInitialized;
+use crate::state::{Market, ProgramConfig};
+use crate::types::{
+    market_nonce_seed, outcome_index_u8, FixedSizeString, MarketConfig, MarketSpec,
+};
 use anchor_lang::system_program;
 use common::constants::{
-    MARKET_SEED, MAX_OUTCOMES, MIN_MARKET_DURATION, OUTCOME_MINT_DECIMALS, OUTCOME_MINT_SEED,
+    BPS_DENOMINATOR, COLLATERAL_VAULT_SEED, FEE_BPS, MARKET_SEED, MAX_OUTCOMES,
+    MIN_MARKET_DURATION, NO_OUTCOME, OUTCOME_MINT_DECIMALS, OUTCOME_MINT_SEED, PROGRAM_CONFIG_SEED,
     VAULT_SEED,
 };
 use common::{check_condition, errors::ErrorCode};
 
 #[derive(Accounts)]
-#[instruction(num_outcomes: u8, scale: u64, resolve_at: i64, label: FixedSizeString)]
+#[instruction(num_outcomes: u8, scale: u64, resolve_at: i64, label: FixedSizeString, config: MarketConfig)]
 pub struct InitMarket<'info> {
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -23,11 +26,18 @@ pub struct InitMarket<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
+    #[account(
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump = program_config.bump,
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    /// Unique per label and nonce: `init` fails if a market already lives at this PDA
     #[account(
         init,
         payer = admin,
         space = Market::SIZE,
-        seeds = [MARKET_SEED, label.as_bytes()],
+        seeds = [MARKET_SEED, label.as_bytes(), market_nonce_seed(&config.nonce.to_le_bytes())],
         bump
     )]
     pub market: AccountLoader<'info, Market>,
@@ -41,6 +51,18 @@ pub struct InitMarket<'info> {
         bump,
     )]
     pub market_vault: UncheckedAccount<'info>,
+
+    /// Mint of [`MarketConfig::collateral_mint`], omitted for SOL markets
+    pub collateral_mint: Option<Account<'info, Mint>>,
+
+    /// CHECK: Check PDA. Created here as the market's collateral token account when
+    /// [`MarketConfig::collateral_mint`] is set, omitted for SOL markets
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<UncheckedAccount<'info>>,
 }
 
 pub fn init_market<'info>(
@@ -49,98 +71,309 @@ pub fn init_market<'info>(
     scale: u64,
     resolve_at: i64,
     label: FixedSizeString,
+    config: MarketConfig,
 ) -> Result<()> {
-    let mut market = ctx.accounts.market.load_init()?;
-
-    let now = Clock::get()?.unix_timestamp;
-    check_condition!(now + MIN_MARKET_DURATION < resolve_at, MarketTooQuick);
-    check_condition!(num_outcomes as usize <= MAX_OUTCOMES, TooManyOutcomes);
     check_condition!(
-        label.value.len() <= MAX_PADDED_STRING_LENGTH,
-        InvalidLabelLength
+        !ctx.accounts.program_config.market_creation_paused,
+        MarketCreationPaused
     );
 
-    let bump = ctx.bumps.market;
+    let mut market = ctx.accounts.market.load_init()?;
+    let spec = MarketSpec {
+        label,
+        num_outcomes,
+        scale,
+        resolve_at,
+    };
+    write_market(
+        &mut market,
+        &spec,
+        &config,
+        ctx.accounts.admin.key(),
+        ctx.bumps.market,
+        ctx.bumps.market_vault,
+        &Clock::get()?,
+    )?;
+
     let market_key = ctx.accounts.market.key();
 
     // Market PDA seeds
-    let market_signer_seeds: &[&[&[u8]]] = &[&[MARKET_SEED, label.as_bytes(), &[bump]]];
+    let bump_seed = [ctx.bumps.market];
+    let nonce_seed = config.nonce.to_le_bytes();
+    let market_signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce_seed, &bump_seed)];
+
+    market.collateral_mint = match config.collateral_mint {
+        Some(collateral_mint) => {
+            init_collateral_vault(&ctx, collateral_mint, &market_key)?;
+            collateral_mint
+        }
+        None => {
+            check_condition!(
+                ctx.accounts.collateral_mint.is_none() && ctx.accounts.collateral_vault.is_none(),
+                InvalidCollateral
+            );
+            Pubkey::default()
+        }
+    };
+
+    // Backstop funding, deposited beyond the vault's rent-exempt minimum.
+    // It is paid in lamports, so token-collateral markets are funded by transferring
+    // collateral into their vault instead.
+    if config.min_vault_funding > 0 {
+        check_condition!(config.collateral_mint.is_none(), InvalidCollateral);
+        check_condition!(
+            ctx.accounts.admin.lamports() >= config.min_vault_funding,
+            InsufficientFunds
+        );
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.market_vault.to_account_info(),
+                },
+            ),
+            config.min_vault_funding,
+        )?;
+        market.vault_funding = config.min_vault_funding;
+    }
+
+    let remaining = ctx.remaining_accounts;
+
+    check_condition!(remaining.len() == num_outcomes as usize, InvalidMintCount);
+
+    OutcomeMintCreator {
+        payer: &ctx.accounts.admin.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+        token_program: &ctx.accounts.token_program.to_account_info(),
+        rent: &ctx.accounts.rent.to_account_info(),
+    }
+    .create(
+        remaining,
+        &market_key,
+        market.decimals,
+        market_signer_seeds,
+        ctx.program_id,
+    )?;
+
+    emit!(MarketInitialized {
+        market: market_key,
+        admin: market.admin,
+        num_outcomes,
+        scale,
+        resolve_at,
+        label,
+    });
+
+    Ok(())
+}
 
-    market.admin = *ctx.accounts.admin.key;
+/// Validate `spec` and `config` and write them into a freshly loaded `market`, with everything
+/// else `init_market` sets that doesn't need accounts: timestamps, bumps and the invariant.
+pub(crate) fn write_market(
+    market: &mut Market,
+    spec: &MarketSpec,
+    config: &MarketConfig,
+    admin: Pubkey,
+    bump: u8,
+    vault_bump: u8,
+    clock: &Clock,
+) -> Result<()> {
+    let MarketSpec {
+        label,
+        num_outcomes,
+        scale,
+        resolve_at,
+    } = *spec;
+
+    let now = clock.unix_timestamp;
+    market.initialized_at = now as u64;
+    market.created_slot = clock.slot;
+    check_condition!(
+        resolve_at > market.initialized_at as i64,
+        InvalidResolveTime
+    );
+    check_condition!(now + MIN_MARKET_DURATION < resolve_at, MarketTooQuick);
+    check_condition!(num_outcomes >= 2, TooFewOutcomes);
+    check_condition!(num_outcomes as usize <= MAX_OUTCOMES, TooManyOutcomes);
+    label.validate()?;
+
+    market.admin = admin;
+    market.fee_recipient = config.fee_recipient.unwrap_or(market.admin);
     market.num_outcomes = num_outcomes;
     market.resolve_at = resolve_at;
+    market.last_trade_at = now;
     market.scale = scale;
-    market.bump = ctx.bumps.market;
-    market.vault_bump = ctx.bumps.market_vault;
+    market.decimals = OUTCOME_MINT_DECIMALS;
+    market.bump = bump;
+    market.vault_bump = vault_bump;
     market.label = label;
+    market.nonce = config.nonce;
+    market.initialization_mode = config.initialization_mode as u8;
+    let fee_bps = config.fee_bps.unwrap_or(FEE_BPS as u16);
+    check_condition!(fee_bps as u64 <= BPS_DENOMINATOR, InvalidFeeBps);
+    market.fee_bps = fee_bps;
+    check_condition!(config.buy_fee_bps as u64 <= BPS_DENOMINATOR, InvalidFeeBps);
+    market.buy_fee_bps = config.buy_fee_bps;
+    if config.sell_fee_ramp_secs > 0 {
+        check_condition!(
+            config.sell_fee_max_bps >= fee_bps && config.sell_fee_max_bps as u64 <= BPS_DENOMINATOR,
+            InvalidFeeBps
+        );
+    }
+    market.sell_fee_ramp_secs = config.sell_fee_ramp_secs as i64;
+    market.sell_fee_max_bps = config.sell_fee_max_bps;
+    market.fee_rounding = config.fee_rounding as u8;
+    market.min_resolve_liquidity = config.min_resolve_liquidity;
+    market.min_trades_before_resolve = config.min_trades_before_resolve;
+    market.virtual_reserve = config.virtual_reserve;
+    market.max_buyable_price = config.max_buyable_price;
+    market.invariant_recheck_interval = config.invariant_recheck_interval;
+    market.void_outcome = match config.void_outcome {
+        Some(void_outcome) => {
+            check_condition!(void_outcome < num_outcomes, InvalidOutcomeIndex);
+            void_outcome
+        }
+        None => NO_OUTCOME,
+    };
+    check_condition!(
+        config.settlement_fee_bps as u64 <= BPS_DENOMINATOR,
+        InvalidFeeBps
+    );
+    market.settlement_fee_bps = config.settlement_fee_bps;
+    check_condition!(
+        config.min_trade_fraction_bps as u64 <= BPS_DENOMINATOR,
+        InvalidBps
+    );
+    market.min_trade_fraction_bps = config.min_trade_fraction_bps;
+    market.enforce_solvency = config.enforce_solvency as u8;
+    market.post_expiry_sell_grace = config.post_expiry_sell_grace as i64;
+    market.claim_vesting_secs = config.claim_vesting_secs as i64;
 
-    let remaining = ctx.remaining_accounts;
+    // No reserves exist yet, so start from the product of those the first buy opens at
+    let opening_invariant = market.opening_invariant()?;
+    market.set_invariant_u256(opening_invariant);
 
-    check_condition!(remaining.len() == num_outcomes as usize, InvalidMintCount);
+    Ok(())
+}
 
-    for (i, acct) in remaining.iter().enumerate() {
-        // Unchecked -> Mint
-        let mint_info = acct.clone();
-        let rent_info = ctx.accounts.rent.to_account_info().clone();
+/// Accounts that pay for and create a new market's outcome mints
+pub(crate) struct OutcomeMintCreator<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub rent: &'a AccountInfo<'info>,
+}
 
-        // get PDA + bump exactly how off-chain code does
-        let (expected_key, mint_bump) = Pubkey::find_program_address(
-            &[OUTCOME_MINT_SEED, market_key.as_ref(), &[i as u8]],
-            ctx.program_id,
-        );
+impl<'info> OutcomeMintCreator<'_, 'info> {
+    /// Create and initialize one outcome mint per account in `mints`, in outcome order, each
+    /// checked against its PDA and with the market as mint authority
+    pub fn create(
+        &self,
+        mints: &[AccountInfo<'info>],
+        market_key: &Pubkey,
+        decimals: u8,
+        market_signer_seeds: &[&[&[u8]]],
+        program_id: &Pubkey,
+    ) -> Result<()> {
+        for (i, acct) in mints.iter().enumerate() {
+            // Unchecked -> Mint
+            let mint_info = acct.clone();
+            let rent_info = self.rent.clone();
 
-        check_condition!(mint_info.key() == expected_key, InvalidMintSeed);
+            // get PDA + bump exactly how off-chain code does
+            let outcome_seed = [outcome_index_u8(i)?];
+            let (expected_key, mint_bump) = Pubkey::find_program_address(
+                &[OUTCOME_MINT_SEED, market_key.as_ref(), &outcome_seed],
+                program_id,
+            );
 
-        let mint_signer_seeds: &[&[&[u8]]] = &[&[
-            OUTCOME_MINT_SEED,
-            market_key.as_ref(),
-            &[i as u8],
-            &[mint_bump],
-        ]];
+            check_condition!(mint_info.key() == expected_key, InvalidMintSeed);
 
-        let mint_space = spl_token::state::Mint::LEN;
-        let rent_lamports = Rent::get()?.minimum_balance(mint_space);
+            let mint_signer_seeds: &[&[&[u8]]] = &[&[
+                OUTCOME_MINT_SEED,
+                market_key.as_ref(),
+                &outcome_seed,
+                &[mint_bump],
+            ]];
 
-        system_program::create_account(
-            CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info().clone(),
-                system_program::CreateAccount {
-                    from: ctx.accounts.admin.to_account_info(),
-                    to: mint_info.clone(),
-                },
-                mint_signer_seeds,
-            ),
-            rent_lamports,
-            mint_space as u64,
-            &ctx.accounts.token_program.key(),
-        )?;
+            let mint_space = spl_token::state::Mint::LEN;
+            let rent_lamports = Rent::get()?.minimum_balance(mint_space);
 
-        anchor_spl::token_interface::initialize_mint(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info().clone(),
-                anchor_spl::token_interface::InitializeMint {
-                    mint: mint_info.clone(),
-                    rent: rent_info.clone(),
-                },
-                market_signer_seeds,
-            ),
-            OUTCOME_MINT_DECIMALS,
-            &market_key,
-            None,
-        )?;
-    }
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    self.system_program.clone(),
+                    system_program::CreateAccount {
+                        from: self.payer.clone(),
+                        to: mint_info.clone(),
+                    },
+                    mint_signer_seeds,
+                ),
+                rent_lamports,
+                mint_space as u64,
+                self.token_program.key,
+            )?;
 
-    // Compute initial invariant
-    // product(reserves[0..num_outcomes]) = 0 as all reserves = 0
-    // But we compute it properly so later it is easy to modify the logic.
-    let n = num_outcomes as usize;
-    let mut prod = U256::from(1u64);
-    for i in 0..n {
-        let r = U256::from(market.reserves[i]);
-        prod = prod.checked_mul(r).ok_or(error!(ErrorCode::MathOverflow))?;
+            anchor_spl::token_interface::initialize_mint(
+                CpiContext::new_with_signer(
+                    self.token_program.clone(),
+                    anchor_spl::token_interface::InitializeMint {
+                        mint: mint_info.clone(),
+                        rent: rent_info.clone(),
+                    },
+                    market_signer_seeds,
+                ),
+                decimals,
+                market_key,
+                None,
+            )?;
+        }
+        Ok(())
     }
+}
 
-    market.set_invariant_u256(prod);
+/// Create the market-owned token account that holds a token-collateral market's reserves
+fn init_collateral_vault<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, InitMarket<'info>>,
+    collateral_mint: Pubkey,
+    market_key: &Pubkey,
+) -> Result<()> {
+    let (Some(mint), Some(vault)) = (
+        ctx.accounts.collateral_mint.as_ref(),
+        ctx.accounts.collateral_vault.as_ref(),
+    ) else {
+        return err!(ErrorCode::InvalidCollateral);
+    };
+    check_condition!(mint.key() == collateral_mint, InvalidCollateral);
 
-    Ok(())
+    let vault_bump = ctx
+        .bumps
+        .collateral_vault
+        .ok_or(error!(ErrorCode::InvalidCollateral))?;
+    let vault_signer_seeds: &[&[&[u8]]] =
+        &[&[COLLATERAL_VAULT_SEED, market_key.as_ref(), &[vault_bump]]];
+
+    let space = spl_token::state::Account::LEN;
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::CreateAccount {
+                from: ctx.accounts.admin.to_account_info(),
+                to: vault.to_account_info(),
+            },
+            vault_signer_seeds,
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &ctx.accounts.token_program.key(),
+    )?;
+
+    anchor_spl::token::initialize_account3(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::InitializeAccount3 {
+            account: vault.to_account_info(),
+            mint: mint.to_account_info(),
+            authority: ctx.accounts.market.to_account_info(),
+        },
+    ))
 }
@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::Token;
+
+use super::init_market::{write_market, OutcomeMintCreator};
+use crate::events::MarketInitialized;
+use crate::state::{Market, ProgramConfig};
+use crate::types::{MarketConfig, MarketSpec};
+use common::check_condition;
+use common::constants::{MAX_MARKETS_PER_BATCH, PROGRAM_CONFIG_SEED, VAULT_SEED};
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct InitMarketBatch<'info> {
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump = program_config.bump,
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    // Remaining accounts: for each spec in order, its market PDA, its market vault PDA and then
+    // one outcome mint PDA per outcome
+}
+
+pub fn init_market_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InitMarketBatch<'info>>,
+    specs: Vec<MarketSpec>,
+) -> Result<()> {
+    check_condition!(
+        !ctx.accounts.program_config.market_creation_paused,
+        MarketCreationPaused
+    );
+    check_condition!(
+        !specs.is_empty() && specs.len() <= MAX_MARKETS_PER_BATCH,
+        InvalidBatchLength
+    );
+    // Colliding labels would derive the same market PDA, so reject them before creating anything
+    for (i, spec) in specs.iter().enumerate() {
+        check_condition!(
+            specs[..i]
+                .iter()
+                .all(|other| other.label.as_bytes() != spec.label.as_bytes()),
+            DuplicateMarketLabel
+        );
+    }
+
+    let remaining = ctx.remaining_accounts;
+    let expected_accounts: usize = specs.iter().map(|s| 2 + s.num_outcomes as usize).sum();
+    check_condition!(remaining.len() == expected_accounts, InvalidMintCount);
+
+    // Batch markets take the default config, the admin can tune limits per market afterwards
+    let config = MarketConfig::default();
+    let nonce = config.nonce.to_le_bytes();
+    let clock = Clock::get()?;
+    let rent = Rent::get()?;
+    let mint_creator = OutcomeMintCreator {
+        payer: &ctx.accounts.admin.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+        token_program: &ctx.accounts.token_program.to_account_info(),
+        rent: &ctx.accounts.rent.to_account_info(),
+    };
+
+    let mut offset = 0;
+    for spec in &specs {
+        let accounts = &remaining[offset..offset + 2 + spec.num_outcomes as usize];
+        offset += accounts.len();
+        let (market_info, vault_info, mints) = (&accounts[0], &accounts[1], &accounts[2..]);
+
+        let (market_key, bump) = spec.label.market_pda(config.nonce, ctx.program_id);
+        check_condition!(market_info.key() == market_key, InvalidMarketSeed);
+        let (vault_key, vault_bump) =
+            Pubkey::find_program_address(&[VAULT_SEED, market_key.as_ref()], ctx.program_id);
+        check_condition!(vault_info.key() == vault_key, InvalidMarketSeed);
+
+        let bump_seed = [bump];
+        let market_signer_seeds: &[&[&[u8]]] =
+            &[&spec.label.market_signer_seeds(&nonce, &bump_seed)];
+        let vault_signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, market_key.as_ref(), &[vault_bump]]];
+
+        // The same accounts `init_market` creates with `init`, both owned by this program
+        for (info, space, signer_seeds) in [
+            (market_info, Market::SIZE, market_signer_seeds),
+            (vault_info, 0, vault_signer_seeds),
+        ] {
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::CreateAccount {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: info.clone(),
+                    },
+                    signer_seeds,
+                ),
+                rent.minimum_balance(space),
+                space as u64,
+                ctx.program_id,
+            )?;
+        }
+
+        let loader = AccountLoader::<Market>::try_from_unchecked(ctx.program_id, market_info)?;
+        let decimals = {
+            let mut market = loader.load_init()?;
+            write_market(
+                &mut market,
+                spec,
+                &config,
+                ctx.accounts.admin.key(),
+                bump,
+                vault_bump,
+                &clock,
+            )?;
+            market.decimals
+        };
+
+        mint_creator.create(
+            mints,
+            &market_key,
+            decimals,
+            market_signer_seeds,
+            ctx.program_id,
+        )?;
+
+        // Writes the discriminator, as Anchor does for accounts created with `init`
+        loader.exit(ctx.program_id)?;
+
+        emit!(MarketInitialized {
+            market: market_key,
+            admin: ctx.accounts.admin.key(),
+            num_outcomes: spec.num_outcomes,
+            scale: spec.scale,
+            resolve_at: spec.resolve_at,
+            label: spec.label,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::program::Gamma;
+use crate::state::ProgramConfig;
+use common::constants::PROGRAM_CONFIG_SEED;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct InitProgramConfig<'info> {
+    /// Becomes the config admin. The config can only be created once, so only the program's
+    /// upgrade authority may create it.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::Unauthorized
+    )]
+    pub program: Program<'info, Gamma>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ProgramConfig::SIZE,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump,
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_program_config(ctx: Context<InitProgramConfig>) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    program_config.admin = ctx.accounts.admin.key();
+    program_config.market_creation_paused = false;
+    program_config.bump = ctx.bumps.program_config;
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Market;
+use common::check_condition;
+use common::errors::ErrorCode;
+
+/// Permissionless: anyone may record that a market has expired
+#[derive(Accounts)]
+pub struct MarkAwaitingResolution<'info> {
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+}
+
+pub fn mark_awaiting_resolution(ctx: Context<MarkAwaitingResolution>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let mut market = ctx.accounts.market.load_mut()?;
+    check_condition!(market.mark_awaiting_resolution(now)?, MarketNotExpired);
+    msg!("market awaiting resolution: {}", ctx.accounts.market.key());
+
+    Ok(())
+}
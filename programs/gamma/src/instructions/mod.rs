@@ -1,7 +1,57 @@
+pub mod batch_buy;
+pub mod batch_claim;
 pub mod buy;
+pub mod claim_fees;
+pub mod claim_to_escrow;
+pub mod close_market;
+pub mod donate_fees;
+pub mod get_exposure;
+pub mod get_probabilities;
+pub mod get_snapshot;
+pub mod get_vault_health;
 pub mod init_market;
+pub mod init_market_batch;
+pub mod init_program_config;
+pub mod mark_awaiting_resolution;
+pub mod pause_market;
+pub mod redeem;
+pub mod release_escrow;
+pub mod resolve_market;
+pub mod seed_market;
 pub mod sell;
+pub mod set_limits;
+pub mod set_market_creation_paused;
+pub mod swap_outcome;
+pub mod sweep_surplus;
+pub mod transfer_admin;
+pub mod update_resolve_at;
+pub mod verify_and_repair;
 
+pub use batch_buy::*;
+pub use batch_claim::*;
 pub use buy::*;
+pub use claim_fees::*;
+pub use claim_to_escrow::*;
+pub use close_market::*;
+pub use donate_fees::*;
+pub use get_exposure::*;
+pub use get_probabilities::*;
+pub use get_snapshot::*;
+pub use get_vault_health::*;
 pub use init_market::*;
+pub use init_market_batch::*;
+pub use init_program_config::*;
+pub use mark_awaiting_resolution::*;
+pub use pause_market::*;
+pub use redeem::*;
+pub use release_escrow::*;
+pub use resolve_market::*;
+pub use seed_market::*;
 pub use sell::*;
+pub use set_limits::*;
+pub use set_market_creation_paused::*;
+pub use swap_outcome::*;
+pub use sweep_surplus::*;
+pub use transfer_admin::*;
+pub use update_resolve_at::*;
+pub use verify_and_repair::*;
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Market;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct PauseMarket<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+}
+
+pub fn pause_market(ctx: Context<PauseMarket>) -> Result<()> {
+    ctx.accounts.market.load_mut()?.paused = 1;
+    msg!("market paused");
+    Ok(())
+}
+
+pub fn unpause_market(ctx: Context<PauseMarket>) -> Result<()> {
+    ctx.accounts.market.load_mut()?.paused = 0;
+    msg!("market unpaused");
+    Ok(())
+}
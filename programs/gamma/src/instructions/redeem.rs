@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+
+use crate::collateral::CollateralVault;
+use crate::state::Market;
+use common::check_condition;
+use common::constants::seeds::*;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(outcome_index: u8, amount: u64)]
+pub struct Redeem<'info> {
+    /// Holder burning outcome tokens and receiving SOL, or the collateral token
+    #[account(
+        mut,
+        constraint = user_outcome_token_account.owner == user.key()
+    )]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, lamports are moved out by the program which owns it
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    /// Outcome SPL token being redeemed. Authority must be the market PDA.
+    #[account(
+        mut,
+        mint::authority = market,
+        seeds = [OUTCOME_MINT_SEED, market.key().as_ref(), &[outcome_index]],
+        bump,
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user,
+        associated_token::token_program = outcome_mint.to_account_info().owner,
+    )]
+    pub user_outcome_token_account: Account<'info, TokenAccount>,
+
+    /// Token program for burn CPI
+    pub token_program: Program<'info, Token>,
+
+    /// System program for lamport transfer
+    pub system_program: Program<'info, System>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+
+    /// User's collateral token account receiving the payout, only for token-collateral markets
+    #[account(mut)]
+    pub user_collateral_account: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn redeem(ctx: Context<Redeem>, outcome_index: u8, amount: u64) -> Result<()> {
+    // The payout goes to `user` with add_lamports, so it must never be the market's own accounts
+    let user_key = ctx.accounts.user.key();
+    check_condition!(
+        user_key != ctx.accounts.market.key() && user_key != ctx.accounts.market_vault.key(),
+        InvalidRecipient
+    );
+    check_condition!(
+        ctx.accounts.user_outcome_token_account.amount >= amount,
+        InsufficientFunds
+    );
+
+    let mut market = ctx.accounts.market.load_mut()?;
+    check_condition!(!market.vests_claims(), ClaimMustBeEscrowed);
+    check_condition!(
+        ctx.accounts.outcome_mint.decimals == market.decimals,
+        DecimalsMismatch
+    );
+
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+
+    // Settle the market's books before the burn so a rejected claim keeps the user's tokens
+    let net_payout = market.redeem(
+        outcome_index as usize,
+        amount,
+        vault.balance(),
+        vault.rent_min()?,
+    )?;
+
+    let label = market.label;
+    let nonce = market.nonce.to_le_bytes();
+    let bump = [market.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
+
+    // The market PDA signs token payouts, so it can't stay borrowed across the CPI
+    drop(market);
+
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.outcome_mint.to_account_info(),
+                from: ctx.accounts.user_outcome_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    vault.withdraw(
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.user_collateral_account.as_ref(),
+        ctx.accounts.market.to_account_info(),
+        signer_seeds,
+        Some(ctx.accounts.token_program.to_account_info()),
+        net_payout,
+    )?;
+
+    Ok(())
+}
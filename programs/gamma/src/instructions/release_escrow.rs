@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::state::ClaimEscrow;
+use common::check_condition;
+use common::constants::seeds::*;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ReleaseEscrow<'info> {
+    /// Holder the escrow was claimed for, receives the winnings and the escrow's rent
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = user,
+        close = user,
+        seeds = [ESCROW_SEED, escrow.market.as_ref(), user.key().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, ClaimEscrow>,
+}
+
+pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    check_condition!(now >= ctx.accounts.escrow.release_at, EscrowLocked);
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Market;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ResolveMarket<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+}
+
+pub fn resolve_market(
+    ctx: Context<ResolveMarket>,
+    winning_outcome: u8,
+    resolution_proof: Option<[u8; 32]>,
+    allow_empty: bool,
+) -> Result<()> {
+    let mut market = ctx.accounts.market.load_mut()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    market.resolve(winning_outcome as usize, now, allow_empty)?;
+    // Recorded so anyone can audit what the resolution was based on off-chain
+    market.resolution_proof = resolution_proof.unwrap_or_default();
+    msg!("resolved to outcome: {}", winning_outcome);
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, MintTo, Token, TokenAccount};
+
+use crate::collateral::CollateralVault;
+use crate::state::Market;
+use crate::types::outcome_index_u8;
+use common::check_condition;
+use common::constants::{COLLATERAL_VAULT_SEED, OUTCOME_MINT_SEED, VAULT_SEED};
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SeedMarket<'info> {
+    /// Admin depositing the seed and receiving every outcome's tokens
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, receives the seed lamports for SOL markets
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+
+    /// Admin's collateral token account paying the seed, only for token-collateral markets
+    #[account(mut)]
+    pub admin_collateral_account: Option<Account<'info, TokenAccount>>,
+    // Remaining accounts: for each outcome in order, its outcome mint followed by the admin's
+    // token account for it
+}
+
+pub fn seed_market<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SeedMarket<'info>>,
+    amount_per_outcome: u64,
+) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let mut market = ctx.accounts.market.load_mut()?;
+    let num_outcomes = market.num_outcomes as usize;
+
+    let remaining = ctx.remaining_accounts;
+    check_condition!(remaining.len() == 2 * num_outcomes, InvalidMintCount);
+
+    let now = Clock::get()?.unix_timestamp;
+    market.accumulate_prices(now)?;
+    let tokens_per_outcome = market.seed(amount_per_outcome)?;
+    // Every seeded reserve is backed, num_outcomes × amount_per_outcome in total
+    let deposit = tokens_per_outcome;
+
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+    vault.deposit(
+        ctx.accounts.admin.to_account_info(),
+        ctx.accounts.admin_collateral_account.as_ref(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        deposit,
+    )?;
+
+    let label = market.label;
+    let nonce = market.nonce.to_le_bytes();
+    let bump = [market.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
+
+    // The market PDA signs the mints, so it can't stay borrowed across the CPIs
+    drop(market);
+
+    for (i, pair) in remaining.chunks(2).enumerate() {
+        let (mint_info, token_account_info) = (&pair[0], &pair[1]);
+
+        let (expected_mint, _) = Pubkey::find_program_address(
+            &[
+                OUTCOME_MINT_SEED,
+                market_key.as_ref(),
+                &[outcome_index_u8(i)?],
+            ],
+            ctx.program_id,
+        );
+        check_condition!(mint_info.key() == expected_mint, InvalidMintSeed);
+
+        let token_account = Account::<TokenAccount>::try_from(token_account_info)?;
+        check_condition!(
+            token_account.mint == expected_mint && token_account.owner == ctx.accounts.admin.key(),
+            InvalidRecipient
+        );
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: mint_info.clone(),
+                    to: token_account_info.clone(),
+                    authority: ctx.accounts.market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            tokens_per_outcome,
+        )?;
+    }
+    msg!("seeded market: {} per outcome", amount_per_outcome);
+
+    Ok(())
+}
@@ -1,15 +1,17 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
 
+use crate::collateral::CollateralVault;
+use crate::events::SellExecuted;
 use crate::state::Market;
 use common::check_condition;
-use common::constants::{common::*, seeds::*};
+use common::constants::seeds::*;
 use common::errors::ErrorCode;
 
 #[derive(Accounts)]
-#[instruction(outcome_index: u8, burn_amount: u64)]
+#[instruction(outcome_index: u8, burn_amount: u64, min_payout: u64)]
 pub struct Sell<'info> {
-    /// user who holds the outcome tokens and will receive SOL back
+    /// user who holds the outcome tokens and will receive SOL, or the collateral token, back
     #[account(
         mut,
         constraint = user_outcome_token_account.owner == user.key()
@@ -30,7 +32,6 @@ pub struct Sell<'info> {
     /// Outcome SPL token to mint to user. Authority must be the market PDA.
     #[account(
         mut,
-        mint::decimals = OUTCOME_MINT_DECIMALS,
         mint::authority = market,
         seeds = [OUTCOME_MINT_SEED, market.key().as_ref(), &[outcome_index]],
         bump,
@@ -50,25 +51,61 @@ pub struct Sell<'info> {
 
     /// System program for lamport transfer
     pub system_program: Program<'info, System>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+
+    /// User's collateral token account receiving the payout, only for token-collateral markets
+    #[account(mut)]
+    pub user_collateral_account: Option<Account<'info, TokenAccount>>,
 }
 
-pub fn sell(ctx: Context<Sell>, outcome_index: u8, burn_amount: u64) -> Result<()> {
+pub fn sell(
+    ctx: Context<Sell>,
+    outcome_index: u8,
+    burn_amount: u64,
+    min_payout: u64,
+) -> Result<u64> {
+    // The payout goes to `user` with add_lamports, so it must never be the market's own accounts
+    let user_key = ctx.accounts.user.key();
+    check_condition!(
+        user_key != ctx.accounts.market.key() && user_key != ctx.accounts.market_vault.key(),
+        InvalidRecipient
+    );
+
     let mut market = ctx.accounts.market.load_mut()?;
     let idx = outcome_index as usize;
     let n = market.num_outcomes as usize;
 
     let now = Clock::get()?.unix_timestamp;
-    check_condition!(now < market.resolve_at, MarketExpired);
+    market.check_not_resolved()?;
+    market.check_not_paused()?;
+    market.mark_awaiting_resolution(now)?;
+    // Sells may run past resolve_at for the configured grace so holders aren't stuck waiting
+    check_condition!(now < market.sell_deadline()?, MarketExpired);
     check_condition!(burn_amount > 0, BurnIsZero);
     check_condition!(n > 0, OutcomeBelowZero);
     check_condition!(idx < n, InvalidOutcomeIndex);
+    check_condition!(
+        ctx.accounts.outcome_mint.decimals == market.decimals,
+        DecimalsMismatch
+    );
     check_condition!(
         ctx.accounts.user_outcome_token_account.amount >= burn_amount,
         InsufficientFunds
     );
 
-    // Ensure vault has enough lamports
-    let vault_lamports = ctx.accounts.market_vault.to_account_info().lamports();
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+    let vault_lamports = vault.balance();
 
     // Ensure burn_amount <= current supply
     let supply_before = market.supplies[idx];
@@ -86,6 +123,24 @@ pub fn sell(ctx: Context<Sell>, outcome_index: u8, burn_amount: u64) -> Result<(
     //     return Err(error!(ErrorCode::BurnIsMoreThanSupply));
     // }
 
+    // compute payout then update market reserves, supplies, and invariant.
+    // This runs before the burn so a sell that would refund nothing fails without burning.
+    market.accumulate_prices(now)?;
+    let fees_before = market.undistributed_fees;
+    let net_payout_u64 = market.sell_outcome(idx, burn_amount, vault_lamports, now)?;
+    let fee_u64 = market.undistributed_fees - fees_before;
+    // Protects the user from fees and reserve changes between their quote and execution
+    check_condition!(net_payout_u64 >= min_payout, SlippageExceeded);
+    let outcome_price = market.outcome_price(idx)?;
+
+    let label = market.label;
+    let nonce = market.nonce.to_le_bytes();
+    let bump = [market.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
+
+    // The market PDA signs token payouts, so it can't stay borrowed across the CPI
+    drop(market);
+
     // burn user's outcome tokens
     token::burn(
         CpiContext::new(
@@ -99,14 +154,27 @@ pub fn sell(ctx: Context<Sell>, outcome_index: u8, burn_amount: u64) -> Result<(
         burn_amount,
     )?;
 
-    // compute payout then update market reserves, supplies, and invariant
-    let net_payout_u64 = market.sell_outcome(idx, burn_amount, vault_lamports)?;
+    vault.withdraw(
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.user_collateral_account.as_ref(),
+        ctx.accounts.market.to_account_info(),
+        signer_seeds,
+        Some(ctx.accounts.token_program.to_account_info()),
+        net_payout_u64,
+    )?;
+
+    emit!(SellExecuted {
+        market: ctx.accounts.market.key(),
+        user: user_key,
+        outcome_index,
+        burn_amount,
+        net_payout: net_payout_u64,
+        fee: fee_u64,
+    });
 
-    // market_vault PDA signs for lamport transfer from self
-    ctx.accounts.market_vault.sub_lamports(net_payout_u64)?;
-    ctx.accounts.user.add_lamports(net_payout_u64)?;
+    msg!("outcome_price: {} {}", outcome_index, outcome_price);
 
     // fee remains in vault; if you want to route fee to admin, implement additional transfer
 
-    Ok(())
+    Ok(outcome_price)
 }
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::events::LimitsUpdated;
+use crate::state::Market;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SetLimits<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+}
+
+pub fn set_limits(
+    ctx: Context<SetLimits>,
+    min_deposit: u64,
+    max_reserve: u64,
+    max_total_reserves: u64,
+    max_supply: u64,
+) -> Result<()> {
+    let mut market = ctx.accounts.market.load_mut()?;
+    market.set_limits(min_deposit, max_reserve, max_total_reserves, max_supply)?;
+
+    emit!(LimitsUpdated {
+        market: ctx.accounts.market.key(),
+        min_deposit,
+        max_reserve,
+        max_total_reserves,
+        max_supply,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::state::ProgramConfig;
+use common::constants::PROGRAM_CONFIG_SEED;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SetMarketCreationPaused<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump = program_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+pub fn set_market_creation_paused(
+    ctx: Context<SetMarketCreationPaused>,
+    paused: bool,
+) -> Result<()> {
+    ctx.accounts.program_config.market_creation_paused = paused;
+    msg!("market creation paused: {}", paused);
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount};
+
+use crate::collateral::CollateralVault;
+use crate::events::{BuyExecuted, SellExecuted};
+use crate::state::Market;
+use common::check_condition;
+use common::constants::seeds::*;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(from_index: u8, to_index: u8)]
+pub struct SwapOutcome<'info> {
+    /// Holder moving their position between outcomes
+    #[account(
+        mut,
+        constraint = user_from_token_account.owner == user.key()
+    )]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, the refund stays in the vault to pay for the buy
+    #[account(
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    /// Outcome SPL token being sold. Authority must be the market PDA.
+    #[account(
+        mut,
+        mint::authority = market,
+        seeds = [OUTCOME_MINT_SEED, market.key().as_ref(), &[from_index]],
+        bump,
+    )]
+    pub from_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = from_mint,
+        associated_token::authority = user,
+        associated_token::token_program = from_mint.to_account_info().owner,
+    )]
+    pub user_from_token_account: Account<'info, TokenAccount>,
+
+    /// Outcome SPL token being bought. Authority must be the market PDA.
+    #[account(
+        mut,
+        mint::authority = market,
+        seeds = [OUTCOME_MINT_SEED, market.key().as_ref(), &[to_index]],
+        bump,
+    )]
+    pub to_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = to_mint,
+        associated_token::authority = user,
+        associated_token::token_program = to_mint.to_account_info().owner,
+    )]
+    pub user_to_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn swap_outcome(
+    ctx: Context<SwapOutcome>,
+    from_index: u8,
+    to_index: u8,
+    burn_amount: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    let mut market = ctx.accounts.market.load_mut()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    // The swap buys, so it closes with buys at resolve_at rather than the sell deadline
+    market.check_buy_allowed(
+        to_index as usize,
+        now,
+        ctx.accounts.user.key() == market.admin,
+    )?;
+    check_condition!(
+        ctx.accounts.from_mint.decimals == market.decimals
+            && ctx.accounts.to_mint.decimals == market.decimals,
+        DecimalsMismatch
+    );
+    check_condition!(
+        ctx.accounts.user_from_token_account.amount >= burn_amount,
+        InsufficientFunds
+    );
+
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+    let vault_lamports = vault.balance();
+
+    // Same circuit breaker as buy, the swap deposits its refund into the other outcome
+    if market.enforce_solvency != 0 {
+        market.assert_solvent(vault_lamports, vault.rent_min()?)?;
+    }
+
+    market.accumulate_prices(now)?;
+    let fill = market.swap_outcome(
+        from_index as usize,
+        to_index as usize,
+        burn_amount,
+        min_amount_out,
+        vault_lamports,
+        now,
+    )?;
+    let outcome_price = market.outcome_price(to_index as usize)?;
+
+    let label = market.label;
+    let nonce = market.nonce.to_le_bytes();
+    let bump = [market.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&label.market_signer_seeds(&nonce, &bump)];
+
+    drop(market);
+
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.from_mint.to_account_info(),
+                from: ctx.accounts.user_from_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        burn_amount,
+    )?;
+
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.to_mint.to_account_info(),
+                to: ctx.accounts.user_to_token_account.to_account_info(),
+                authority: ctx.accounts.market.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        fill.amount_out,
+    )?;
+
+    let market_key = ctx.accounts.market.key();
+    let user_key = ctx.accounts.user.key();
+    emit!(SellExecuted {
+        market: market_key,
+        user: user_key,
+        outcome_index: from_index,
+        burn_amount,
+        net_payout: fill.net_refund,
+        fee: fill.sell_fee,
+    });
+    emit!(BuyExecuted {
+        market: market_key,
+        user: user_key,
+        outcome_index: to_index,
+        amount_in: fill.net_refund,
+        amount_out: fill.amount_out,
+        outcome_price,
+    });
+    msg!("outcome_price: {} {}", to_index, outcome_price);
+
+    Ok(())
+}
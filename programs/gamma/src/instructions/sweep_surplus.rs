@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::collateral::CollateralVault;
+use crate::state::Market;
+use common::constants::{COLLATERAL_VAULT_SEED, VAULT_SEED};
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SweepSurplus<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, only the lamport balance is read
+    #[account(
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+}
+
+pub fn sweep_surplus(ctx: Context<SweepSurplus>) -> Result<()> {
+    let mut market = ctx.accounts.market.load_mut()?;
+
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+
+    // The surplus already sits in the vault, so only the accounting moves
+    let swept = market.sweep_surplus(vault.balance(), vault.rent_min()?)?;
+    msg!("swept surplus: {}", swept);
+
+    Ok(())
+}
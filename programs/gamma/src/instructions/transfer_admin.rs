@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Market;
+use common::check_condition;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+}
+
+/// Propose `new_admin`, who must accept before anything changes. The default key cancels a
+/// pending transfer.
+pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+    let mut market = ctx.accounts.market.load_mut()?;
+    market.pending_admin = new_admin;
+    msg!("pending admin: {}", new_admin);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(mut)]
+    pub market: AccountLoader<'info, Market>,
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let mut market = ctx.accounts.market.load_mut()?;
+    check_condition!(
+        market.pending_admin != Pubkey::default()
+            && market.pending_admin == ctx.accounts.new_admin.key(),
+        NotPendingAdmin
+    );
+    market.admin = market.pending_admin;
+    market.pending_admin = Pubkey::default();
+    msg!("admin: {}", market.admin);
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::events::ResolveAtUpdated;
+use crate::state::Market;
+use common::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct UpdateResolveAt<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+}
+
+pub fn update_resolve_at(ctx: Context<UpdateResolveAt>, resolve_at: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let mut market = ctx.accounts.market.load_mut()?;
+    market.update_resolve_at(resolve_at, now)?;
+
+    emit!(ResolveAtUpdated {
+        market: ctx.accounts.market.key(),
+        resolve_at,
+    });
+
+    Ok(())
+}
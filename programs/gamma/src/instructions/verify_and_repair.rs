@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::TokenAccount;
+use common::check_condition;
+use common::constants::{COLLATERAL_VAULT_SEED, OUTCOME_MINT_SEED, VAULT_SEED};
+use common::errors::ErrorCode;
+
+use crate::collateral::CollateralVault;
+use crate::state::Market;
+use crate::types::{outcome_index_u8, ReconcileReport};
+
+#[derive(Accounts)]
+pub struct VerifyAndRepair<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub market: AccountLoader<'info, Market>,
+
+    /// CHECK: PDA check, only the lamport balance is read
+    #[account(
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub market_vault: UncheckedAccount<'info>,
+
+    /// The market's collateral token account, only for token-collateral markets
+    #[account(
+        seeds = [COLLATERAL_VAULT_SEED, market.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: Option<Account<'info, TokenAccount>>,
+    // remaining_accounts: every outcome mint, in outcome index order
+}
+
+/// Cross-check the market's recorded supplies against the outcome mints and its reserves against
+/// the vault. With `repair`, recorded supplies are overwritten from the mints.
+///
+/// Reserve discrepancies are only reported: there is no single outcome to charge a vault
+/// shortfall to, so fixing them is left to the admin.
+pub fn verify_and_repair(ctx: Context<VerifyAndRepair>, repair: bool) -> Result<ReconcileReport> {
+    let market_key = ctx.accounts.market.key();
+    let mut market = ctx.accounts.market.load_mut()?;
+    let num_outcomes = market.num_outcomes as usize;
+
+    let remaining = ctx.remaining_accounts;
+    check_condition!(remaining.len() == num_outcomes, InvalidMintCount);
+
+    let mut mint_supplies = Vec::with_capacity(num_outcomes);
+    for (i, acct) in remaining.iter().enumerate() {
+        let (expected_key, _) = Pubkey::find_program_address(
+            &[
+                OUTCOME_MINT_SEED,
+                market_key.as_ref(),
+                &[outcome_index_u8(i)?],
+            ],
+            ctx.program_id,
+        );
+        check_condition!(acct.key() == expected_key, InvalidMintSeed);
+
+        let mint = spl_token::state::Mint::unpack(&acct.try_borrow_data()?)?;
+        mint_supplies.push(mint.supply);
+    }
+
+    let supply_mismatches = market.reconcile_supplies(&mint_supplies, repair)?;
+
+    let vault = CollateralVault::resolve(
+        &market,
+        ctx.accounts.market_vault.to_account_info(),
+        ctx.accounts.collateral_vault.as_ref(),
+    )?;
+    let vault_lamports = vault.balance();
+    let rent_min = vault.rent_min()?;
+    let report = ReconcileReport {
+        supply_mismatches,
+        reserves_total: market.total_reserves_u128()?.min(u64::MAX as u128) as u64,
+        vault_backing: market.vault_backing(vault_lamports, rent_min),
+        repaired: repair,
+    };
+
+    msg!(
+        "supply mismatches: {:?}, reserves: {}, vault backing: {}",
+        report.supply_mismatches,
+        report.reserves_total,
+        report.vault_backing
+    );
+
+    Ok(report)
+}
@@ -8,6 +8,10 @@ use anchor_lang::prelude::*;
 use instructions::*;
 use types::*;
 
+pub mod collateral;
+#[cfg(feature = "dto")]
+pub mod dto;
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod types;
@@ -18,6 +22,20 @@ declare_id!("JDP9AsSqpzeea8yqscvMHU7gkvC7QR16UF35hf74tAFG");
 pub mod gamma {
     use super::*;
 
+    /// Create the program-wide config with the signer as its admin. Can only run once, and only
+    /// the program's upgrade authority may run it.
+    pub fn init_program_config(ctx: Context<InitProgramConfig>) -> Result<()> {
+        instructions::init_program_config(ctx)
+    }
+
+    /// Pause or resume the creation of new markets without affecting existing ones
+    pub fn set_market_creation_paused(
+        ctx: Context<SetMarketCreationPaused>,
+        paused: bool,
+    ) -> Result<()> {
+        instructions::set_market_creation_paused(ctx, paused)
+    }
+
     /// Create a new market with N outcomes
     pub fn init_market<'info>(
         ctx: Context<'_, '_, 'info, 'info, InitMarket<'info>>,
@@ -25,17 +43,234 @@ pub mod gamma {
         scale: u64,
         resolve_at: i64,
         label: FixedSizeString,
+        config: MarketConfig,
+    ) -> Result<()> {
+        instructions::init_market(ctx, num_outcomes, scale, resolve_at, label, config)
+    }
+
+    /// Create up to `MAX_MARKETS_PER_BATCH` markets with the default config in one instruction,
+    /// for example every game of a tournament. Labels must be unique within the batch.
+    /// Remaining accounts are each spec's market, market vault and outcome mints, in order.
+    pub fn init_market_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitMarketBatch<'info>>,
+        specs: Vec<MarketSpec>,
+    ) -> Result<()> {
+        instructions::init_market_batch(ctx, specs)
+    }
+
+    /// Seed a market with no reserves by depositing `amount_per_outcome` into every outcome,
+    /// so each opens at a price of `1 / num_outcomes`. The admin receives every outcome's supply.
+    /// Remaining accounts are each outcome's mint followed by the admin's token account for it.
+    pub fn seed_market<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SeedMarket<'info>>,
+        amount_per_outcome: u64,
+    ) -> Result<()> {
+        instructions::seed_market(ctx, amount_per_outcome)
+    }
+
+    /// Buy into a single outcome with SOL, or the market's collateral token, and receive
+    /// liquid-stake tokens for that position.
+    /// Fails if fewer than `min_amount_out` tokens would be minted.
+    ///
+    /// `min_amount_out` extends the instruction data after `amount_in`, so clients built
+    /// before it was added must be updated to send it; 0 accepts any amount.
+    ///
+    /// The buy that opens an empty market also mints the buyer every other outcome's opening
+    /// supply, see [`Market::buy_outcome`](crate::state::Market::buy_outcome). It takes each
+    /// other outcome's mint followed by the user's token account for it as remaining accounts.
+    ///
+    /// Returns the outcome's price after the buy, also logged as `outcome_price: <index> <price>`.
+    pub fn buy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Buy<'info>>,
+        outcome_index: u8,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<u64> {
+        instructions::buy(ctx, outcome_index, amount_in, min_amount_out)
+    }
+
+    /// Buy into several outcomes in one instruction, one [`BuyLeg`] per outcome, each leg
+    /// pricing against the state the previous one left. At most `MAX_BATCH_BUYS` legs.
+    /// The market must already be open.
+    /// Remaining accounts are each leg's outcome mint followed by the user's token account for it.
+    pub fn batch_buy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchBuy<'info>>,
+        legs: Vec<BuyLeg>,
+    ) -> Result<()> {
+        instructions::batch_buy(ctx, legs)
+    }
+
+    /// Sell out of a single outcome by burning the liquid-stake token for that position and
+    /// receiving SOL, or the market's collateral token, in return.
+    /// Fails if the payout after fees would be less than `min_payout`.
+    ///
+    /// `min_payout` extends the instruction data after `burn_amount`, so clients built before it
+    /// was added must be updated to send it; 0 accepts any payout.
+    ///
+    /// Returns the outcome's price after the sell, also logged as `outcome_price: <index> <price>`.
+    pub fn sell(
+        ctx: Context<Sell>,
+        outcome_index: u8,
+        burn_amount: u64,
+        min_payout: u64,
+    ) -> Result<u64> {
+        instructions::sell(ctx, outcome_index, burn_amount, min_payout)
+    }
+
+    /// Burn outcome tokens of a resolved market for their share of the pot, net of the
+    /// settlement fee
+    pub fn redeem(ctx: Context<Redeem>, outcome_index: u8, amount: u64) -> Result<()> {
+        instructions::redeem(ctx, outcome_index, amount)
+    }
+
+    /// Burn winning tokens of a resolved SOL market whose winnings vest, moving their payout into
+    /// the user's escrow until the market's `claim_vesting_secs` after `resolve_at` have passed
+    pub fn claim_to_escrow(
+        ctx: Context<ClaimToEscrow>,
+        outcome_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::claim_to_escrow(ctx, outcome_index, amount)
+    }
+
+    /// Pay out and close the user's escrow once its vesting cliff has passed
+    pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+        instructions::release_escrow(ctx)
+    }
+
+    /// Claim from several resolved SOL markets in one instruction, redeeming the user's whole
+    /// winning balance in each. At most `MAX_BATCH_CLAIMS` markets.
+    /// Remaining accounts are, per market, the market, its vault, its winning outcome mint and
+    /// the user's token account for it.
+    pub fn batch_claim<'info>(ctx: Context<'_, '_, 'info, 'info, BatchClaim<'info>>) -> Result<()> {
+        instructions::batch_claim(ctx)
+    }
+
+    /// Move a position between outcomes in one step: burn `from_index` tokens and buy
+    /// `to_index` with the refund, failing if fewer than `min_amount_out` tokens are minted.
+    /// The buy side is gated like `buy`, including the solvency circuit breaker.
+    pub fn swap_outcome(
+        ctx: Context<SwapOutcome>,
+        from_index: u8,
+        to_index: u8,
+        burn_amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::swap_outcome(ctx, from_index, to_index, burn_amount, min_amount_out)
+    }
+
+    /// Propose a new market admin, who takes over once they call `accept_admin`.
+    /// Proposing the default key cancels a pending transfer.
+    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::transfer_admin(ctx, new_admin)
+    }
+
+    /// Complete an admin transfer, signed by the pending admin
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    /// Halt buys, sells and swaps on a market in an emergency, until `unpause_market`
+    pub fn pause_market(ctx: Context<PauseMarket>) -> Result<()> {
+        instructions::pause_market(ctx)
+    }
+
+    /// Resume trading on a paused market
+    pub fn unpause_market(ctx: Context<PauseMarket>) -> Result<()> {
+        instructions::unpause_market(ctx)
+    }
+
+    /// Update a live market's buy limits: the minimum deposit and the caps on a single reserve,
+    /// total reserves and a single outcome's supply. A cap of 0 removes it.
+    pub fn set_limits(
+        ctx: Context<SetLimits>,
+        min_deposit: u64,
+        max_reserve: u64,
+        max_total_reserves: u64,
+        max_supply: u64,
     ) -> Result<()> {
-        instructions::init_market(ctx, num_outcomes, scale, resolve_at, label)
+        instructions::set_limits(
+            ctx,
+            min_deposit,
+            max_reserve,
+            max_total_reserves,
+            max_supply,
+        )
+    }
+
+    /// Move an unresolved market's `resolve_at` to a later or earlier time that is still in the
+    /// future, e.g. when the event it tracks is postponed
+    pub fn update_resolve_at(ctx: Context<UpdateResolveAt>, resolve_at: i64) -> Result<()> {
+        instructions::update_resolve_at(ctx, resolve_at)
+    }
+
+    /// Record that an unresolved market has passed `resolve_at` and is awaiting resolution.
+    /// Permissionless; sells after `resolve_at` record it as well.
+    pub fn mark_awaiting_resolution(ctx: Context<MarkAwaitingResolution>) -> Result<()> {
+        instructions::mark_awaiting_resolution(ctx)
+    }
+
+    /// Resolve the market to its winning outcome once `resolve_at` has passed, closing trading.
+    /// An optional proof hash of the evidence behind the resolution is stored for auditing.
+    /// An outcome nobody holds is rejected unless `allow_empty` is set, in which case every
+    /// holder is refunded as if the market were cancelled.
+    pub fn resolve_market(
+        ctx: Context<ResolveMarket>,
+        winning_outcome: u8,
+        resolution_proof: Option<[u8; 32]>,
+        allow_empty: bool,
+    ) -> Result<()> {
+        instructions::resolve_market(ctx, winning_outcome, resolution_proof, allow_empty)
+    }
+
+    /// Pay accrued trading and settlement fees from the market vault to the market's fee recipient
+    pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+        instructions::claim_fees(ctx)
+    }
+
+    /// Return accrued trading fees to holders by moving them into the reserves
+    pub fn donate_fees(ctx: Context<DonateFees>) -> Result<()> {
+        instructions::donate_fees(ctx)
+    }
+
+    /// Book collateral sent straight to the vault, beyond what the market owes and its
+    /// backstop funding, as fees the fee recipient can claim
+    pub fn sweep_surplus(ctx: Context<SweepSurplus>) -> Result<()> {
+        instructions::sweep_surplus(ctx)
+    }
+
+    /// Close a resolved market once every claim is redeemed and its fees are claimed, returning
+    /// the rent and anything left in its vaults to the admin
+    pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+        instructions::close_market(ctx)
+    }
+
+    /// Get the lamports the vault would owe holders if each active outcome won
+    pub fn get_exposure(ctx: Context<GetExposure>) -> Result<Vec<u64>> {
+        instructions::get_exposure(ctx)
+    }
+
+    /// Get the implied probability of each active outcome in basis points, summing to 10_000
+    pub fn get_probabilities(ctx: Context<GetProbabilities>) -> Result<Vec<u16>> {
+        instructions::get_probabilities(ctx)
+    }
+
+    /// Get a snapshot of the market's reserves, supplies and fees
+    pub fn get_snapshot(ctx: Context<GetSnapshot>) -> Result<MarketSnapshot> {
+        instructions::get_snapshot(ctx)
     }
 
-    /// Buy into a single outcome with SOL and receive liquid-stake tokens for that position
-    pub fn buy(ctx: Context<Buy>, outcome_index: u8, amount_in: u64) -> Result<()> {
-        instructions::buy(ctx, outcome_index, amount_in)
+    /// Get the vault balance and the market's liabilities, and whether the vault covers them
+    pub fn get_vault_health(ctx: Context<GetVaultHealth>) -> Result<VaultHealth> {
+        instructions::get_vault_health(ctx)
     }
 
-    /// Sell out of a single outcome by burning the liquid-stake token for that position and receiving SOL in return
-    pub fn sell(ctx: Context<Sell>, outcome_index: u8, burn_amount: u64) -> Result<()> {
-        instructions::sell(ctx, outcome_index, burn_amount)
+    /// Cross-check recorded supplies and reserves against the mints and vault, optionally
+    /// repairing supplies. Outcome mints are passed as remaining accounts.
+    pub fn verify_and_repair(
+        ctx: Context<VerifyAndRepair>,
+        repair: bool,
+    ) -> Result<ReconcileReport> {
+        instructions::verify_and_repair(ctx, repair)
     }
 }
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// Winnings a user claimed from a vesting market, held as lamports on this PDA until
+/// `release_at`. One per market and user, later claims add to it.
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimEscrow {
+    /// Market the winnings were claimed from
+    pub market: Pubkey,
+
+    /// Holder the winnings are released to
+    pub user: Pubkey,
+
+    /// Lamports claimed into the escrow, on top of its rent-exempt minimum
+    pub amount: u64,
+
+    /// Unix timestamp from which [`release_escrow`](crate::gamma::release_escrow) pays out
+    pub release_at: i64,
+
+    pub bump: u8,
+}
+
+impl ClaimEscrow {
+    pub const SIZE: usize = 8 + ClaimEscrow::INIT_SPACE;
+}
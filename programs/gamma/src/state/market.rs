@@ -5,14 +5,19 @@ use common::constants::MAX_OUTCOMES;
 use common::errors::ErrorCode;
 use spl_math::uint::U256;
 
-use crate::types::FixedSizeString;
+use crate::types::{
+    outcome_index_u8, FeeRounding, FixedSizeString, InitializationMode, MarketPhase,
+    MarketSnapshot, SwapFill, VaultHealth,
+};
 
 #[account(zero_copy)]
 #[derive(InitSpace, Default)]
 #[repr(C)]
 pub struct Market {
-    /// invariant = geometric mean * scale^N
-    /// This never changes except at initialization.
+    /// Product of the active reserves, ∏ reserves[i]. Prices come from each outcome's own reserve
+    /// and supply, not from this product; it backs the invariant-restoration helpers such as
+    /// [`Market::required_delta`]. Until the market opens it is `scale^N`, the product of the
+    /// reserves the first buy opens at, see [`Market::opening_invariant`].
     /// This is a u256 but raw so it can impl Pod
     pub invariant: [u8; 32],
 
@@ -29,19 +34,101 @@ pub struct Market {
     /// Used so geometric mean calculations stay stable.
     pub scale: u64,
 
+    /// Unix timestamp `init_market` ran at
     pub initialized_at: u64,
 
+    /// Slot `init_market` ran in, orders markets created within the same second
+    pub created_slot: u64,
+
     /// When the market will resolve and halt trading
     pub resolve_at: i64,
 
     /// Lamports held in the market_vault not yet claimed by the fee recipient
     pub undistributed_fees: u64,
 
+    /// Settlement fees taken from claims, held in the market_vault until the fee recipient claims them
+    pub undistributed_settlement_fees: u64,
+
+    /// Every fee ever taken by the market. Never decreases, unlike the withdrawable buckets.
+    pub lifetime_fees: u64,
+
+    /// Minimum total reserves required before the market can resolve
+    pub min_resolve_liquidity: u64,
+
+    /// Market PDA seed that lets several markets share a label, see [`market_nonce_seed`](crate::types::market_nonce_seed)
+    pub nonce: u64,
+
+    /// Seconds before `resolve_at` over which the sell fee ramps up, 0 keeps it flat
+    pub sell_fee_ramp_secs: i64,
+
+    /// Timestamp the price accumulators were last advanced, set at init then on every trade
+    pub last_trade_at: i64,
+
+    /// Seconds after `resolve_at` during which sells stay open, see [`Market::sell_deadline`]
+    pub post_expiry_sell_grace: i64,
+
+    /// Seconds after `resolve_at` that escrowed winnings stay locked, see
+    /// [`Market::escrow_release_at`]. 0 pays claims out directly.
+    pub claim_vesting_secs: i64,
+
+    /// Smallest `amount_in` a buy may use, in lamports. 0 disables it.
+    pub min_deposit: u64,
+
+    /// Largest reserve any single outcome may reach through buys. 0 means no cap.
+    pub max_reserve: u64,
+
+    /// Largest total reserves across all outcomes buys may reach. 0 means no cap.
+    pub max_total_reserves: u64,
+
+    /// Largest supply any single outcome may reach through buys. 0 means no cap.
+    pub max_supply: u64,
+
+    /// Lamports added to every active reserve when computing implied probabilities only,
+    /// pulling them away from 0 and 1. Never backs a payout. 0 disables it.
+    pub virtual_reserve: u64,
+
+    /// Backstop lamports deposited at init from [`MarketConfig::min_vault_funding`].
+    /// [`Market::sweep_surplus`] leaves them in place.
+    ///
+    /// [`MarketConfig::min_vault_funding`]: crate::types::MarketConfig::min_vault_funding
+    pub vault_funding: u64,
+
+    /// Highest implied probability, scaled by 1e9, a buy may push its outcome to. 0 disables it.
+    /// See [`MarketConfig::max_buyable_price`](crate::types::MarketConfig::max_buyable_price).
+    pub max_buyable_price: u64,
+
+    /// Buys and sells executed so far, a swap counts as one of each
+    pub trade_count: u64,
+
+    /// Per-outcome running sum of `outcome_price × seconds`, used to derive a TWAP.
+    /// These are u128 but raw little-endian bytes so they can impl Pod.
+    /// They wrap on overflow by design; differences between two readings stay correct.
+    pub price_cumulative: [[u8; 16]; MAX_OUTCOMES],
+
+    /// [`Market::outcome_price`] of each outcome right after its last buy or sell.
+    /// A snapshot from the last trade, not a live price: trades never move other outcomes'
+    /// prices. Every outcome is recorded at its opening price when the market opens.
+    pub last_price: [u64; MAX_OUTCOMES],
+
     /// The admin of the market who can mutate it
     pub admin: Pubkey,
 
+    /// Admin proposed by [`transfer_admin`](crate::gamma::transfer_admin), who takes over once
+    /// they call [`accept_admin`](crate::gamma::accept_admin). The default key if none.
+    pub pending_admin: Pubkey,
+
+    /// Account [`claim_fees`](crate::gamma::claim_fees) pays accrued trading fees to
+    pub fee_recipient: Pubkey,
+
+    /// SPL mint the market trades in, or the default key for native SOL markets.
+    /// See [`Market::collateral_mint`].
+    pub collateral_mint: Pubkey,
+
     pub label: FixedSizeString,
 
+    /// Trades required before the market can resolve, see [`Market::check_resolve_activity`]
+    pub min_trades_before_resolve: u32,
+
     /// Number of outcomes (N)
     pub num_outcomes: u8,
 
@@ -51,12 +138,83 @@ pub struct Market {
     /// Bump for market_vault which contains SOL reserves on behalf of the [`Market`]
     pub vault_bump: u8,
 
-    /// Padding for zero copy alignment
-    pub _padding: [u8; 13],
+    /// Outcome that refunds all holders if it wins, or [`NO_OUTCOME`] if there is none
+    pub void_outcome: u8,
+
+    /// Fee in basis points deducted from each claim payout
+    pub settlement_fee_bps: u16,
+
+    /// Minimum buy size in basis points of total reserves, 0 disables
+    pub min_trade_fraction_bps: u16,
+
+    /// Non-zero to reject buys while the market is insolvent, see [`Market::assert_solvent`]
+    pub enforce_solvency: u8,
+
+    /// Non-zero once the market has been resolved to `winning_outcome`
+    pub resolved: u8,
+
+    /// Outcome the market resolved to, only meaningful once `resolved` is set
+    pub winning_outcome: u8,
+
+    /// [`InitializationMode`](crate::types::InitializationMode) as its u8 discriminant
+    pub initialization_mode: u8,
+
+    /// Sell fee in basis points reached at `resolve_at` when the sell fee ramp is enabled
+    pub sell_fee_max_bps: u16,
+
+    /// Base sell fee in basis points, [`FEE_BPS`] unless set at init
+    pub fee_bps: u16,
+
+    /// Fee in basis points taken from each buy's `amount_in` before it enters the curve
+    pub buy_fee_bps: u16,
+
+    /// [`FeeRounding`](crate::types::FeeRounding) as its u8 discriminant
+    pub fee_rounding: u8,
+
+    /// Hash of the evidence or attestation the admin resolved on, all zeros if none was given
+    pub resolution_proof: [u8; 32],
+
+    /// Non-zero while the admin has halted trading, see [`Market::check_not_paused`]
+    pub paused: u8,
+
+    /// Decimals every outcome mint of this market was created with
+    pub decimals: u8,
+
+    /// Non-zero once an instruction has seen the market past `resolve_at`, see
+    /// [`Market::mark_awaiting_resolution`]
+    pub awaiting_resolution: u8,
+
+    /// Non-zero when the market resolved with `allow_empty` to an outcome nobody held, so it
+    /// refunds every holder like the void outcome, see [`Market::settles_as_void`]
+    pub refund_all: u8,
+
+    /// See [`MarketConfig::invariant_recheck_interval`](crate::types::MarketConfig::invariant_recheck_interval)
+    pub invariant_recheck_interval: u8,
+
+    pub _padding: [u8; 4],
 }
 
 impl Market {
     pub const SIZE: usize = 8 + Market::INIT_SPACE;
+
+    /// Compute units for loading the market and the fixed per-instruction overhead
+    pub const ESTIMATED_BASE_CU: u32 = 20_000;
+
+    /// Compute units per buy or sell: the U256 invariant recompute across [`MAX_OUTCOMES`]
+    /// reserves plus the token and lamport CPIs. Deliberately conservative.
+    pub const ESTIMATED_CU_PER_OP: u32 = 40_000;
+
+    /// Compute units a transaction may request at most
+    pub const MAX_TRANSACTION_CU: u32 = 1_400_000;
+
+    /// Estimate the compute units for a batch of `num_ops` trades against one market, so SDKs
+    /// can attach a `SetComputeUnitLimit` instruction. Capped at [`Market::MAX_TRANSACTION_CU`].
+    pub fn estimated_cu(num_ops: u32) -> u32 {
+        Self::ESTIMATED_CU_PER_OP
+            .saturating_mul(num_ops)
+            .saturating_add(Self::ESTIMATED_BASE_CU)
+            .min(Self::MAX_TRANSACTION_CU)
+    }
 }
 
 impl Market {
@@ -79,6 +237,13 @@ impl Market {
     /// invariant = ∏_{i=0..num_outcomes-1} reserves[i]
     /// Returns the new invariant (U256) or MathOverflow error.
     pub fn recompute_invariant(&mut self) -> Result<U256> {
+        let prod = self.reserves_product()?;
+        self.set_invariant_u256(prod);
+        Ok(prod)
+    }
+
+    /// Product of the active reserves, what [`Market::recompute_invariant`] stores
+    fn reserves_product(&self) -> Result<U256> {
         let n = self.num_outcomes as usize;
         check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
 
@@ -89,11 +254,74 @@ impl Market {
             let r = U256::from(self.reserves[i]);
             prod = prod.checked_mul(r).ok_or(error!(ErrorCode::MathOverflow))?;
         }
+        Ok(prod)
+    }
+
+    /// Check the stored invariant still equals the product of the reserves. Trades update it
+    /// incrementally, see [`Market::update_invariant_after_change`], so this catches any drift
+    /// between the two.
+    pub fn check_invariant(&self) -> Result<()> {
+        check_condition!(
+            self.invariant_u256() == self.reserves_product()?,
+            InvariantDrift
+        );
+        Ok(())
+    }
+
+    /// Update the invariant after only `reserves[idx]` changed from `old_reserve`, dividing the
+    /// old reserve out and multiplying the new one in rather than rebuilding the whole product.
+    ///
+    /// Falls back to [`Market::recompute_invariant`] when either the old reserve or the stored
+    /// product is 0, since a zero factor can't be divided back out, or when the stored invariant
+    /// isn't a multiple of `old_reserve`.
+    pub fn update_invariant_after_change(&mut self, idx: usize, old_reserve: u64) -> Result<U256> {
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
+        check_condition!(idx < n, InvalidOutcomeIndex);
+
+        let k = self.invariant_u256();
+        let old = U256::from(old_reserve);
+        if old.is_zero() || k.is_zero() || !(k % old).is_zero() {
+            return self.recompute_invariant();
+        }
 
+        let prod = (k / old)
+            .checked_mul(U256::from(self.reserves[idx]))
+            .ok_or(error!(ErrorCode::MathOverflow))?;
         self.set_invariant_u256(prod);
         Ok(prod)
     }
 
+    /// Whether every active outcome holds a reserve. An [empty](Market::is_empty) market is opened
+    /// by its first buy, which sets every reserve to `scale`, or by [`Market::seed`].
+    pub fn is_open(&self) -> bool {
+        let n = (self.num_outcomes as usize).min(MAX_OUTCOMES);
+        n > 0 && self.reserves[..n].iter().all(|r| *r > 0)
+    }
+
+    /// Whether the market holds nothing: no reserve and no outcome tokens in any outcome.
+    /// A market with one outcome drained is not open, but its other holders still have claims,
+    /// so only an empty market may have its opening reserves set.
+    pub fn is_empty(&self) -> bool {
+        let n = (self.num_outcomes as usize).min(MAX_OUTCOMES);
+        self.reserves[..n].iter().all(|r| *r == 0) && self.supplies[..n].iter().all(|s| *s == 0)
+    }
+
+    /// Invariant of the reserves a first buy opens the market at, `scale` in every outcome:
+    /// `scale^num_outcomes`. Stored at init so the invariant is meaningful before any trade.
+    pub fn opening_invariant(&self) -> Result<U256> {
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
+
+        let mut prod = U256::from(1u64);
+        for _ in 0..n {
+            prod = prod
+                .checked_mul(U256::from(self.scale))
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+        }
+        Ok(prod)
+    }
+
     /// Compute product of reserves excluding index `idx`:
     /// returns ∏_{j != idx} reserves[j] as U256
     pub fn product_except(&self, idx: usize) -> Result<U256> {
@@ -116,13 +344,19 @@ impl Market {
     ///
     /// required_r_i = invariant / ∏_{j != i} r_j
     ///
-    /// If product_except == 0, this returns 0 (degenerate case).
+    /// An unopened market is measured against the reserves it opens at, so every outcome
+    /// requires `scale`, `opening_invariant / scale^(N-1)`. If product_except == 0 on an open
+    /// market, this returns 0 (degenerate case).
     pub fn required_reserve_for(&self, idx: usize) -> Result<U256> {
         // validate
         let n = self.num_outcomes as usize;
         check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
         check_condition!(idx < n, InvalidOutcomeIndex);
 
+        if !self.is_open() {
+            return Ok(U256::from(self.scale));
+        }
+
         let inv = self.invariant_u256();
         let denom = self.product_except(idx)?;
 
@@ -139,7 +373,8 @@ impl Market {
 
     /// Compute how many raw units (u64) must be added to outcome idx to restore the invariant:
     ///
-    /// returns 0 if already satisfied; clamps to u64::MAX if delta > u64::MAX
+    /// returns 0 if already satisfied; errors with `DeltaTooLarge` if delta > u64::MAX, since no
+    /// single deposit could cover it and a delta that large points at a miscalculation
     pub fn required_delta(&self, idx: usize) -> Result<u64> {
         let n = self.num_outcomes as usize;
         check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
@@ -152,45 +387,302 @@ impl Market {
             return Ok(0u64);
         }
         let delta = req - cur;
+        check_condition!(delta <= U256::from(u64::MAX), DeltaTooLarge);
+        Ok(delta.as_u64())
+    }
 
-        // clamp to u64::MAX, though a delta that large indicates something is off
-        if delta > U256::from(u64::MAX) {
-            Ok(u64::MAX)
-        } else {
-            Ok(delta.as_u64())
+    /// Time from which sells are rejected: `resolve_at` plus the post-expiry sell grace
+    pub fn sell_deadline(&self) -> Result<i64> {
+        self.resolve_at
+            .checked_add(self.post_expiry_sell_grace)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    /// Whether winnings must be claimed into escrow rather than paid out directly
+    pub fn vests_claims(&self) -> bool {
+        self.claim_vesting_secs > 0
+    }
+
+    /// Time from which escrowed winnings can be released: `resolve_at` plus the claim vesting
+    /// period
+    pub fn escrow_release_at(&self) -> Result<i64> {
+        self.resolve_at
+            .checked_add(self.claim_vesting_secs)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    /// The market's lifecycle phase at `now`, so clients can branch on one value instead of
+    /// combining timestamps and flags.
+    pub fn phase(&self, now: i64) -> Result<MarketPhase> {
+        if let Some(winner) = self.winning_outcome() {
+            if self.settles_as_void(winner) {
+                return Ok(MarketPhase::Cancelled);
+            }
+            return Ok(MarketPhase::Resolved);
         }
+        if now >= self.sell_deadline()? {
+            return Ok(MarketPhase::Resolving);
+        }
+        if self.awaiting_resolution != 0 || now >= self.resolve_at {
+            return Ok(MarketPhase::SellOnly);
+        }
+        if self.check_seeded(false).is_err() {
+            return Ok(MarketPhase::PreOpen);
+        }
+        Ok(MarketPhase::Trading)
     }
 
-    pub fn buy_outcome(&mut self, outcome_index: usize, amount_in: u64) -> Result<u64> {
+    /// Whether a buy into `outcome_index` would currently be accepted from a non-admin buyer, so
+    /// clients can enable or disable buy buttons per outcome without repeating the instruction's
+    /// checks.
+    ///
+    /// Runs [`Market::check_buy_allowed`], then quotes a buy of [`Market::min_buy_amount`] so the
+    /// caps, price limit and minimum sizes in [`Market::buy_outcome`] apply too. Every one of those
+    /// only tightens as a buy grows, so if the smallest buy is refused every buy is. The vault
+    /// solvency circuit breaker needs the vault balance and isn't covered.
+    pub fn can_buy_outcome(&self, outcome_index: usize, now: i64) -> bool {
+        self.check_buy_allowed(outcome_index, now, false).is_ok()
+            && self
+                .min_buy_amount()
+                .and_then(|amount_in| self.quote_buy(outcome_index, amount_in))
+                .is_ok()
+    }
+
+    /// Check the market is taking buys into `outcome_index` at `now`, whatever their size: it is
+    /// unresolved, unpaused, before `resolve_at` and not awaiting resolution, the outcome is
+    /// active, and the market is [seeded](Market::check_seeded) unless the buyer is the admin.
+    ///
+    /// `buy` and `batch_buy` run this before trading, and size-dependent limits are left to
+    /// [`Market::buy_outcome`]. Any new restriction on buying belongs in one of the two.
+    pub fn check_buy_allowed(
+        &self,
+        outcome_index: usize,
+        now: i64,
+        buyer_is_admin: bool,
+    ) -> Result<()> {
+        self.check_not_resolved()?;
+        self.check_not_paused()?;
+        check_condition!(
+            now < self.resolve_at && self.awaiting_resolution == 0,
+            MarketExpired
+        );
+
         let n = self.num_outcomes as usize;
+        check_condition!(n > 0, OutcomeBelowZero);
         check_condition!(outcome_index < n, InvalidOutcomeIndex);
-        check_condition!(amount_in > 0, DepositIsZero);
+        self.check_seeded(buyer_is_admin)
+    }
 
-        // Get current invariant k = ∏ reserves[i]
-        let k = self.invariant_u256();
-        let is_first_trade = k.is_zero();
+    /// Smallest deposit [`Market::buy_outcome`] would accept right now: enough to put a lamport
+    /// into the curve after the buy fee, or the opening deposit on an empty market, and at least
+    /// `min_deposit` and [`Market::min_trade_amount`].
+    pub fn min_buy_amount(&self) -> Result<u64> {
+        let net = if self.is_empty() {
+            self.opening_deposit()?
+        } else {
+            1
+        };
+        Ok(self
+            .gross_up_buy_fee(net)?
+            .max(self.min_deposit)
+            .max(self.min_trade_amount()?))
+    }
 
-        if is_first_trade {
-            // First trade: initialize all reserves to scale
-            for i in 0..n {
-                self.reserves[i] = self.scale;
-            }
+    /// Check the market has not been resolved, after which trading is closed for good
+    pub fn check_not_resolved(&self) -> Result<()> {
+        check_condition!(self.winning_outcome().is_none(), MarketResolved);
+        Ok(())
+    }
 
-            // Add user's deposit to the bought outcome's reserve
-            self.reserves[outcome_index] = self.reserves[outcome_index]
-                .checked_add(amount_in)
-                .ok_or(error!(ErrorCode::MathOverflow))?;
+    /// Check the admin has not paused trading. Unlike resolution a pause can be lifted, so it is
+    /// meant for emergencies such as a pricing bug.
+    pub fn check_not_paused(&self) -> Result<()> {
+        check_condition!(self.paused == 0, MarketPaused);
+        Ok(())
+    }
+
+    /// Resolve the market to `winning_outcome` at `now`.
+    ///
+    /// Only allowed once, from `resolve_at` onward, for a market that met its minimum resolve
+    /// liquidity and to an outcome someone can claim, see [`Market::check_resolution_target`].
+    /// With `allow_empty` an outcome nobody holds is accepted too, and the market then refunds
+    /// every holder as if the void outcome had won.
+    pub fn resolve(&mut self, winning_outcome: usize, now: i64, allow_empty: bool) -> Result<()> {
+        self.check_not_resolved()?;
+        check_condition!(now >= self.resolve_at, MarketNotExpired);
+        self.check_resolve_liquidity()?;
+        self.check_resolve_activity()?;
+        self.check_resolution_target(winning_outcome, allow_empty)?;
+
+        self.winning_outcome = winning_outcome as u8;
+        self.resolved = 1;
+        if self.void_outcome() != Some(winning_outcome) && self.supplies[winning_outcome] == 0 {
+            self.refund_all = 1;
+        }
+        Ok(())
+    }
+
+    /// Check a buy may set the opening prices of a market with no reserves.
+    /// In [`InitializationMode::RequireSeed`] only the admin may buy until reserves exist.
+    pub fn check_seeded(&self, buyer_is_admin: bool) -> Result<()> {
+        if self.initialization_mode == InitializationMode::RequireSeed as u8 && !buyer_is_admin {
+            check_condition!(self.total_reserves_u128()? > 0, MarketNotSeeded);
+        }
+        Ok(())
+    }
+
+    /// Check a buy into `outcome_index` left it and the market within the configured caps
+    fn check_caps(&self, outcome_index: usize) -> Result<()> {
+        let within = |value: u128, cap: u64| cap == 0 || value <= cap as u128;
+        check_condition!(
+            within(self.reserves[outcome_index] as u128, self.max_reserve)
+                && within(self.supplies[outcome_index] as u128, self.max_supply)
+                && within(self.total_reserves_u128()?, self.max_total_reserves),
+            MarketCapExceeded
+        );
+        if self.max_buyable_price > 0 {
+            let price = self.liquidity_percentages()?[outcome_index];
+            check_condition!(price <= self.max_buyable_price, OutcomePriceTooHigh);
+        }
+        Ok(())
+    }
+
+    /// Replace the buy limits, see [`set_limits`](crate::gamma::set_limits).
+    ///
+    /// A cap of 0 removes it. Caps below what the market already holds are rejected, since
+    /// they could only be met by forcing holders out.
+    pub fn set_limits(
+        &mut self,
+        min_deposit: u64,
+        max_reserve: u64,
+        max_total_reserves: u64,
+        max_supply: u64,
+    ) -> Result<()> {
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
+        let covers = |usage: u128, cap: u64| cap == 0 || usage <= cap as u128;
+        let largest_reserve = self.reserves[..n].iter().max().copied().unwrap_or(0);
+        let largest_supply = self.supplies[..n].iter().max().copied().unwrap_or(0);
+        check_condition!(
+            covers(largest_reserve as u128, max_reserve)
+                && covers(largest_supply as u128, max_supply)
+                && covers(self.total_reserves_u128()?, max_total_reserves),
+            InvalidLimits
+        );
+
+        self.min_deposit = min_deposit;
+        self.max_reserve = max_reserve;
+        self.max_total_reserves = max_total_reserves;
+        self.max_supply = max_supply;
+        Ok(())
+    }
+
+    /// Move `resolve_at` of an unresolved market, e.g. when the event it tracks is postponed.
+    /// The new time must be after `now`, so an expired market reopens for trading until then and
+    /// stops [awaiting resolution](Market::mark_awaiting_resolution).
+    pub fn update_resolve_at(&mut self, resolve_at: i64, now: i64) -> Result<()> {
+        self.check_not_resolved()?;
+        check_condition!(resolve_at > now, InvalidResolveTime);
+        self.resolve_at = resolve_at;
+        self.awaiting_resolution = 0;
+        Ok(())
+    }
+
+    /// Record on chain that an unresolved market has crossed `resolve_at` and is waiting for the
+    /// admin to resolve it, returning whether it is now awaiting resolution.
+    ///
+    /// Buys already stop at `resolve_at` by the clock alone; this only persists the transition
+    /// the first time an instruction observes it, so clients can read it from the account.
+    pub fn mark_awaiting_resolution(&mut self, now: i64) -> Result<bool> {
+        self.check_not_resolved()?;
+        if now >= self.resolve_at {
+            self.awaiting_resolution = 1;
+        }
+        Ok(self.awaiting_resolution != 0)
+    }
+
+    /// Smallest `amount_in` a buy may use: `min_trade_fraction_bps` of total reserves
+    pub fn min_trade_amount(&self) -> Result<u64> {
+        let min_amount = self
+            .total_reserves_u128()?
+            .checked_mul(self.min_trade_fraction_bps as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?
+            / BPS_DENOMINATOR as u128;
+        Ok(min_amount.min(u64::MAX as u128) as u64)
+    }
+
+    /// Seed an [empty](Market::is_empty) market with `amount_per_outcome` in every outcome's reserve,
+    /// returning the tokens of each outcome minted to the seeder.
+    ///
+    /// Each outcome mints `num_outcomes × amount_per_outcome` tokens, so every outcome opens at
+    /// `outcome_price = 1 / num_outcomes` and a winning token pays out 1 lamport. The seeder
+    /// holds the whole supply of every outcome, so whichever wins, the deposit is theirs to
+    /// redeem.
+    pub fn seed(&mut self, amount_per_outcome: u64) -> Result<u64> {
+        self.check_not_resolved()?;
+        check_condition!(self.is_empty(), MarketAlreadySeeded);
+        check_condition!(amount_per_outcome > 0, DepositIsZero);
 
-            // Set initial invariant k = ∏ reserves[i]
-            self.recompute_invariant()?;
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
+        let tokens_per_outcome = amount_per_outcome
+            .checked_mul(n as u64)
+            .ok_or(error!(ErrorCode::SupplyTooLarge))?;
+
+        for i in 0..n {
+            self.reserves[i] = amount_per_outcome;
+            self.supplies[i] = tokens_per_outcome;
+        }
+        self.recompute_invariant()?;
+        self.last_price = self.all_marginal_prices()?;
+        Ok(tokens_per_outcome)
+    }
 
-            // Mint tokens 1:1 for first trade
-            let amount_out = amount_in;
-            self.supplies[outcome_index] = amount_out;
+    /// Buy `outcome_index` with `amount_in` lamports, returning the tokens of it minted.
+    ///
+    /// The first buy into an [empty](Market::is_empty) market opens it as [`Market::seed`] would
+    /// with `scale` per outcome, paid out of the deposit, so every reserve has holders and every
+    /// reserve lamport is in the vault. The buyer receives every outcome's opening supply of
+    /// [`Market::opening_deposit`] tokens, and the rest of the deposit buys on the curve.
+    pub fn buy_outcome(&mut self, outcome_index: usize, amount_in: u64) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        check_condition!(outcome_index < n, InvalidOutcomeIndex);
+        check_condition!(amount_in > 0, DepositIsZero);
+        check_condition!(amount_in >= self.min_trade_amount()?, TradeTooSmall);
+        check_condition!(amount_in >= self.min_deposit, TradeTooSmall);
+
+        // The buy fee stays in the vault, only the rest of the deposit enters the curve
+        let fee = self.fee_at_bps(amount_in, self.buy_fee_bps as u64)?;
+        self.accrue_trading_fee(fee)?;
+        let mut amount_in = amount_in
+            .checked_sub(fee)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        check_condition!(amount_in > 0, TradeTooSmall);
+
+        let mut amount_out = 0;
+        if self.is_empty() {
+            let opening_deposit = self.opening_deposit()?;
+            check_condition!(amount_in >= opening_deposit, TradeTooSmall);
+            amount_out = self.seed(self.scale)?;
+            amount_in -= opening_deposit;
+        }
 
-            return Ok(amount_out);
+        if amount_in > 0 {
+            let minted = self.buy_on_curve(outcome_index, amount_in)?;
+            amount_out = amount_out
+                .checked_add(minted)
+                .ok_or(error!(ErrorCode::SupplyTooLarge))?;
         }
 
+        self.check_caps(outcome_index)?;
+
+        self.record_trade(outcome_index)?;
+        Ok(amount_out)
+    }
+
+    /// Add `amount_in` to the reserve of `outcome_index` and mint in proportion, returning the
+    /// tokens minted
+    fn buy_on_curve(&mut self, outcome_index: usize, amount_in: u64) -> Result<u64> {
         // Geometric mean AMM (Balancer-style with equal weights)
         // Invariant: k = ∏ reserves[i]
         //
@@ -206,7 +698,10 @@ impl Market {
         let old_reserve = self.reserves[outcome_index];
         check_condition!(old_reserve > 0, ReserveIsZero);
 
+        // The reserve belongs to the outcome's holders, so with none it can't price the buy.
+        // Minting against it 1:1 would let the buyer sell the whole reserve straight back.
         let old_supply = self.supplies[outcome_index];
+        check_condition!(old_supply > 0, SupplyIsZero);
 
         // Add user's deposit to reserve
         let new_reserve = old_reserve
@@ -216,26 +711,21 @@ impl Market {
         self.reserves[outcome_index] = new_reserve;
 
         // Calculate tokens to mint: supply × (amount_in / old_reserve)
-        let amount_out = if old_supply == 0 {
-            // If no supply yet, mint 1:1
-            amount_in
-        } else {
-            // Mint proportional to reserve increase
-            ((old_supply as u128)
-                .checked_mul(amount_in as u128)
-                .ok_or(error!(ErrorCode::MathOverflow))?
-                .checked_div(old_reserve as u128)
-                .ok_or(error!(ErrorCode::MathOverflow))?) as u64
-        };
-
-        // Update supply
-        self.supplies[outcome_index] = self.supplies[outcome_index]
-            .checked_add(amount_out)
+        let amount_out = (old_supply as u128)
+            .checked_mul(amount_in as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?
+            .checked_div(old_reserve as u128)
             .ok_or(error!(ErrorCode::MathOverflow))?;
+        let amount_out =
+            u64::try_from(amount_out).map_err(|_| error!(ErrorCode::SupplyTooLarge))?;
 
-        // Recompute invariant (it increases as we add liquidity)
-        self.recompute_invariant()?;
+        // Update supply
+        let new_supply = (old_supply as u128) + amount_out as u128;
+        self.supplies[outcome_index] =
+            u64::try_from(new_supply).map_err(|_| error!(ErrorCode::SupplyTooLarge))?;
 
+        // Update the invariant (it increases as we add liquidity)
+        self.update_invariant_after_change(outcome_index, old_reserve)?;
         Ok(amount_out)
     }
 
@@ -244,6 +734,7 @@ impl Market {
         outcome_index: usize,
         burn_amount: u64,
         vault_lamports: u64,
+        now: i64,
     ) -> Result<u64> {
         let n = self.num_outcomes as usize;
         check_condition!(outcome_index < n, InvalidOutcomeIndex);
@@ -262,40 +753,26 @@ impl Market {
         // This maintains the reserve-to-supply ratio and ensures symmetry
 
         // Calculate refund: reserve × (burn_amount / supply)
-        let refund_u64 = ((reserve_before as u128)
+        let refund = (reserve_before as u128)
             .checked_mul(burn_amount as u128)
             .ok_or(error!(ErrorCode::MathOverflow))?
             .checked_div(supply_before as u128)
-            .ok_or(error!(ErrorCode::MathOverflow))?) as u64;
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        let refund_u64 = u64::try_from(refund).map_err(|_| error!(ErrorCode::MathOverflow))?;
 
-        // If nothing to refund (due to rounding), return early
-        if refund_u64 == 0 {
-            // update supplies only and recompute invariant
-            self.supplies[outcome_index] = self.supplies[outcome_index]
-                .checked_sub(burn_amount)
-                .ok_or(error!(ErrorCode::MathOverflow))?;
-            self.recompute_invariant()
-                .map_err(|_| error!(ErrorCode::MathOverflow))?;
-            return Ok(0);
-        }
+        // A dust burn that rounds to no refund would destroy the user's tokens for nothing
+        check_condition!(refund_u64 > 0, BurnProducesNoRefund);
 
         // Ensure vault has enough lamports
         check_condition!(vault_lamports >= refund_u64, InsufficientVaultFunds);
 
         // --- apply fee (fee stays in market vault) ---
-        let fee = (refund_u64 as u128)
-            .checked_mul(FEE_BPS as u128)
-            .ok_or(error!(ErrorCode::MathOverflow))?
-            / 10_000u128;
-        let fee_u64 = fee as u64;
+        let fee_u64 = self.sell_fee(refund_u64, now)?;
         let net_payout_u64 = refund_u64
             .checked_sub(fee_u64)
             .ok_or(error!(ErrorCode::MathOverflow))?;
 
-        self.undistributed_fees = self
-            .undistributed_fees
-            .checked_add(fee_u64)
-            .ok_or(error!(ErrorCode::MathOverflow))?;
+        self.accrue_trading_fee(fee_u64)?;
 
         // --- Update market state: decrease reserve by full refund (refund includes fee that remains in vault)
         self.reserves[outcome_index] = self.reserves[outcome_index]
@@ -307,88 +784,966 @@ impl Market {
             .checked_sub(burn_amount)
             .ok_or(error!(ErrorCode::MathOverflow))?;
 
-        self.recompute_invariant()?;
+        self.update_invariant_after_change(outcome_index, reserve_before)?;
 
+        self.record_trade(outcome_index)?;
         Ok(net_payout_u64)
     }
 
-    /// Compute normalized percentage of total liquidity for each outcome.
-    /// Returns [u64; MAX_OUTCOMES] where each value represents the percentage
-    /// of total reserves that outcome holds, scaled by 1e9 (i.e., 100% = 1_000_000_000).
-    ///
-    /// For example, if outcome 0 has 30% of total liquidity, the returned value
-    /// at index 0 would be 300_000_000.
-    pub fn liquidity_percentages(&self) -> Result<[u64; MAX_OUTCOMES]> {
+    /// Compare recorded supplies against the real supply of each active outcome mint and return
+    /// the indices that differ. With `repair`, mismatched supplies are overwritten from the mints.
+    pub fn reconcile_supplies(&mut self, mint_supplies: &[u64], repair: bool) -> Result<Vec<u8>> {
+        let n = self.num_outcomes as usize;
+        check_condition!(mint_supplies.len() == n, InvalidMintCount);
+
+        let mut mismatches = Vec::new();
+        for (i, mint_supply) in mint_supplies.iter().enumerate() {
+            if self.supplies[i] != *mint_supply {
+                mismatches.push(outcome_index_u8(i)?);
+                if repair {
+                    self.supplies[i] = *mint_supply;
+                }
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// Smallest deposit, after the buy fee, the first buy must make: `scale` for every outcome's
+    /// opening reserve. The first buy funds them all, so the reserves never exceed the vault, and
+    /// receives this many tokens of every outcome, see [`Market::buy_outcome`].
+    pub fn opening_deposit(&self) -> Result<u64> {
+        self.scale
+            .checked_mul(self.num_outcomes as u64)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    /// Vault lamports available to back reserves once recorded fees and rent are set aside
+    pub fn vault_backing(&self, vault_lamports: u64, rent_min: u64) -> u64 {
+        vault_lamports
+            .saturating_sub(self.undistributed_fees)
+            .saturating_sub(self.undistributed_settlement_fees)
+            .saturating_sub(rent_min)
+    }
+
+    /// Sum of reserves across all active outcomes, promoted to u128 so the total of
+    /// [`MAX_OUTCOMES`] u64 reserves can never overflow in practice.
+    /// This is the single source of truth for "total liquidity" used by the pricing helpers.
+    pub fn total_reserves_u128(&self) -> Result<u128> {
         let n = self.num_outcomes as usize;
         check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
 
-        // Compute total reserves across all active outcomes
         let mut total: u128 = 0;
         for i in 0..n {
             total = total
                 .checked_add(self.reserves[i] as u128)
                 .ok_or(error!(ErrorCode::MathOverflow))?;
         }
+        Ok(total)
+    }
 
-        // Initialize result array with zeros
-        let mut percentages = [0u64; MAX_OUTCOMES];
+    /// Sum of supplies across all active outcomes, the total outstanding outcome tokens.
+    /// The supply-side counterpart of [`Market::total_reserves_u128`].
+    pub fn total_supply(&self) -> Result<u128> {
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
 
-        // Handle edge case: if total is zero, all percentages are zero
+        let mut total: u128 = 0;
+        for i in 0..n {
+            total = total
+                .checked_add(self.supplies[i] as u128)
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+        }
+        Ok(total)
+    }
+
+    /// Total reserves as seen by the implied probabilities: every active reserve plus
+    /// `virtual_reserve`. Zero for a market with no reserves so it still has no prices.
+    fn pricing_total_reserves(&self) -> Result<u128> {
+        let total = self.total_reserves_u128()?;
         if total == 0 {
-            return Ok(percentages);
+            return Ok(0);
         }
+        let buffer = (self.virtual_reserve as u128)
+            .checked_mul(self.num_outcomes as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        total
+            .checked_add(buffer)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
 
-        // Compute percentage for each active outcome
-        // percentage = (reserve / total) * 1e9
-        // We use 1e9 scaling to maintain precision (100% = 1_000_000_000)
+    /// Circulating supply of an outcome's token, read from the tracked `supplies` so clients
+    /// don't need to fetch the mint account.
+    pub fn circulating_supply(&self, outcome_index: usize) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
+        check_condition!(outcome_index < n, InvalidOutcomeIndex);
+        Ok(self.supplies[outcome_index])
+    }
 
-        for i in 0..n {
-            let reserve = self.reserves[i] as u128;
-            let percentage = reserve
-                .checked_mul(D9_U128)
-                .ok_or(error!(ErrorCode::MathOverflow))?
-                .checked_div(total)
-                .ok_or(error!(ErrorCode::MathOverflow))?;
+    /// Build a [`MarketSnapshot`] of the active outcomes
+    pub fn snapshot(&self) -> Result<MarketSnapshot> {
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
 
-            // Clamp to u64::MAX if somehow exceeds (shouldn't happen in practice)
-            percentages[i] = if percentage > u64::MAX as u128 {
-                u64::MAX
-            } else {
-                percentage as u64
-            };
-        }
+        Ok(MarketSnapshot {
+            num_outcomes: self.num_outcomes,
+            resolve_at: self.resolve_at,
+            created_slot: self.created_slot,
+            reserves: self.reserves[..n].to_vec(),
+            supplies: self.supplies[..n].to_vec(),
+            total_supply: self.total_supply()?,
+            max_payout: self.max_payout()?,
+            undistributed_fees: self.undistributed_fees,
+            lifetime_fees: self.lifetime_fees,
+        })
+    }
 
-        Ok(percentages)
+    /// Cumulative `outcome_price × seconds` for an outcome as of `last_trade_at`
+    pub fn price_cumulative(&self, outcome_index: usize) -> Result<u128> {
+        check_condition!(
+            outcome_index < self.num_outcomes as usize,
+            InvalidOutcomeIndex
+        );
+        Ok(u128::from_le_bytes(self.price_cumulative[outcome_index]))
     }
 
-    /// Compute the marginal price for a given outcome.
-    /// This represents the cost per token based on the current reserve-to-supply ratio.
-    /// Returns a u64 scaled by 1e9 (i.e., price of 1.0 = 1_000_000_000).
-    ///
-    /// Formula: price = reserve_i / supply_i
-    ///
-    /// This gives each outcome an independent price that reflects its own liquidity,
-    /// minimizing cross-impact from other outcomes.
-    ///
-    /// For example:
-    /// - If reserve = 100M and supply = 100M tokens, price = 1.0 (1_000_000_000)
-    /// - If reserve = 200M and supply = 100M tokens, price = 2.0 (2_000_000_000)
-    pub fn outcome_price(&self, outcome_index: usize) -> Result<u64> {
+    /// Cumulative price for an outcome extrapolated to `now` with the current price,
+    /// which has held since the last trade.
+    pub fn price_cumulative_at(&self, outcome_index: usize, now: i64) -> Result<u128> {
+        let cumulative = self.price_cumulative(outcome_index)?;
+        let elapsed = now.saturating_sub(self.last_trade_at).max(0) as u128;
+        let price = self.outcome_price(outcome_index)? as u128;
+        Ok(cumulative.wrapping_add(price.wrapping_mul(elapsed)))
+    }
+
+    /// Advance every outcome's price accumulator to `now` using the prices that held since
+    /// the last trade. Must be called before a trade mutates reserves or supplies.
+    pub fn accumulate_prices(&mut self, now: i64) -> Result<()> {
         let n = self.num_outcomes as usize;
         check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
-        check_condition!(outcome_index < n, InvalidOutcomeIndex);
 
-        let reserve = self.reserves[outcome_index] as u128;
-        let supply = self.supplies[outcome_index] as u128;
+        if now <= self.last_trade_at {
+            return Ok(());
+        }
 
-        // Handle edge case: if supply is zero, return 0
-        if supply == 0 {
-            return Ok(0);
+        for i in 0..n {
+            let cumulative = self.price_cumulative_at(i, now)?;
+            self.price_cumulative[i] = cumulative.to_le_bytes();
         }
+        self.last_trade_at = now;
+        Ok(())
+    }
 
-        // Compute price: (reserve / supply) * 1e9
-        // This gives the average cost per token in lamports, scaled by 1e9
-        let price = reserve
+    /// Time-weighted average price of an outcome (scaled by 1e9, like [`Market::outcome_price`])
+    /// between a previously observed accumulator reading and `now`.
+    ///
+    /// Integrators snapshot `price_cumulative_at(outcome_index, t0)` at `t0`, then call this at a
+    /// later `now`. Moving the average requires holding a manipulated price for the whole window,
+    /// which makes it far more resistant to manipulation than the spot price.
+    pub fn twap(
+        &self,
+        outcome_index: usize,
+        window_start_cumulative: u128,
+        window_start_time: i64,
+        now: i64,
+    ) -> Result<u64> {
+        check_condition!(now > window_start_time, InvalidTwapWindow);
+
+        let cumulative = self.price_cumulative_at(outcome_index, now)?;
+        let elapsed = (now - window_start_time) as u128;
+        let twap = cumulative.wrapping_sub(window_start_cumulative) / elapsed;
+
+        Ok(twap.min(u64::MAX as u128) as u64)
+    }
+
+    /// The SPL mint used as collateral, or `None` if the market trades native SOL
+    pub fn collateral_mint(&self) -> Option<Pubkey> {
+        if self.collateral_mint == Pubkey::default() {
+            None
+        } else {
+            Some(self.collateral_mint)
+        }
+    }
+
+    /// The designated void ("none of the above") outcome, if the market has one
+    pub fn void_outcome(&self) -> Option<usize> {
+        if self.void_outcome == NO_OUTCOME {
+            None
+        } else {
+            Some(self.void_outcome as usize)
+        }
+    }
+
+    /// Check the market may resolve to `winning_outcome`.
+    ///
+    /// Under winner-takes-all, resolving to an outcome with no supply leaves the whole pot
+    /// unclaimable, so it is rejected unless `allow_empty` is set, in which case
+    /// [`Market::resolve`] refunds every holder instead. The void outcome refunds everyone
+    /// anyway, so it may always be chosen.
+    pub fn check_resolution_target(&self, winning_outcome: usize, allow_empty: bool) -> Result<()> {
+        check_condition!(
+            winning_outcome < self.num_outcomes as usize,
+            InvalidOutcomeIndex
+        );
+        if self.void_outcome() != Some(winning_outcome) && !allow_empty {
+            check_condition!(self.supplies[winning_outcome] > 0, NoWinnersForOutcome);
+        }
+        Ok(())
+    }
+
+    /// Whether settling to `winning_outcome` refunds every holder from their own outcome's
+    /// reserve: it is the void outcome, or the outcome an `allow_empty` resolution picked while
+    /// nobody held it.
+    pub fn settles_as_void(&self, winning_outcome: usize) -> bool {
+        self.void_outcome() == Some(winning_outcome)
+            || (self.refund_all != 0 && self.winning_outcome() == Some(winning_outcome))
+    }
+
+    /// Lamports a winner-takes-all resolution splits between the winning holders: every active
+    /// reserve. The first buy funds the opening reserves out of its deposit, so each of these
+    /// lamports is held by the vault and the pot never promises more than was paid in.
+    pub fn settlement_pot(&self) -> Result<u64> {
+        Ok(self.total_reserves_u128()?.min(u64::MAX as u128) as u64)
+    }
+
+    /// Compute the lamports a holder of `tokens_held` of `outcome_index` receives once the
+    /// market settles to `winning_outcome`.
+    ///
+    /// - If the market [settles as void](Market::settles_as_void), every holder is refunded at
+    ///   liquidation value: `reserve_i × (tokens_held / supply_i)`, the same pre-fee refund a
+    ///   sell would give.
+    /// - Otherwise the winner takes all: winning holders split `pot` pro-rata to their share
+    ///   of the winning supply, and every other outcome pays 0.
+    pub fn settlement_payout(
+        &self,
+        winning_outcome: usize,
+        outcome_index: usize,
+        tokens_held: u64,
+        pot: u64,
+    ) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        check_condition!(winning_outcome < n, InvalidOutcomeIndex);
+        check_condition!(outcome_index < n, InvalidOutcomeIndex);
+
+        let supply = self.supplies[outcome_index];
+        check_condition!(tokens_held <= supply, BurnIsMoreThanSupply);
+        if tokens_held == 0 {
+            return Ok(0);
+        }
+
+        let value = if self.settles_as_void(winning_outcome) {
+            self.reserves[outcome_index]
+        } else if outcome_index == winning_outcome {
+            pot
+        } else {
+            return Ok(0);
+        };
+
+        let payout = (value as u128)
+            .checked_mul(tokens_held as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?
+            .checked_div(supply as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        u64::try_from(payout).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Base trading fee charged on a sell refund of `refund` lamports, at `fee_bps`
+    pub fn trading_fee(&self, refund: u64) -> Result<u64> {
+        self.fee_at_bps(refund, self.fee_bps as u64)
+    }
+
+    /// Sell fee in basis points at `now`.
+    ///
+    /// Flat at `fee_bps` until the last `sell_fee_ramp_secs` before `resolve_at`, then rises
+    /// linearly to `sell_fee_max_bps` at `resolve_at` and stays there through any post-expiry
+    /// sell grace. A zero ramp keeps the fee flat.
+    pub fn sell_fee_bps(&self, now: i64) -> u64 {
+        let ramp = self.sell_fee_ramp_secs;
+        let ramp_start = self.resolve_at.saturating_sub(ramp);
+        let base = self.fee_bps as u64;
+        if ramp <= 0 || now <= ramp_start {
+            return base;
+        }
+        let elapsed = now.saturating_sub(ramp_start).min(ramp) as u128;
+        let rise = (self.sell_fee_max_bps as u64).saturating_sub(base) as u128;
+        base + (rise * elapsed / ramp as u128) as u64
+    }
+
+    /// Fee charged on a sell refund of `refund` lamports at `now`, see [`Market::sell_fee_bps`]
+    pub fn sell_fee(&self, refund: u64, now: i64) -> Result<u64> {
+        self.fee_at_bps(refund, self.sell_fee_bps(now))
+    }
+
+    /// `fee_bps` of `amount`, rounded per `fee_rounding`. Never exceeds `amount` for
+    /// `fee_bps <= BPS_DENOMINATOR`, so `amount - fee` can't underflow.
+    fn fee_at_bps(&self, amount: u64, fee_bps: u64) -> Result<u64> {
+        let numerator = (amount as u128)
+            .checked_mul(fee_bps as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        let denominator = BPS_DENOMINATOR as u128;
+        let fee = if self.fee_rounding == FeeRounding::Ceil as u8 {
+            numerator.div_ceil(denominator)
+        } else {
+            numerator / denominator
+        };
+        u64::try_from(fee).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// The outcome the market resolved to, or `None` while it is unresolved
+    pub fn winning_outcome(&self) -> Option<usize> {
+        if self.resolved == 0 {
+            None
+        } else {
+            Some(self.winning_outcome as usize)
+        }
+    }
+
+    /// Check the market can be closed: it is resolved, the fee recipient has claimed its trading
+    /// and settlement fees, and no outcome tokens are left that could still redeem from the vault.
+    ///
+    /// After a winner-takes-all resolution only the winning supply can redeem, so losing supplies
+    /// are ignored. After a void resolution every supply can. Supplies up to
+    /// [`CLOSE_DUST_SUPPLY`] are treated as abandoned.
+    pub fn check_closable(&self) -> Result<()> {
+        let winner = self
+            .winning_outcome()
+            .ok_or(error!(ErrorCode::MarketNotResolved))?;
+        check_condition!(
+            self.undistributed_fees == 0 && self.undistributed_settlement_fees == 0,
+            MarketNotEmpty
+        );
+
+        let n = self.num_outcomes as usize;
+        let is_void = self.settles_as_void(winner);
+        for i in (0..n).filter(|i| is_void || *i == winner) {
+            check_condition!(self.supplies[i] <= CLOSE_DUST_SUPPLY, MarketNotEmpty);
+        }
+        Ok(())
+    }
+
+    /// Lamports `token_balance` of `outcome_index` is worth right now, net of fees.
+    ///
+    /// - Before resolution this is the curve value: what selling the balance would pay,
+    ///   `reserve_i × (token_balance / supply_i)` less the base trading fee. Inside the sell fee
+    ///   ramp a sell pays more, see [`Market::sell_fee_bps`].
+    /// - After resolution it is the flat claim value from [`Market::settlement_payout`] with the
+    ///   [`Market::settlement_pot`] as the pot, less the settlement fee. Losing outcomes are worth 0.
+    pub fn redeemable_lamports(&self, outcome_index: usize, token_balance: u64) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        check_condition!(outcome_index < n, InvalidOutcomeIndex);
+
+        if let Some(winner) = self.winning_outcome() {
+            let pot = self.settlement_pot()?;
+            let payout = self.settlement_payout(winner, outcome_index, token_balance, pot)?;
+            return payout
+                .checked_sub(self.settlement_fee(payout)?)
+                .ok_or(error!(ErrorCode::MathOverflow));
+        }
+
+        let refund = self.gross_refund(outcome_index, token_balance)?;
+        refund
+            .checked_sub(self.trading_fee(refund)?)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    /// Fee a full exit of `tokens_held` of `outcome_index` would pay right now: the base trading
+    /// fee on the sell's gross refund, for clients itemizing fees before selling a position.
+    ///
+    /// Inside the sell fee ramp a sell pays more, see [`Market::sell_fee_bps`]. Read only.
+    pub fn liquidation_fee(&self, outcome_index: usize, tokens_held: u64) -> Result<u64> {
+        check_condition!(
+            outcome_index < self.num_outcomes as usize,
+            InvalidOutcomeIndex
+        );
+        let refund = self.gross_refund(outcome_index, tokens_held)?;
+        self.trading_fee(refund)
+    }
+
+    /// Pre-fee refund for `tokens` of `outcome_index`, their pro-rata share of its reserve:
+    /// `reserve_i × tokens / supply_i`. The caller validates `outcome_index`.
+    fn gross_refund(&self, outcome_index: usize, tokens: u64) -> Result<u64> {
+        let supply = self.supplies[outcome_index];
+        check_condition!(tokens <= supply, BurnIsMoreThanSupply);
+        if tokens == 0 {
+            return Ok(0);
+        }
+        let refund = (self.reserves[outcome_index] as u128)
+            .checked_mul(tokens as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?
+            / supply as u128;
+        u64::try_from(refund).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Settlement fee charged on a claim payout of `payout` lamports
+    pub fn settlement_fee(&self, payout: u64) -> Result<u64> {
+        self.fee_at_bps(payout, self.settlement_fee_bps as u64)
+    }
+
+    /// Move a position from `from_index` to `to_index`: sell `burn_amount` of `from_index` at
+    /// `now` and buy `to_index` with the net refund, see [`SwapFill`].
+    ///
+    /// The refund never leaves the vault, so only the sell fee is kept back. Fails with
+    /// `SlippageExceeded` if fewer than `min_amount_out` tokens would be minted.
+    pub fn swap_outcome(
+        &mut self,
+        from_index: usize,
+        to_index: usize,
+        burn_amount: u64,
+        min_amount_out: u64,
+        vault_lamports: u64,
+        now: i64,
+    ) -> Result<SwapFill> {
+        let n = self.num_outcomes as usize;
+        check_condition!(from_index < n && to_index < n, InvalidOutcomeIndex);
+        check_condition!(from_index != to_index, InvalidOutcomeIndex);
+
+        // Trade on a copy so a rejected swap leaves the market untouched
+        let mut swapped = *self;
+        let net_refund = swapped.sell_outcome(from_index, burn_amount, vault_lamports, now)?;
+        let sell_fee = swapped.undistributed_fees - self.undistributed_fees;
+        let amount_out = swapped.buy_outcome(to_index, net_refund)?;
+        check_condition!(amount_out >= min_amount_out, SlippageExceeded);
+
+        *self = swapped;
+        Ok(SwapFill {
+            net_refund,
+            sell_fee,
+            amount_out,
+        })
+    }
+
+    /// Redeem `tokens` of `outcome_index` from a resolved market whose vault holds
+    /// `vault_lamports` on top of `rent_min`, returning the payout net of the settlement fee.
+    /// The payout must fit in the vault's [`Market::vault_backing`], so it never comes out of
+    /// fees or rent.
+    ///
+    /// The gross payout, see [`Market::settlement_payout`], leaves the reserves and the fee stays
+    /// in the vault. A void resolution draws each refund from that outcome's own reserve, while a
+    /// winner-takes-all payout draws from the shared pot starting with the winning reserve, so
+    /// later claims keep the same pro-rata share.
+    pub fn redeem(
+        &mut self,
+        outcome_index: usize,
+        tokens: u64,
+        vault_lamports: u64,
+        rent_min: u64,
+    ) -> Result<u64> {
+        let winner = self
+            .winning_outcome()
+            .ok_or(error!(ErrorCode::MarketNotResolved))?;
+        check_condition!(
+            outcome_index < self.num_outcomes as usize,
+            InvalidOutcomeIndex
+        );
+        let is_void = self.settles_as_void(winner);
+        check_condition!(is_void || outcome_index == winner, NotWinningOutcome);
+        check_condition!(tokens > 0, BurnIsZero);
+
+        let pot = self.settlement_pot()?;
+        let payout = self.settlement_payout(winner, outcome_index, tokens, pot)?;
+        check_condition!(payout > 0, BurnProducesNoRefund);
+        check_condition!(
+            self.vault_backing(vault_lamports, rent_min) >= payout,
+            InsufficientVaultFunds
+        );
+
+        if is_void {
+            self.reserves[outcome_index] = self.reserves[outcome_index]
+                .checked_sub(payout)
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+        } else {
+            let n = self.num_outcomes as usize;
+            let mut remaining = payout;
+            for i in std::iter::once(winner).chain((0..n).filter(|i| *i != winner)) {
+                let drawn = remaining.min(self.reserves[i]);
+                self.reserves[i] -= drawn;
+                remaining -= drawn;
+            }
+            check_condition!(remaining == 0, MathOverflow);
+        }
+        self.supplies[outcome_index] = self.supplies[outcome_index]
+            .checked_sub(tokens)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        self.recompute_invariant()?;
+
+        self.apply_settlement_fee(payout)
+    }
+
+    /// Lamports the vault would pay out to every holder if the market resolved to
+    /// `hypothetical_winner` right now, before the settlement fee.
+    ///
+    /// Each outcome's whole supply is valued with [`Market::settlement_payout`] against the current
+    /// [`Market::settlement_pot`]. Outcomes without holders owe nothing, so a winner with no supply leaves the
+    /// reserves unclaimed. Read only.
+    pub fn simulate_resolution(&self, hypothetical_winner: usize) -> Result<u64> {
+        let pot = self.settlement_pot()?;
+        let mut owed: u64 = 0;
+        for i in 0..self.num_outcomes as usize {
+            let payout = self.settlement_payout(hypothetical_winner, i, self.supplies[i], pot)?;
+            owed = owed
+                .checked_add(payout)
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+        }
+        Ok(owed)
+    }
+
+    /// Lamports a holder of `tokens_held` of `outcome_index` would recover if the market were
+    /// cancelled right now, net of the settlement fee.
+    ///
+    /// Cancelling is resolving to the void outcome, which refunds each holding its pro-rata share
+    /// of its own outcome's reserve. That is the same gross amount a sell would refund, but it pays
+    /// the settlement fee instead of the time-dependent sell fee, and it works whether or not the
+    /// market configured a void outcome. Read only.
+    pub fn cancel_recovery(&self, outcome_index: usize, tokens_held: u64) -> Result<u64> {
+        check_condition!(
+            outcome_index < self.num_outcomes as usize,
+            InvalidOutcomeIndex
+        );
+        let refund = self.gross_refund(outcome_index, tokens_held)?;
+        refund
+            .checked_sub(self.settlement_fee(refund)?)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    /// Most any single resolution could pay out to holders, before the settlement fee: the
+    /// winner-takes-all pot of every active reserve.
+    ///
+    /// Fees are already held outside the reserves, so nothing is deducted for them. A void
+    /// resolution refunds each outcome from its own reserve and never pays more. Read only, for
+    /// operators and auditors bounding vault outflow.
+    pub fn max_payout(&self) -> Result<u64> {
+        self.settlement_pot()
+    }
+
+    /// Profit or loss in lamports for a holder of `tokens_held` of `outcome_index` who paid
+    /// `cost_basis`, if the market were resolved to `hypothetical_winner` right now.
+    ///
+    /// The pot is the market's current [`Market::settlement_pot`] and the payout is net of the settlement
+    /// fee, matching what a claim would pay. Read only, for pre-resolution warnings in clients.
+    pub fn resolution_pnl(
+        &self,
+        outcome_index: usize,
+        tokens_held: u64,
+        cost_basis: u64,
+        hypothetical_winner: usize,
+    ) -> Result<i64> {
+        let pot = self.settlement_pot()?;
+        let payout =
+            self.settlement_payout(hypothetical_winner, outcome_index, tokens_held, pot)?;
+        let net = payout
+            .checked_sub(self.settlement_fee(payout)?)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+
+        let pnl = net as i128 - cost_basis as i128;
+        i64::try_from(pnl).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Effective leverage of holding `tokens_held` of `outcome_index` bought for `cost_basis`
+    /// lamports, scaled by 1e9 (1_000_000_000 = 1x).
+    ///
+    /// This is the multiple of the cost basis the position pays if its outcome wins, net of the
+    /// settlement fee. A position worth `p` of the pot moves by `1/p` for each unit the outcome's
+    /// probability moves, so positions bought cheaply where the outcome was unlikely carry the
+    /// most convexity. Read only, for risk displays in clients.
+    pub fn position_leverage(
+        &self,
+        outcome_index: usize,
+        tokens_held: u64,
+        cost_basis: u64,
+    ) -> Result<u64> {
+        check_condition!(cost_basis > 0, DepositIsZero);
+        let pot = self.settlement_pot()?;
+        let payout = self.settlement_payout(outcome_index, outcome_index, tokens_held, pot)?;
+        let net = payout
+            .checked_sub(self.settlement_fee(payout)?)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+
+        let leverage = (net as u128)
+            .checked_mul(D9_U128)
+            .ok_or(error!(ErrorCode::MathOverflow))?
+            / cost_basis as u128;
+        u64::try_from(leverage).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Value of `lamports` in a display currency, given the price of one SOL in that currency
+    /// scaled by 1e9. The result carries the same 1e9 scale, so at 150 USD per SOL
+    /// (`150_000_000_000`) one SOL is worth `150_000_000_000`, i.e. 150.000000000 USD.
+    ///
+    /// Pure arithmetic rounding down, and saturating at `u64::MAX`. Token-collateral markets pass
+    /// the collateral's base units and price instead. Read only, for front ends.
+    pub fn value_in_quote(&self, lamports: u64, sol_price_1e9: u64) -> u64 {
+        // One SOL is 1e9 lamports, which cancels the price's scale
+        let value = lamports as u128 * sol_price_1e9 as u128 / D9_U128;
+        value.min(u64::MAX as u128) as u64
+    }
+
+    /// Minimum true probability of `outcome_index` winning, scaled by 1e9, at which buying
+    /// `amount_in` now has non-negative expected value.
+    ///
+    /// Simulates the buy, then compares `amount_in` with what the minted tokens would pay if the
+    /// outcome won, net of the settlement fee: `amount_in / payout`, rounded up. A result above
+    /// 1e9 means the buy can't be profitable at any probability. Read only, for clients.
+    pub fn break_even_probability(&self, outcome_index: usize, amount_in: u64) -> Result<u64> {
+        let mut after = *self;
+        let tokens = after.buy_outcome(outcome_index, amount_in)?;
+        let pot = after.settlement_pot()?;
+        let payout = after.settlement_payout(outcome_index, outcome_index, tokens, pot)?;
+        let net = payout
+            .checked_sub(after.settlement_fee(payout)?)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        if net == 0 {
+            return Ok(u64::MAX);
+        }
+
+        let probability = (amount_in as u128)
+            .checked_mul(D9_U128)
+            .ok_or(error!(ErrorCode::MathOverflow))?
+            .div_ceil(net as u128);
+        Ok(probability.min(u64::MAX as u128) as u64)
+    }
+
+    /// Record a buy or sell fee left in the vault for the fee recipient
+    fn accrue_trading_fee(&mut self, fee: u64) -> Result<()> {
+        self.undistributed_fees = self
+            .undistributed_fees
+            .checked_add(fee)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        self.lifetime_fees = self
+            .lifetime_fees
+            .checked_add(fee)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        Ok(())
+    }
+
+    /// Deduct the settlement fee from a claim payout, adding it to
+    /// `undistributed_settlement_fees`. Returns the net payout owed to the claimant.
+    /// A zero `settlement_fee_bps` disables the fee.
+    pub fn apply_settlement_fee(&mut self, payout: u64) -> Result<u64> {
+        let fee_u64 = self.settlement_fee(payout)?;
+
+        self.undistributed_settlement_fees = self
+            .undistributed_settlement_fees
+            .checked_add(fee_u64)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        self.lifetime_fees = self
+            .lifetime_fees
+            .checked_add(fee_u64)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+
+        payout
+            .checked_sub(fee_u64)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    /// Move all `undistributed_fees` into the reserves, split pro-rata to each outcome's share
+    /// of total reserves, raising every holder's value instead of paying the fee recipient.
+    /// Once resolved, the reserves are the claimable pot, so the donation lands there.
+    ///
+    /// The lamports already sit in the vault, so only the accounting moves. Any rounding
+    /// remainder goes to the outcome with the largest reserve. Returns the lamports donated.
+    pub fn donate_fees(&mut self) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        let fees = self.undistributed_fees;
+        if fees == 0 {
+            return Ok(0);
+        }
+        let total = self.total_reserves_u128()?;
+        check_condition!(total > 0, ReserveIsZero);
+
+        let mut donated: u64 = 0;
+        let mut largest = 0;
+        for i in 0..n {
+            let share = u64::try_from(fees as u128 * self.reserves[i] as u128 / total)
+                .map_err(|_| error!(ErrorCode::MathOverflow))?;
+            if self.reserves[i] > self.reserves[largest] {
+                largest = i;
+            }
+            self.reserves[i] = self.reserves[i]
+                .checked_add(share)
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+            donated += share;
+        }
+        self.reserves[largest] = self.reserves[largest]
+            .checked_add(fees - donated)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+
+        self.undistributed_fees = 0;
+        self.recompute_invariant()?;
+        Ok(fees)
+    }
+
+    /// Lamports the vault owes: the full refund value of every outcome's reserve plus all
+    /// fees held on behalf of the fee recipient.
+    pub fn liabilities(&self) -> Result<u128> {
+        self.total_reserves_u128()?
+            .checked_add(self.undistributed_fees as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?
+            .checked_add(self.undistributed_settlement_fees as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))
+    }
+
+    /// Check the vault holds enough lamports to cover [`Market::liabilities`] on top of its
+    /// rent-exempt minimum. Rounding or lamports moved out of band could otherwise leave the
+    /// vault short, and accepting more buys would only deepen the hole.
+    pub fn assert_solvent(&self, vault_lamports: u64, rent_min: u64) -> Result<()> {
+        let required = self
+            .liabilities()?
+            .checked_add(rent_min as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        check_condition!(vault_lamports as u128 >= required, MarketInsolvent);
+        Ok(())
+    }
+
+    /// Whether [`Market::assert_solvent`] would pass, for callers that only need the answer.
+    /// Overflowing liabilities count as insolvent.
+    pub fn is_solvent(&self, vault_lamports: u64, rent_min: u64) -> bool {
+        self.assert_solvent(vault_lamports, rent_min).is_ok()
+    }
+
+    /// Vault balance alongside the market's liabilities, see [`VaultHealth`]
+    pub fn vault_health(&self, vault_lamports: u64, rent_min: u64) -> Result<VaultHealth> {
+        let sum_reserves = u64::try_from(self.total_reserves_u128()?)
+            .map_err(|_| error!(ErrorCode::MathOverflow))?;
+        Ok(VaultHealth {
+            vault_lamports,
+            sum_reserves,
+            undistributed_fees: self.undistributed_fees,
+            undistributed_settlement_fees: self.undistributed_settlement_fees,
+            rent_min,
+            is_solvent: self.is_solvent(vault_lamports, rent_min),
+        })
+    }
+
+    /// Reclaim collateral sent straight to the vault, e.g. SOL transferred to it by mistake.
+    ///
+    /// Anything beyond [`Market::liabilities`], the rent-exempt minimum and the backstop
+    /// [`Market::vault_funding`] backs nothing, so it is booked as `undistributed_fees` where the
+    /// fee recipient can claim it. Only the accounting moves. Returns the amount swept.
+    pub fn sweep_surplus(&mut self, vault_lamports: u64, rent_min: u64) -> Result<u64> {
+        let kept = self
+            .liabilities()?
+            .checked_add(rent_min as u128)
+            .and_then(|kept| kept.checked_add(self.vault_funding as u128))
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        let surplus = u64::try_from((vault_lamports as u128).saturating_sub(kept))
+            .map_err(|_| error!(ErrorCode::MathOverflow))?;
+
+        self.undistributed_fees = self
+            .undistributed_fees
+            .checked_add(surplus)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        Ok(surplus)
+    }
+
+    /// Fees `claim_fees` may pay the fee recipient from a vault holding `vault_lamports`: the
+    /// trading fees in `undistributed_fees` plus the settlement fees taken from claims.
+    ///
+    /// Both buckets are paid only if the vault still covers the reserves and its rent-exempt
+    /// minimum afterwards. If bookkeeping ever drifted so the recorded fees aren't actually backed
+    /// by surplus lamports, this errors rather than letting the payout eat into reserve capital
+    /// owed to traders.
+    pub fn withdrawable_fees(&self, vault_lamports: u64, rent_min: u64) -> Result<u64> {
+        let fees = self
+            .undistributed_fees
+            .checked_add(self.undistributed_settlement_fees)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        let remaining = vault_lamports
+            .checked_sub(fees)
+            .ok_or(error!(ErrorCode::WouldDrainReserves))?;
+
+        let required = self
+            .total_reserves_u128()?
+            .checked_add(rent_min as u128)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        check_condition!(remaining as u128 >= required, WouldDrainReserves);
+
+        Ok(fees)
+    }
+
+    /// Check the market attracted enough liquidity to be worth resolving.
+    /// A market below `min_resolve_liquidity` would settle dust claims, so it must be
+    /// cancelled and refunded instead.
+    pub fn check_resolve_liquidity(&self) -> Result<()> {
+        let total = self.total_reserves_u128()?;
+        check_condition!(
+            total >= self.min_resolve_liquidity as u128,
+            InsufficientResolveLiquidity
+        );
+        Ok(())
+    }
+
+    /// Check the market traded often enough to be worth resolving. Like a market below
+    /// `min_resolve_liquidity`, one with fewer than `min_trades_before_resolve` trades may have
+    /// been priced by a handful of wallets, so it must be cancelled and refunded instead.
+    pub fn check_resolve_activity(&self) -> Result<()> {
+        check_condition!(
+            self.trade_count >= self.min_trades_before_resolve as u64,
+            InsufficientResolveActivity
+        );
+        Ok(())
+    }
+
+    /// Compute normalized percentage of total liquidity for each outcome.
+    /// Returns [u64; MAX_OUTCOMES] where each value represents the percentage
+    /// of total reserves that outcome holds, scaled by 1e9 (i.e., 100% = 1_000_000_000).
+    ///
+    /// For example, if outcome 0 has 30% of total liquidity, the returned value
+    /// at index 0 would be 300_000_000.
+    ///
+    /// Each reserve is padded with `virtual_reserve`, which softens shares near 0% and 100%.
+    pub fn liquidity_percentages(&self) -> Result<[u64; MAX_OUTCOMES]> {
+        let n = self.num_outcomes as usize;
+        let total = self.pricing_total_reserves()?;
+
+        // Initialize result array with zeros
+        let mut percentages = [0u64; MAX_OUTCOMES];
+
+        // Handle edge case: if total is zero, all percentages are zero
+        if total == 0 {
+            return Ok(percentages);
+        }
+
+        // Compute percentage for each active outcome
+        // percentage = (reserve / total) * 1e9
+        // We use 1e9 scaling to maintain precision (100% = 1_000_000_000)
+
+        for (out, reserve) in percentages.iter_mut().zip(self.reserves.iter()).take(n) {
+            let percentage = (*reserve as u128 + self.virtual_reserve as u128)
+                .checked_mul(D9_U128)
+                .ok_or(error!(ErrorCode::MathOverflow))?
+                .checked_div(total)
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+
+            // Clamp to u64::MAX if somehow exceeds (shouldn't happen in practice)
+            *out = if percentage > u64::MAX as u128 {
+                u64::MAX
+            } else {
+                percentage as u64
+            };
+        }
+
+        Ok(percentages)
+    }
+
+    /// Rounding dust left by [`Market::liquidity_percentages`]: `1e9` minus the sum of the active
+    /// outcomes' shares, for clients that want to surface it or redistribute it.
+    ///
+    /// Each share is floored independently and loses less than one unit, so the residual is
+    /// between 0 and `num_outcomes - 1`. A market with no reserves has no shares and returns 0.
+    pub fn price_residual(&self) -> Result<i64> {
+        let percentages = self.liquidity_percentages()?;
+        let sum: u128 = percentages.iter().map(|p| *p as u128).sum();
+        if sum == 0 {
+            return Ok(0);
+        }
+        i64::try_from(D9_U128 as i128 - sum as i128).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Compute the implied probability of each outcome in basis points (10_000 = 100%).
+    /// Uses the same reserve weighting as [`Market::liquidity_percentages`], but rounds with the
+    /// largest-remainder method so the active outcomes always sum to exactly 10_000.
+    ///
+    /// Leftover basis points from flooring go to the outcomes with the largest remainders,
+    /// lowest index first on ties. If the market holds no reserves, all values are zero.
+    /// Like the percentages, each reserve is padded with `virtual_reserve`.
+    pub fn probabilities_bps(&self) -> Result<[u16; MAX_OUTCOMES]> {
+        let n = self.num_outcomes as usize;
+        let total = self.pricing_total_reserves()?;
+
+        let mut probabilities = [0u16; MAX_OUTCOMES];
+        if total == 0 {
+            return Ok(probabilities);
+        }
+
+        // Floor each share and keep the remainder to rank who gets the leftover bps
+        let mut remainders = [0u128; MAX_OUTCOMES];
+        let mut allocated: u64 = 0;
+        for i in 0..n {
+            let scaled = (self.reserves[i] as u128 + self.virtual_reserve as u128)
+                .checked_mul(BPS_DENOMINATOR as u128)
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+            let bps = (scaled / total) as u16;
+            probabilities[i] = bps;
+            remainders[i] = scaled % total;
+            allocated += bps as u64;
+        }
+
+        // Leftover is always < n, and there are always more non-zero remainders than leftover bps
+        let mut leftover = BPS_DENOMINATOR - allocated;
+        while leftover > 0 {
+            let mut largest = 0;
+            for i in 1..n {
+                if remainders[i] > remainders[largest] {
+                    largest = i;
+                }
+            }
+            probabilities[largest] += 1;
+            remainders[largest] = 0;
+            leftover -= 1;
+        }
+
+        Ok(probabilities)
+    }
+
+    /// Slope of the implied probability of `outcome_index` against lamports paid to buy it, at
+    /// the current state. Scaled by 1e18, i.e. the change in a 1e9-scaled probability per 1e9
+    /// lamports bought.
+    ///
+    /// A buy leaves [`Market::outcome_price`] unchanged, since it grows reserve and supply in
+    /// proportion, so what moves is the outcome's share of the reserves. With
+    /// `w = reserve_i + virtual_reserve` and `T` the padded total, a buy of `a` after the buy fee
+    /// moves the share `w / T` to `(w + a) / (T + a)`, whose slope at `a = 0` is
+    /// `(T - w) / T²`. A market with no reserves has no prices yet and returns 0.
+    pub fn price_elasticity(&self, outcome_index: usize) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        check_condition!(outcome_index < n, InvalidOutcomeIndex);
+
+        let total = self.pricing_total_reserves()?;
+        if total == 0 {
+            return Ok(0);
+        }
+        let weight = self.reserves[outcome_index] as u128 + self.virtual_reserve as u128;
+
+        let numerator = U256::from(total - weight)
+            * U256::from(D18_U128)
+            * U256::from(BPS_DENOMINATOR.saturating_sub(self.buy_fee_bps as u64));
+        let denominator = U256::from(total) * U256::from(total) * U256::from(BPS_DENOMINATOR);
+        let elasticity = numerator / denominator;
+        if elasticity > U256::from(u64::MAX) {
+            Ok(u64::MAX)
+        } else {
+            Ok(elasticity.as_u64())
+        }
+    }
+
+    /// Compute the marginal price for a given outcome.
+    /// This represents the cost per token based on the current reserve-to-supply ratio.
+    /// Returns a u64 scaled by 1e9 (i.e., price of 1.0 = 1_000_000_000).
+    ///
+    /// Formula: price = reserve_i / supply_i
+    ///
+    /// This gives each outcome an independent price that reflects its own liquidity,
+    /// minimizing cross-impact from other outcomes.
+    ///
+    /// For example:
+    /// - If reserve = 100M and supply = 100M tokens, price = 1.0 (1_000_000_000)
+    /// - If reserve = 200M and supply = 100M tokens, price = 2.0 (2_000_000_000)
+    pub fn outcome_price(&self, outcome_index: usize) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
+        check_condition!(outcome_index < n, InvalidOutcomeIndex);
+
+        let reserve = self.reserves[outcome_index] as u128;
+        let supply = self.supplies[outcome_index] as u128;
+
+        // Handle edge case: if supply is zero, return 0
+        if supply == 0 {
+            return Ok(0);
+        }
+
+        // Compute price: (reserve / supply) * 1e9
+        // This gives the average cost per token in lamports, scaled by 1e9
+        let price = reserve
             .checked_mul(D9_U128)
             .ok_or(error!(ErrorCode::MathOverflow))?
             .checked_div(supply)
@@ -401,4 +1756,263 @@ impl Market {
             Ok(price as u64)
         }
     }
+
+    /// Lamports a holder would get for selling one whole token (`10^decimals` base units) of
+    /// every active outcome, before the trading fee.
+    ///
+    /// Each outcome's value is floored, and an outcome with less than one whole token in
+    /// circulation is valued at its full reserve, so the total never exceeds what the
+    /// reserves can pay out.
+    pub fn full_set_value(&self) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
+        let one_token = 10u128
+            .checked_pow(self.decimals as u32)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+
+        let mut value: u128 = 0;
+        for (reserve, supply) in self.reserves.iter().zip(self.supplies.iter()).take(n) {
+            if *supply == 0 {
+                continue;
+            }
+            let tokens = one_token.min(*supply as u128);
+            let outcome_value = (*reserve as u128)
+                .checked_mul(tokens)
+                .ok_or(error!(ErrorCode::MathOverflow))?
+                / *supply as u128;
+            value = value
+                .checked_add(outcome_value)
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+        }
+
+        u64::try_from(value).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Lamports a buy of `outcome_index` needs to make it the sole favorite, or 0 if it
+    /// already leads.
+    ///
+    /// Buys leave an outcome's [`Market::outcome_price`] unchanged, so the lead is measured by
+    /// implied probability: the outcome's share of reserves, which a buy raises one lamport at
+    /// a time. Only the deposit net of the buy fee reaches the reserve, so the result is grossed
+    /// up by the fee, and it is at least [`Market::min_trade_amount`] so the buy is accepted.
+    pub fn cost_to_lead(&self, outcome_index: usize) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        check_condition!(outcome_index < n, InvalidOutcomeIndex);
+
+        // The first buy funds every opening reserve, and only its surplus puts it ahead
+        if self.is_empty() {
+            let needed = self
+                .opening_deposit()?
+                .checked_add(1)
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+            return self.gross_up_buy_fee(needed);
+        }
+
+        let reserve = self.reserves[outcome_index];
+        let max_other = self.reserves[..n]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != outcome_index)
+            .map(|(_, r)| *r)
+            .max()
+            .unwrap_or(0);
+        if reserve > max_other {
+            return Ok(0);
+        }
+
+        let needed = self.gross_up_buy_fee(max_other - reserve + 1)?;
+        Ok(needed.max(self.min_trade_amount()?))
+    }
+
+    /// Smallest deposit that still leaves `net` lamports entering the curve after the buy fee.
+    /// Errors if the fee takes the whole deposit, since no buy can then reach the curve.
+    fn gross_up_buy_fee(&self, net: u64) -> Result<u64> {
+        let fee_bps = self.buy_fee_bps as u64;
+        let kept_bps = BPS_DENOMINATOR.saturating_sub(fee_bps) as u128;
+        check_condition!(kept_bps > 0, TradeTooSmall);
+
+        let gross = (net as u128 * BPS_DENOMINATOR as u128).div_ceil(kept_bps);
+        let mut gross = u64::try_from(gross).map_err(|_| error!(ErrorCode::MathOverflow))?;
+        // Fee rounding can move the smallest such deposit a lamport either side of the closed form
+        while gross > 0 && gross - 1 - self.fee_at_bps(gross - 1, fee_bps)? >= net {
+            gross -= 1;
+        }
+        while gross - self.fee_at_bps(gross, fee_bps)? < net {
+            gross = gross
+                .checked_add(1)
+                .ok_or(error!(ErrorCode::MathOverflow))?;
+        }
+        Ok(gross)
+    }
+
+    /// Largest buy into `outcome_index`, in lamports, that keeps its
+    /// [`Market::outcome_price`] at or below `max_price_1e9`. Returns 0 if it is already above.
+    ///
+    /// Buys mint in proportion to the reserve added, so they leave the price where it is and
+    /// the bound only matters at the outset. Past that, a buy is limited by how far the
+    /// outcome's supply and reserve can grow: the supply up to u64, and the reserve up to u64 and
+    /// the point at which the U256 invariant would overflow. The bound is on the deposit, so the
+    /// buy fee it pays on top of the reserve growth is included, saturating at u64.
+    pub fn max_buy_within_price(&self, outcome_index: usize, max_price_1e9: u64) -> Result<u64> {
+        let price = self.outcome_price(outcome_index)?;
+        if price > max_price_1e9 {
+            return Ok(0);
+        }
+
+        let reserve = self.reserves[outcome_index] as u128;
+        let supply = self.supplies[outcome_index] as u128;
+        let mut max_reserve = U256::from(u64::MAX);
+        let others = self.product_except(outcome_index)?;
+        if !others.is_zero() {
+            max_reserve = max_reserve.min(U256::MAX / others);
+        }
+        let reserve_headroom = max_reserve.as_u128().saturating_sub(reserve);
+        if supply == 0 || reserve == 0 {
+            return self.max_deposit_for_net(reserve_headroom as u64);
+        }
+
+        // tokens minted = supply × amount / reserve, which must keep the supply within u64
+        let supply_headroom = (u64::MAX as u128).saturating_sub(supply);
+        let amount = supply_headroom
+            .checked_mul(reserve)
+            .ok_or(error!(ErrorCode::MathOverflow))?
+            / supply;
+        self.max_deposit_for_net(amount.min(reserve_headroom) as u64)
+    }
+
+    /// Largest deposit that leaves at most `net` lamports entering the curve after the buy fee,
+    /// saturating at u64. A fee taking the whole deposit allows none.
+    fn max_deposit_for_net(&self, net: u64) -> Result<u64> {
+        if self.buy_fee_bps as u64 >= BPS_DENOMINATOR {
+            return Ok(0);
+        }
+        // The net amount never falls as the deposit grows, so this is one below the smallest
+        // deposit netting `net + 1`
+        match net.checked_add(1).map(|next| self.gross_up_buy_fee(next)) {
+            Some(Ok(gross)) => Ok(gross - 1),
+            _ => Ok(u64::MAX),
+        }
+    }
+
+    /// Exact tokens a buy of `amount_in` lamports into `outcome_index` would mint right now.
+    ///
+    /// Runs [`Market::buy_outcome`] on a copy of the market, so the quote always matches the
+    /// real buy and fails for the same reasons, without touching reserves, supplies or the
+    /// invariant.
+    pub fn quote_buy(&self, outcome_index: usize, amount_in: u64) -> Result<u64> {
+        let mut after = *self;
+        after.buy_outcome(outcome_index, amount_in)
+    }
+
+    /// Exact net lamports a sell of `burn_amount` of `outcome_index` at `now` would pay out.
+    ///
+    /// Runs [`Market::sell_outcome`] on a copy of the market, so the quote includes the sell fee
+    /// in effect at `now` and fails for the same reasons as the sell. The vault balance isn't
+    /// checked, as a healthy vault always covers the refund.
+    pub fn quote_sell(&self, outcome_index: usize, burn_amount: u64, now: i64) -> Result<u64> {
+        let mut after = *self;
+        after.sell_outcome(outcome_index, burn_amount, u64::MAX, now)
+    }
+
+    /// Tokens of `outcome_index` to burn at `now` so the sell nets at least
+    /// `desired_net_lamports` after the sell fee, the inverse of [`Market::quote_sell`].
+    ///
+    /// Inverts `refund = reserve × burn / supply` and the fee in closed form, rounding up, so
+    /// selling the result always nets the target. Fee rounding can make it overshoot by at most
+    /// a token, since burning one fewer nets no more than the target. Errors if even the whole
+    /// supply falls short.
+    pub fn tokens_for_payout(
+        &self,
+        outcome_index: usize,
+        desired_net_lamports: u64,
+        now: i64,
+    ) -> Result<u64> {
+        let n = self.num_outcomes as usize;
+        check_condition!(outcome_index < n, InvalidOutcomeIndex);
+        if desired_net_lamports == 0 {
+            return Ok(0);
+        }
+        let supply = self.supplies[outcome_index] as u128;
+        let reserve = self.reserves[outcome_index];
+        check_condition!(supply > 0, SupplyIsZero);
+
+        // Burning everything refunds the whole reserve
+        let fee_bps = self.sell_fee_bps(now);
+        let max_net = reserve
+            .checked_sub(self.fee_at_bps(reserve, fee_bps)?)
+            .ok_or(error!(ErrorCode::MathOverflow))?;
+        check_condition!(desired_net_lamports <= max_net, PayoutUnreachable);
+
+        // Smallest refund whose pre-rounding net reaches the target. A reachable target means
+        // the fee is below 100%, so `kept_bps` is non-zero.
+        let kept_bps = BPS_DENOMINATOR.saturating_sub(fee_bps) as u128;
+        let refund = (desired_net_lamports as u128 * BPS_DENOMINATOR as u128).div_ceil(kept_bps);
+        let burn = (refund * supply).div_ceil(reserve as u128).min(supply);
+        u64::try_from(burn).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Estimate the tokens `amount_in` lamports buys of `outcome_index` as
+    /// `(amount_in - buy fee) / outcome_price`, without running the buy math.
+    ///
+    /// This is an estimate for instant client feedback only, use [`Market::quote_buy`] for the
+    /// exact figure. An empty market, which the buy opens at a price of `1 / num_outcomes`, mints
+    /// about `num_outcomes` tokens per lamport. An outcome with no supply can't be bought and an
+    /// invalid index estimates 0.
+    pub fn approx_tokens_out(&self, outcome_index: usize, amount_in: u64) -> u64 {
+        let fee = self
+            .fee_at_bps(amount_in, self.buy_fee_bps as u64)
+            .unwrap_or(amount_in);
+        let amount_in = amount_in.saturating_sub(fee);
+        let price = match self.outcome_price(outcome_index) {
+            Ok(0) if self.is_empty() => {
+                return amount_in.saturating_mul(self.num_outcomes as u64);
+            }
+            Ok(0) | Err(_) => return 0,
+            Ok(price) => price,
+        };
+        let tokens = amount_in as u128 * D9_U128 / price as u128;
+        tokens.min(u64::MAX as u128) as u64
+    }
+
+    /// Record the post-trade price of `outcome_index` in `last_price` and count the trade.
+    /// Every `invariant_recheck_interval` trades the invariant is checked against a full
+    /// recompute, see [`Market::check_invariant`].
+    fn record_trade(&mut self, outcome_index: usize) -> Result<()> {
+        self.last_price[outcome_index] = self.outcome_price(outcome_index)?;
+        self.trade_count = self.trade_count.saturating_add(1);
+        let interval = self.invariant_recheck_interval as u64;
+        if self.trade_count.checked_rem(interval) == Some(0) {
+            self.check_invariant()?;
+        }
+        Ok(())
+    }
+
+    /// Compute [`Market::outcome_price`] for every outcome in a single pass.
+    /// Inactive outcomes, and active outcomes with no supply, are 0.
+    ///
+    /// Each price is that outcome's own reserve over its supply, so they don't sum to 1e9; for
+    /// shares of the market that do, see [`Market::liquidity_percentages`].
+    pub fn all_marginal_prices(&self) -> Result<[u64; MAX_OUTCOMES]> {
+        let n = self.num_outcomes as usize;
+        check_condition!(n <= MAX_OUTCOMES, InvalidOutcomeIndex);
+
+        let mut prices = [0u64; MAX_OUTCOMES];
+        for ((price, reserve), supply) in prices
+            .iter_mut()
+            .zip(self.reserves.iter())
+            .zip(self.supplies.iter())
+            .take(n)
+        {
+            if *supply == 0 {
+                continue;
+            }
+            let scaled = (*reserve as u128)
+                .checked_mul(D9_U128)
+                .ok_or(error!(ErrorCode::MathOverflow))?
+                / *supply as u128;
+            *price = scaled.min(u64::MAX as u128) as u64;
+        }
+
+        Ok(prices)
+    }
 }
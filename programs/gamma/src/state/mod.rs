@@ -1,3 +1,7 @@
+pub mod claim_escrow;
 pub mod market;
+pub mod program_config;
 
+pub use claim_escrow::*;
 pub use market::*;
+pub use program_config::*;
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Program-wide settings, a single PDA shared by every market.
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramConfig {
+    /// Key allowed to change the program-wide settings
+    pub admin: Pubkey,
+
+    /// While set, [`init_market`](crate::gamma::init_market) rejects new markets.
+    /// Existing markets keep trading.
+    pub market_creation_paused: bool,
+
+    pub bump: u8,
+}
+
+impl ProgramConfig {
+    pub const SIZE: usize = 8 + ProgramConfig::INIT_SPACE;
+}
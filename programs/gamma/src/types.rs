@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 use bytemuck::{Pod, Zeroable};
+use common::check_condition;
+use common::constants::{MARKET_SEED, MAX_OUTCOMES};
+use common::errors::ErrorCode;
 
 /// The maximum length of a fixed size string in bytes.
 pub const MAX_PADDED_STRING_LENGTH: usize = 32;
@@ -46,4 +49,296 @@ impl FixedSizeString {
     pub fn as_bytes(&self) -> &[u8] {
         &self.value
     }
+
+    /// Check the label is fit to display: printable ASCII, not blank, and zero padded after the
+    /// text with no stray bytes behind the padding.
+    pub fn validate(&self) -> Result<()> {
+        let len = self
+            .value
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(MAX_PADDED_STRING_LENGTH);
+        let (text, padding) = self.value.split_at(len);
+
+        check_condition!(
+            text.iter().any(|b| !b.is_ascii_whitespace()),
+            InvalidLabelLength
+        );
+        check_condition!(
+            text.iter().all(|b| b.is_ascii_graphic() || *b == b' '),
+            InvalidLabelCharacters
+        );
+        check_condition!(padding.iter().all(|b| *b == 0), InvalidLabelCharacters);
+        Ok(())
+    }
+
+    /// Derive the market PDA for this label and nonce.
+    /// Seeds with the full padded bytes, exactly as `init_market` does, so callers holding a
+    /// `&str` should convert with [`FixedSizeString::new`] first rather than seeding the raw str.
+    ///
+    /// # Arguments
+    /// * `nonce`: The market nonce, 0 for the first market with this label.
+    /// * `program_id`: The gamma program id.
+    ///
+    /// # Returns
+    /// * `(Pubkey, u8)`: The market PDA and its bump.
+    pub fn market_pda(&self, nonce: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+        let nonce = nonce.to_le_bytes();
+        Pubkey::find_program_address(
+            &[MARKET_SEED, self.as_bytes(), market_nonce_seed(&nonce)],
+            program_id,
+        )
+    }
+
+    /// Signer seeds for the market PDA derived from this label.
+    /// Every CPI the market signs must use these so the signature matches the address
+    /// `init_market` derived from the same padded bytes.
+    ///
+    /// # Arguments
+    /// * `nonce`: The market nonce as little endian bytes.
+    /// * `bump`: The market PDA bump, as a one byte slice.
+    pub fn market_signer_seeds<'a>(&'a self, nonce: &'a [u8; 8], bump: &'a [u8]) -> [&'a [u8]; 4] {
+        [MARKET_SEED, self.as_bytes(), market_nonce_seed(nonce), bump]
+    }
+}
+
+/// Market PDA seed for a nonce given as little endian bytes.
+/// Nonce 0 is an empty seed, which hashes the same as no seed at all, so the first market for a
+/// label keeps the address it had before nonces existed.
+pub fn market_nonce_seed(nonce: &[u8; 8]) -> &[u8] {
+    if *nonce == [0u8; 8] {
+        &[]
+    } else {
+        nonce
+    }
+}
+
+/// Narrow an outcome index to the u8 used in outcome mint seeds and market fields, rejecting
+/// anything outside [`MAX_OUTCOMES`] instead of letting the cast wrap.
+pub fn outcome_index_u8(index: usize) -> Result<u8> {
+    check_condition!(index < MAX_OUTCOMES, InvalidOutcomeIndex);
+    u8::try_from(index).map_err(|_| error!(ErrorCode::InvalidOutcomeIndex))
+}
+
+/// Who may make the first buy into a market with no reserves, which sets its opening prices.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+#[repr(u8)]
+pub enum InitializationMode {
+    /// Only the admin may buy until the market holds reserves, so no user can game the
+    /// opening prices.
+    #[default]
+    RequireSeed = 0,
+    /// Anyone may make the first buy.
+    Open = 1,
+}
+
+/// Direction fees are rounded when a basis point fee doesn't divide evenly.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+#[repr(u8)]
+pub enum FeeRounding {
+    /// Round the fee down, favoring the trader
+    #[default]
+    Floor = 0,
+    /// Round the fee up, favoring the protocol by at most one lamport per fee
+    Ceil = 1,
+}
+
+/// Lifecycle phase of a market, see [`Market::phase`](crate::state::Market::phase).
+///
+/// There is no closed phase: [`close_market`](crate::gamma::close_market) deletes the market
+/// account, so a closed market can't be loaded to report one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum MarketPhase {
+    /// Waiting for the admin's seed buy before public buys open
+    PreOpen,
+    /// Buys and sells are open
+    Trading,
+    /// From `resolve_at` until the post-expiry sell grace ends: buys are closed but holders may
+    /// still sell. Skipped when there is no grace.
+    SellOnly,
+    /// From the end of the sell grace: all trading has stopped and the market is waiting for the
+    /// admin to resolve it
+    Resolving,
+    /// Resolved to a winning outcome, whose holders redeem the pot
+    Resolved,
+    /// Resolved to the void outcome, which refunds every holder from their outcome's reserve
+    Cancelled,
+}
+
+/// Optional per-market settings chosen by the admin at [`init_market`](crate::gamma::init_market).
+///
+/// Every field defaults to zero, which leaves the corresponding feature disabled.
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
+pub struct MarketConfig {
+    /// Minimum total reserves (lamports) the market must hold before it can be resolved.
+    /// Markets below this must be cancelled and refunded instead.
+    pub min_resolve_liquidity: u64,
+
+    /// Buys and sells the market must see before it can be resolved, so a thinly traded market
+    /// is cancelled and refunded instead. 0 disables it.
+    pub min_trades_before_resolve: u32,
+
+    /// Tradable "none of the above" outcome. If it wins, every holder is refunded at
+    /// liquidation value instead of the winner taking the pot.
+    pub void_outcome: Option<u8>,
+
+    /// Fee in basis points taken from each winning claim, separate from the trading fee
+    pub settlement_fee_bps: u16,
+
+    /// Smallest buy accepted, as basis points of the market's total reserves.
+    /// Protects users from trades dominated by rounding and fees.
+    pub min_trade_fraction_bps: u16,
+
+    /// Reject new buys while the vault cannot cover its existing liabilities.
+    /// Sells stay open so holders can still exit.
+    pub enforce_solvency: bool,
+
+    /// Seconds after `resolve_at` that holders may keep selling on the curve while the market
+    /// awaits resolution, so a slow resolution doesn't trap them. 0 closes sells at `resolve_at`.
+    pub post_expiry_sell_grace: u32,
+
+    /// Lamports the admin deposits into the vault at init on top of its rent-exempt minimum.
+    /// They back nothing and can't be withdrawn, so they stay as a backstop for payouts.
+    pub min_vault_funding: u64,
+
+    /// Extra market PDA seed so an admin can reuse a label, e.g. a weekly "BTC price" market.
+    /// 0 derives the same address as a market created without a nonce.
+    pub nonce: u64,
+
+    /// Whether the admin must seed the market before public buys are accepted
+    pub initialization_mode: InitializationMode,
+
+    /// Seconds before `resolve_at` over which the sell fee rises from the base fee to
+    /// `sell_fee_max_bps`, discouraging last-minute dumping. 0 keeps the fee flat.
+    pub sell_fee_ramp_secs: u32,
+
+    /// Base sell fee in basis points, defaults to [`FEE_BPS`](common::constants::FEE_BPS)
+    pub fee_bps: Option<u16>,
+
+    /// Fee in basis points deducted from each buy's `amount_in` before the curve math,
+    /// accrued with the sell fees. 0 disables it.
+    pub buy_fee_bps: u16,
+
+    /// Sell fee in basis points at `resolve_at`, must be at least the base fee if ramping
+    pub sell_fee_max_bps: u16,
+
+    /// How trading and settlement fees round
+    pub fee_rounding: FeeRounding,
+
+    /// Account accrued trading fees are claimed to, defaults to the admin
+    pub fee_recipient: Option<Pubkey>,
+
+    /// Lamports added to each reserve when computing implied probabilities, dampening prices
+    /// near 0 and 1 when one outcome dominates. Payouts still use the real reserves.
+    pub virtual_reserve: u64,
+
+    /// SPL mint to trade in instead of native SOL, e.g. USDC. Reserves and fees are then held by
+    /// the market's collateral token account and every amount is in the mint's base units.
+    pub collateral_mint: Option<Pubkey>,
+
+    /// Reject buys that would push the outcome's implied probability, scaled by 1e9, above this,
+    /// e.g. 990_000_000 for 0.99. A buy at probability `p` pays about `amount / p` if it wins, so
+    /// near 1.0 users risk their whole deposit for almost no upside. [`outcome_price`] is not used, since a buy
+    /// grows reserve and supply together and leaves it unchanged. The opening buy of an unseeded
+    /// market is measured after it funds every outcome's opening reserve. 0 disables it.
+    ///
+    /// [`outcome_price`]: crate::state::Market::outcome_price
+    pub max_buyable_price: u64,
+
+    /// Every this many trades, rebuild the invariant from all reserves and fail with
+    /// `InvariantDrift` if the incrementally updated one no longer matches. 0 disables it.
+    pub invariant_recheck_interval: u8,
+
+    /// Seconds after `resolve_at` that winnings stay locked. When set, claims must go through
+    /// [`claim_to_escrow`](crate::gamma::claim_to_escrow), which holds them in a per-user escrow
+    /// until [`release_escrow`](crate::gamma::release_escrow) pays them out. 0 pays claims directly.
+    pub claim_vesting_secs: u32,
+}
+
+/// One buy in a [`batch_buy`](crate::gamma::batch_buy), with the same arguments as
+/// [`buy`](crate::gamma::buy)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct BuyLeg {
+    pub outcome_index: u8,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+}
+
+/// What [`Market::swap_outcome`](crate::state::Market::swap_outcome) did: the sell's net refund
+/// and fee, and the tokens of the other outcome the refund bought
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapFill {
+    /// Lamports the sell refunded after its fee, all of them reinvested in the buy
+    pub net_refund: u64,
+    /// Lamports the sell kept in the vault as trading fees
+    pub sell_fee: u64,
+    /// Tokens of the bought outcome minted
+    pub amount_out: u64,
+}
+
+/// One market in an [`init_market_batch`](crate::gamma::init_market_batch), with the same
+/// arguments as [`init_market`](crate::gamma::init_market) takes besides its config
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
+pub struct MarketSpec {
+    pub label: FixedSizeString,
+    pub num_outcomes: u8,
+    pub scale: u64,
+    pub resolve_at: i64,
+}
+
+/// Point-in-time view of a [`Market`](crate::state::Market) returned by
+/// [`get_snapshot`](crate::gamma::get_snapshot), so clients can read the tracked state in one
+/// call instead of fetching the market and every outcome mint.
+///
+/// Per-outcome vectors only contain the market's active outcomes.
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct MarketSnapshot {
+    pub num_outcomes: u8,
+    pub resolve_at: i64,
+    /// Slot the market was created in
+    pub created_slot: u64,
+    pub reserves: Vec<u64>,
+    /// Circulating supply of each outcome mint, as tracked by the market
+    pub supplies: Vec<u64>,
+    /// Sum of `supplies`, see [`Market::total_supply`](crate::state::Market::total_supply)
+    pub total_supply: u128,
+    /// Ceiling on what resolution can pay out, see
+    /// [`Market::max_payout`](crate::state::Market::max_payout)
+    pub max_payout: u64,
+    pub undistributed_fees: u64,
+    /// Cumulative fee revenue, unaffected by withdrawals
+    pub lifetime_fees: u64,
+}
+
+/// Vault balance against what the market owes, returned by
+/// [`get_vault_health`](crate::gamma::get_vault_health) for monitoring and alerting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct VaultHealth {
+    /// Current lamport balance of the market vault
+    pub vault_lamports: u64,
+    /// Sum of active reserves
+    pub sum_reserves: u64,
+    /// Trading fees held in the vault for the admin
+    pub undistributed_fees: u64,
+    /// Settlement fees held in the vault for the admin
+    pub undistributed_settlement_fees: u64,
+    /// Rent-exempt minimum the vault must keep
+    pub rent_min: u64,
+    /// Whether the vault covers every liability on top of its rent-exempt minimum,
+    /// see [`Market::assert_solvent`](crate::state::Market::assert_solvent)
+    pub is_solvent: bool,
+}
+
+/// Result of [`crate::instructions::verify_and_repair`], comparing the market's records against
+/// the outcome mints and the vault.
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct ReconcileReport {
+    /// Active outcomes whose recorded supply differed from the real mint supply
+    pub supply_mismatches: Vec<u8>,
+    /// Sum of active reserves
+    pub reserves_total: u64,
+    /// Vault lamports backing reserves: the balance less recorded fees and rent
+    pub vault_backing: u64,
+    /// Whether mismatched supplies were overwritten with the mint supplies
+    pub repaired: bool,
 }
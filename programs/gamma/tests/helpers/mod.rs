@@ -0,0 +1,989 @@
+//! Shared LiteSVM setup for the gamma integration tests.
+#![allow(dead_code, clippy::result_large_err)]
+
+use anchor_lang::{
+    prelude::AccountMeta, solana_program::instruction::Instruction, system_program,
+    AccountDeserialize, AnchorDeserialize, Discriminator, Event, InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::{get_associated_token_address, spl_associated_token_account};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use common::constants::{
+    COLLATERAL_VAULT_SEED, ESCROW_SEED, OUTCOME_MINT_SEED, PROGRAM_CONFIG_SEED, VAULT_SEED,
+};
+use common::errors::ErrorCode;
+use gamma::state::Market;
+use gamma::types::{
+    BuyLeg, FixedSizeString, InitializationMode, MarketConfig, MarketSnapshot, MarketSpec,
+};
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_sdk::{
+    account::Account,
+    account_utils::StateMut,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signer::keypair::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+pub const AIRDROP_LAMPORTS: u64 = 100_000_000_000;
+pub const DEFAULT_SCALE: u64 = 100_000;
+pub const DEFAULT_RESOLVE_AT: i64 = 1_000;
+
+/// Addresses of a market and the PDAs derived from it
+pub struct MarketAccounts {
+    pub label: FixedSizeString,
+    pub market: Pubkey,
+    pub market_vault: Pubkey,
+    pub outcome_mints: Vec<Pubkey>,
+    /// Set for markets trading an SPL token instead of SOL
+    pub collateral_mint: Option<Pubkey>,
+}
+
+impl MarketAccounts {
+    pub fn derive(label: &str, num_outcomes: u8) -> Self {
+        Self::derive_with_nonce(label, 0, num_outcomes)
+    }
+
+    pub fn derive_with_nonce(label: &str, nonce: u64, num_outcomes: u8) -> Self {
+        let program_id = gamma::id();
+        let label = FixedSizeString::new(label);
+        let market = label.market_pda(nonce, &program_id).0;
+        let market_vault =
+            Pubkey::find_program_address(&[VAULT_SEED, market.as_ref()], &program_id).0;
+        let outcome_mints = (0..num_outcomes)
+            .map(|i| {
+                Pubkey::find_program_address(
+                    &[OUTCOME_MINT_SEED, market.as_ref(), &[i]],
+                    &program_id,
+                )
+                .0
+            })
+            .collect();
+
+        Self {
+            label,
+            market,
+            market_vault,
+            outcome_mints,
+            collateral_mint: None,
+        }
+    }
+
+    pub fn user_token_account(&self, user: &Pubkey, outcome_index: u8) -> Pubkey {
+        get_associated_token_address(user, &self.outcome_mints[outcome_index as usize])
+    }
+
+    /// The market's collateral token account, for token-collateral markets
+    pub fn collateral_vault(&self) -> Option<Pubkey> {
+        self.collateral_mint.map(|_| {
+            Pubkey::find_program_address(
+                &[COLLATERAL_VAULT_SEED, self.market.as_ref()],
+                &gamma::id(),
+            )
+            .0
+        })
+    }
+
+    /// The owner's collateral ATA, for token-collateral markets
+    pub fn collateral_account(&self, owner: &Pubkey) -> Option<Pubkey> {
+        self.collateral_mint
+            .map(|mint| get_associated_token_address(owner, &mint))
+    }
+
+    /// Token program for instructions that only need it to move collateral tokens
+    fn collateral_token_program(&self) -> Option<Pubkey> {
+        self.collateral_mint.map(|_| anchor_spl::token::ID)
+    }
+}
+
+/// Create a LiteSVM instance with the gamma program loaded and its program config created
+pub fn setup() -> LiteSVM {
+    setup_with_program_admin().0
+}
+
+/// Like [`setup`], also returning the admin of the program config, who is also the program's
+/// upgrade authority
+pub fn setup_with_program_admin() -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    let program_admin = funded_keypair(&mut svm);
+    deploy_program(&mut svm, &program_admin.pubkey());
+
+    send(
+        &mut svm,
+        &[init_program_config_ix(&program_admin.pubkey())],
+        &program_admin,
+        &[&program_admin],
+    )
+    .unwrap();
+    (svm, program_admin)
+}
+
+/// Deploy gamma through the upgradeable loader with `upgrade_authority`, as a real deploy would
+pub fn deploy_program(svm: &mut LiteSVM, upgrade_authority: &Pubkey) {
+    let elf = include_bytes!("../../../../target/deploy/gamma.so");
+    let programdata_address = program_data_address();
+    set_program_data(svm, &programdata_address, upgrade_authority, elf);
+
+    let size = UpgradeableLoaderState::size_of_program();
+    let mut program = Account {
+        lamports: svm.minimum_balance_for_rent_exemption(size),
+        data: vec![0; size],
+        owner: bpf_loader_upgradeable::id(),
+        executable: true,
+        rent_epoch: 0,
+    };
+    program
+        .set_state(&UpgradeableLoaderState::Program {
+            programdata_address,
+        })
+        .unwrap();
+    svm.set_account(gamma::id(), program).unwrap();
+}
+
+/// Program data account the upgradeable loader keeps gamma's ELF and upgrade authority in
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[gamma::id().as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Write an upgradeable loader program data account at `address` holding `elf`
+pub fn set_program_data(
+    svm: &mut LiteSVM,
+    address: &Pubkey,
+    upgrade_authority: &Pubkey,
+    elf: &[u8],
+) {
+    let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+    let mut program_data = Account {
+        lamports: svm.minimum_balance_for_rent_exemption(metadata_len + elf.len()),
+        data: vec![0; metadata_len + elf.len()],
+        owner: bpf_loader_upgradeable::id(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    program_data
+        .set_state(&UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(*upgrade_authority),
+        })
+        .unwrap();
+    program_data.data[metadata_len..].copy_from_slice(elf);
+    svm.set_account(*address, program_data).unwrap();
+}
+
+pub fn program_config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM_CONFIG_SEED], &gamma::id()).0
+}
+
+pub fn init_program_config_ix(admin: &Pubkey) -> Instruction {
+    init_program_config_ix_with_program_data(admin, &program_data_address())
+}
+
+pub fn init_program_config_ix_with_program_data(
+    admin: &Pubkey,
+    program_data: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::InitProgramConfig {}.data(),
+        gamma::accounts::InitProgramConfig {
+            admin: *admin,
+            program: gamma::id(),
+            program_data: *program_data,
+            program_config: program_config_pda(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn set_market_creation_paused_ix(admin: &Pubkey, paused: bool) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::SetMarketCreationPaused { paused }.data(),
+        gamma::accounts::SetMarketCreationPaused {
+            admin: *admin,
+            program_config: program_config_pda(),
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn funded_keypair(svm: &mut LiteSVM) -> Keypair {
+    let keypair = Keypair::new();
+    svm.airdrop(&keypair.pubkey(), AIRDROP_LAMPORTS).unwrap();
+    keypair
+}
+
+/// Sign and send a transaction, expiring the blockhash first so repeated identical
+/// instructions are not rejected as duplicates.
+pub fn send(
+    svm: &mut LiteSVM,
+    ixs: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> TransactionResult {
+    svm.expire_blockhash();
+    let tx = Transaction::new_signed_with_payer(
+        ixs,
+        Some(&payer.pubkey()),
+        signers,
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx)
+}
+
+pub fn init_market_ix(
+    admin: &Pubkey,
+    accounts: &MarketAccounts,
+    scale: u64,
+    resolve_at: i64,
+    config: MarketConfig,
+) -> Instruction {
+    let mut accounts_ctx = gamma::accounts::InitMarket {
+        system_program: system_program::ID,
+        rent: anchor_lang::solana_program::sysvar::rent::ID,
+        token_program: anchor_spl::token::ID,
+        admin: *admin,
+        program_config: program_config_pda(),
+        market: accounts.market,
+        market_vault: accounts.market_vault,
+        collateral_mint: accounts.collateral_mint,
+        collateral_vault: accounts.collateral_vault(),
+    }
+    .to_account_metas(None);
+    for mint in &accounts.outcome_mints {
+        accounts_ctx.push(AccountMeta {
+            pubkey: *mint,
+            is_signer: false,
+            is_writable: true,
+        });
+    }
+
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::InitMarket {
+            num_outcomes: accounts.outcome_mints.len() as u8,
+            scale,
+            resolve_at,
+            label: accounts.label,
+            config,
+        }
+        .data(),
+        accounts_ctx,
+    )
+}
+
+/// Create every market in `markets` with the default config in one `init_market_batch`
+pub fn init_market_batch_ix(
+    admin: &Pubkey,
+    markets: &[MarketAccounts],
+    scale: u64,
+    resolve_at: i64,
+) -> Instruction {
+    let mut accounts_ctx = gamma::accounts::InitMarketBatch {
+        system_program: system_program::ID,
+        rent: anchor_lang::solana_program::sysvar::rent::ID,
+        token_program: anchor_spl::token::ID,
+        admin: *admin,
+        program_config: program_config_pda(),
+    }
+    .to_account_metas(None);
+    for accounts in markets {
+        for pubkey in [accounts.market, accounts.market_vault]
+            .iter()
+            .chain(&accounts.outcome_mints)
+        {
+            accounts_ctx.push(AccountMeta {
+                pubkey: *pubkey,
+                is_signer: false,
+                is_writable: true,
+            });
+        }
+    }
+
+    let specs = markets
+        .iter()
+        .map(|accounts| MarketSpec {
+            label: accounts.label,
+            num_outcomes: accounts.outcome_mints.len() as u8,
+            scale,
+            resolve_at,
+        })
+        .collect();
+
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::InitMarketBatch { specs }.data(),
+        accounts_ctx,
+    )
+}
+
+/// Default config, except any user may make the first buy so tests can trade immediately
+pub fn open_config() -> MarketConfig {
+    MarketConfig {
+        initialization_mode: InitializationMode::Open,
+        ..Default::default()
+    }
+}
+
+/// Initialize an open market with the default scale and panic if it fails
+pub fn init_market(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    label: &str,
+    num_outcomes: u8,
+    resolve_at: i64,
+) -> MarketAccounts {
+    init_market_with_config(svm, admin, label, num_outcomes, resolve_at, open_config())
+}
+
+pub fn init_market_with_config(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    label: &str,
+    num_outcomes: u8,
+    resolve_at: i64,
+    config: MarketConfig,
+) -> MarketAccounts {
+    let mut accounts = MarketAccounts::derive_with_nonce(label, config.nonce, num_outcomes);
+    accounts.collateral_mint = config.collateral_mint;
+    let ix = init_market_ix(
+        &admin.pubkey(),
+        &accounts,
+        DEFAULT_SCALE,
+        resolve_at,
+        config,
+    );
+    send(svm, &[ix], admin, &[admin]).unwrap();
+    accounts
+}
+
+pub fn buy_ix(
+    user: &Pubkey,
+    accounts: &MarketAccounts,
+    outcome_index: u8,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let accounts_ctx = gamma::accounts::Buy {
+        user: *user,
+        market: accounts.market,
+        market_vault: accounts.market_vault,
+        outcome_mint: accounts.outcome_mints[outcome_index as usize],
+        user_outcome_token_account: accounts.user_token_account(user, outcome_index),
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        collateral_vault: accounts.collateral_vault(),
+        user_collateral_account: accounts.collateral_account(user),
+    }
+    .to_account_metas(None);
+
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::Buy {
+            outcome_index,
+            amount_in,
+            min_amount_out,
+        }
+        .data(),
+        accounts_ctx,
+    )
+}
+
+/// Like [`buy_ix`] for the buy that opens an empty market, passing the user's token account for
+/// every other outcome to receive its opening supply
+pub fn opening_buy_ix(
+    user: &Pubkey,
+    accounts: &MarketAccounts,
+    outcome_index: u8,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let mut ix = buy_ix(user, accounts, outcome_index, amount_in, min_amount_out);
+    for (i, mint) in accounts.outcome_mints.iter().enumerate() {
+        if i == outcome_index as usize {
+            continue;
+        }
+        ix.accounts.push(AccountMeta::new(*mint, false));
+        ix.accounts.push(AccountMeta::new(
+            accounts.user_token_account(user, i as u8),
+            false,
+        ));
+    }
+    ix
+}
+
+/// Buy into an outcome, creating the user's outcome token account if needed.
+/// If the buy opens the market, the user's accounts for the other outcomes are created first.
+pub fn buy(
+    svm: &mut LiteSVM,
+    user: &Keypair,
+    accounts: &MarketAccounts,
+    outcome_index: u8,
+    amount_in: u64,
+) -> TransactionResult {
+    let create_ata_ix = |mint: &Pubkey| {
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &user.pubkey(),
+            &user.pubkey(),
+            mint,
+            &spl_token::ID,
+        )
+    };
+    let ix = if load_market(svm, &accounts.market).is_empty() {
+        let create_others: Vec<_> = accounts.outcome_mints.iter().map(create_ata_ix).collect();
+        send(svm, &create_others, user, &[user]).unwrap();
+        opening_buy_ix(&user.pubkey(), accounts, outcome_index, amount_in, 0)
+    } else {
+        buy_ix(&user.pubkey(), accounts, outcome_index, amount_in, 0)
+    };
+    let create_ata_ix = create_ata_ix(&accounts.outcome_mints[outcome_index as usize]);
+    send(svm, &[create_ata_ix, ix], user, &[user])
+}
+
+pub fn seed_market_ix(
+    admin: &Pubkey,
+    accounts: &MarketAccounts,
+    amount_per_outcome: u64,
+) -> Instruction {
+    let mut accounts_ctx = gamma::accounts::SeedMarket {
+        admin: *admin,
+        market: accounts.market,
+        market_vault: accounts.market_vault,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        collateral_vault: accounts.collateral_vault(),
+        admin_collateral_account: accounts.collateral_account(admin),
+    }
+    .to_account_metas(None);
+    for (i, mint) in accounts.outcome_mints.iter().enumerate() {
+        accounts_ctx.push(AccountMeta::new(*mint, false));
+        accounts_ctx.push(AccountMeta::new(
+            accounts.user_token_account(admin, i as u8),
+            false,
+        ));
+    }
+
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::SeedMarket { amount_per_outcome }.data(),
+        accounts_ctx,
+    )
+}
+
+/// Create the admin's token account for every outcome and seed the market
+pub fn seed_market(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    accounts: &MarketAccounts,
+    amount_per_outcome: u64,
+) -> TransactionResult {
+    let mut ixs: Vec<Instruction> = accounts
+        .outcome_mints
+        .iter()
+        .map(|mint| {
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &admin.pubkey(),
+                &admin.pubkey(),
+                mint,
+                &spl_token::ID,
+            )
+        })
+        .collect();
+    ixs.push(seed_market_ix(
+        &admin.pubkey(),
+        accounts,
+        amount_per_outcome,
+    ));
+    send(svm, &ixs, admin, &[admin])
+}
+
+pub fn batch_buy_ix(user: &Pubkey, accounts: &MarketAccounts, legs: Vec<BuyLeg>) -> Instruction {
+    let mut accounts_ctx = gamma::accounts::BatchBuy {
+        user: *user,
+        market: accounts.market,
+        market_vault: accounts.market_vault,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        collateral_vault: accounts.collateral_vault(),
+        user_collateral_account: accounts.collateral_account(user),
+    }
+    .to_account_metas(None);
+    for leg in &legs {
+        let index = leg.outcome_index;
+        accounts_ctx.push(AccountMeta::new(
+            accounts.outcome_mints[index as usize],
+            false,
+        ));
+        accounts_ctx.push(AccountMeta::new(
+            accounts.user_token_account(user, index),
+            false,
+        ));
+    }
+
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::BatchBuy { legs }.data(),
+        accounts_ctx,
+    )
+}
+
+pub fn sell_ix(
+    user: &Pubkey,
+    accounts: &MarketAccounts,
+    outcome_index: u8,
+    burn_amount: u64,
+    min_payout: u64,
+) -> Instruction {
+    let accounts_ctx = gamma::accounts::Sell {
+        user: *user,
+        market: accounts.market,
+        market_vault: accounts.market_vault,
+        outcome_mint: accounts.outcome_mints[outcome_index as usize],
+        user_outcome_token_account: accounts.user_token_account(user, outcome_index),
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+        collateral_vault: accounts.collateral_vault(),
+        user_collateral_account: accounts.collateral_account(user),
+    }
+    .to_account_metas(None);
+
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::Sell {
+            outcome_index,
+            burn_amount,
+            min_payout,
+        }
+        .data(),
+        accounts_ctx,
+    )
+}
+
+pub fn sell(
+    svm: &mut LiteSVM,
+    user: &Keypair,
+    accounts: &MarketAccounts,
+    outcome_index: u8,
+    burn_amount: u64,
+) -> TransactionResult {
+    let ix = sell_ix(&user.pubkey(), accounts, outcome_index, burn_amount, 0);
+    send(svm, &[ix], user, &[user])
+}
+
+pub fn swap_outcome_ix(
+    user: &Pubkey,
+    accounts: &MarketAccounts,
+    from_index: u8,
+    to_index: u8,
+    burn_amount: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::SwapOutcome {
+            from_index,
+            to_index,
+            burn_amount,
+            min_amount_out,
+        }
+        .data(),
+        gamma::accounts::SwapOutcome {
+            user: *user,
+            market: accounts.market,
+            market_vault: accounts.market_vault,
+            from_mint: accounts.outcome_mints[from_index as usize],
+            user_from_token_account: accounts.user_token_account(user, from_index),
+            to_mint: accounts.outcome_mints[to_index as usize],
+            user_to_token_account: accounts.user_token_account(user, to_index),
+            token_program: anchor_spl::token::ID,
+            collateral_vault: accounts.collateral_vault(),
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn swap_outcome(
+    svm: &mut LiteSVM,
+    user: &Keypair,
+    accounts: &MarketAccounts,
+    from_index: u8,
+    to_index: u8,
+    burn_amount: u64,
+    min_amount_out: u64,
+) -> TransactionResult {
+    let create_ata_ix =
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &user.pubkey(),
+            &user.pubkey(),
+            &accounts.outcome_mints[to_index as usize],
+            &spl_token::ID,
+        );
+    let ix = swap_outcome_ix(
+        &user.pubkey(),
+        accounts,
+        from_index,
+        to_index,
+        burn_amount,
+        min_amount_out,
+    );
+    send(svm, &[create_ata_ix, ix], user, &[user])
+}
+
+pub fn load_market(svm: &LiteSVM, market: &Pubkey) -> Market {
+    let market_account = svm.get_account(market).unwrap();
+    Market::try_deserialize(&mut market_account.data.as_ref()).unwrap()
+}
+
+pub fn token_balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+    let account = svm.get_account(token_account).unwrap();
+    anchor_spl::token::TokenAccount::try_deserialize(&mut account.data.as_ref())
+        .unwrap()
+        .amount
+}
+
+pub fn set_unix_timestamp(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
+
+/// Find the first `E` event in a transaction's logs.
+/// Anchor logs each event as base64 of its discriminator followed by the borsh fields.
+pub fn find_event<E: Event + Discriminator + AnchorDeserialize>(logs: &[String]) -> Option<E> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| BASE64_STANDARD.decode(data).ok())
+        .find(|data| data.starts_with(E::DISCRIMINATOR))
+        .map(|data| E::try_from_slice(&data[E::DISCRIMINATOR.len()..]).unwrap())
+}
+
+/// Assert a transaction failed on its `ix_index` instruction with the given program error
+pub fn assert_error(result: TransactionResult, ix_index: u8, error: ErrorCode) {
+    let err = result.expect_err("transaction should have failed").err;
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(ix_index, InstructionError::Custom(error.into()))
+    );
+}
+
+pub fn transfer_admin_ix(
+    admin: &Pubkey,
+    accounts: &MarketAccounts,
+    new_admin: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::TransferAdmin {
+            new_admin: *new_admin,
+        }
+        .data(),
+        gamma::accounts::TransferAdmin {
+            admin: *admin,
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn accept_admin_ix(new_admin: &Pubkey, accounts: &MarketAccounts) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::AcceptAdmin {}.data(),
+        gamma::accounts::AcceptAdmin {
+            new_admin: *new_admin,
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn sweep_surplus_ix(admin: &Pubkey, accounts: &MarketAccounts) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::SweepSurplus {}.data(),
+        gamma::accounts::SweepSurplus {
+            admin: *admin,
+            market: accounts.market,
+            market_vault: accounts.market_vault,
+            collateral_vault: accounts.collateral_vault(),
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn resolve_market_ix(
+    admin: &Pubkey,
+    accounts: &MarketAccounts,
+    winning_outcome: u8,
+    resolution_proof: Option<[u8; 32]>,
+) -> Instruction {
+    resolve_market_ix_with_allow_empty(admin, accounts, winning_outcome, resolution_proof, false)
+}
+
+pub fn resolve_market_ix_with_allow_empty(
+    admin: &Pubkey,
+    accounts: &MarketAccounts,
+    winning_outcome: u8,
+    resolution_proof: Option<[u8; 32]>,
+    allow_empty: bool,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::ResolveMarket {
+            winning_outcome,
+            resolution_proof,
+            allow_empty,
+        }
+        .data(),
+        gamma::accounts::ResolveMarket {
+            admin: *admin,
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn pause_market_ix(admin: &Pubkey, accounts: &MarketAccounts, paused: bool) -> Instruction {
+    let data = if paused {
+        gamma::instruction::PauseMarket {}.data()
+    } else {
+        gamma::instruction::UnpauseMarket {}.data()
+    };
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &data,
+        gamma::accounts::PauseMarket {
+            admin: *admin,
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn update_resolve_at_ix(
+    admin: &Pubkey,
+    accounts: &MarketAccounts,
+    resolve_at: i64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::UpdateResolveAt { resolve_at }.data(),
+        gamma::accounts::UpdateResolveAt {
+            admin: *admin,
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn mark_awaiting_resolution_ix(accounts: &MarketAccounts) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::MarkAwaitingResolution {}.data(),
+        gamma::accounts::MarkAwaitingResolution {
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn set_limits_ix(
+    admin: &Pubkey,
+    accounts: &MarketAccounts,
+    min_deposit: u64,
+    max_reserve: u64,
+    max_total_reserves: u64,
+    max_supply: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::SetLimits {
+            min_deposit,
+            max_reserve,
+            max_total_reserves,
+            max_supply,
+        }
+        .data(),
+        gamma::accounts::SetLimits {
+            admin: *admin,
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn redeem_ix(
+    user: &Pubkey,
+    accounts: &MarketAccounts,
+    outcome_index: u8,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::Redeem {
+            outcome_index,
+            amount,
+        }
+        .data(),
+        gamma::accounts::Redeem {
+            user: *user,
+            market: accounts.market,
+            market_vault: accounts.market_vault,
+            outcome_mint: accounts.outcome_mints[outcome_index as usize],
+            user_outcome_token_account: accounts.user_token_account(user, outcome_index),
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            collateral_vault: accounts.collateral_vault(),
+            user_collateral_account: accounts.collateral_account(user),
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn redeem(
+    svm: &mut LiteSVM,
+    user: &Keypair,
+    accounts: &MarketAccounts,
+    outcome_index: u8,
+    amount: u64,
+) -> TransactionResult {
+    let ix = redeem_ix(&user.pubkey(), accounts, outcome_index, amount);
+    send(svm, &[ix], user, &[user])
+}
+
+/// Claims `user`'s winnings from each `(market, winning outcome)` in one instruction
+pub fn batch_claim_ix(user: &Pubkey, claims: &[(&MarketAccounts, u8)]) -> Instruction {
+    let mut accounts_ctx = gamma::accounts::BatchClaim {
+        user: *user,
+        token_program: anchor_spl::token::ID,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    for (accounts, winner) in claims {
+        accounts_ctx.push(AccountMeta::new(accounts.market, false));
+        accounts_ctx.push(AccountMeta::new(accounts.market_vault, false));
+        accounts_ctx.push(AccountMeta::new(
+            accounts.outcome_mints[*winner as usize],
+            false,
+        ));
+        accounts_ctx.push(AccountMeta::new(
+            accounts.user_token_account(user, *winner),
+            false,
+        ));
+    }
+
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::BatchClaim {}.data(),
+        accounts_ctx,
+    )
+}
+
+/// Address of `user`'s claim escrow for the market
+pub fn escrow_address(accounts: &MarketAccounts, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[ESCROW_SEED, accounts.market.as_ref(), user.as_ref()],
+        &gamma::id(),
+    )
+    .0
+}
+
+pub fn claim_to_escrow_ix(
+    user: &Pubkey,
+    accounts: &MarketAccounts,
+    outcome_index: u8,
+    amount: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::ClaimToEscrow {
+            outcome_index,
+            amount,
+        }
+        .data(),
+        gamma::accounts::ClaimToEscrow {
+            user: *user,
+            market: accounts.market,
+            market_vault: accounts.market_vault,
+            outcome_mint: accounts.outcome_mints[outcome_index as usize],
+            user_outcome_token_account: accounts.user_token_account(user, outcome_index),
+            escrow: escrow_address(accounts, user),
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn release_escrow_ix(user: &Pubkey, accounts: &MarketAccounts) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::ReleaseEscrow {}.data(),
+        gamma::accounts::ReleaseEscrow {
+            user: *user,
+            escrow: escrow_address(accounts, user),
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn claim_fees_ix(
+    admin: &Pubkey,
+    accounts: &MarketAccounts,
+    fee_recipient: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::ClaimFees {}.data(),
+        gamma::accounts::ClaimFees {
+            admin: *admin,
+            market: accounts.market,
+            market_vault: accounts.market_vault,
+            fee_recipient: *fee_recipient,
+            collateral_vault: accounts.collateral_vault(),
+            fee_recipient_collateral_account: accounts.collateral_account(fee_recipient),
+            token_program: accounts.collateral_token_program(),
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn close_market_ix(admin: &Pubkey, accounts: &MarketAccounts) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::CloseMarket {}.data(),
+        gamma::accounts::CloseMarket {
+            admin: *admin,
+            market: accounts.market,
+            market_vault: accounts.market_vault,
+            collateral_vault: accounts.collateral_vault(),
+            admin_collateral_account: accounts.collateral_account(admin),
+            token_program: accounts.collateral_token_program(),
+        }
+        .to_account_metas(None),
+    )
+}
+
+pub fn get_snapshot_ix(accounts: &MarketAccounts) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::GetSnapshot {}.data(),
+        gamma::accounts::GetSnapshot {
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+/// Read the market through the `get_snapshot` view instruction
+pub fn get_snapshot(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    accounts: &MarketAccounts,
+) -> MarketSnapshot {
+    let meta = send(svm, &[get_snapshot_ix(accounts)], payer, &[payer]).unwrap();
+    MarketSnapshot::try_from_slice(&meta.return_data.data).unwrap()
+}
@@ -0,0 +1,72 @@
+mod helpers;
+
+use common::errors::ErrorCode;
+use helpers::*;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+#[test]
+fn test_transfer_admin_requires_acceptance() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let new_admin = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "transfer_admin", 2, DEFAULT_RESOLVE_AT);
+
+    let ix = transfer_admin_ix(&admin.pubkey(), &accounts, &new_admin.pubkey());
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+
+    // nothing changes until the new admin accepts
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.admin, admin.pubkey());
+    assert_eq!(market.pending_admin, new_admin.pubkey());
+    let ix = set_limits_ix(&new_admin.pubkey(), &accounts, 0, 0, 0, 0);
+    let result = send(&mut svm, &[ix], &new_admin, &[&new_admin]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    let ix = accept_admin_ix(&new_admin.pubkey(), &accounts);
+    send(&mut svm, &[ix], &new_admin, &[&new_admin]).unwrap();
+
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.admin, new_admin.pubkey());
+    assert_eq!(market.pending_admin, Pubkey::default());
+
+    // the new admin manages the market, the old one no longer can
+    let ix = set_limits_ix(&new_admin.pubkey(), &accounts, 1_000, 0, 0, 0);
+    send(&mut svm, &[ix], &new_admin, &[&new_admin]).unwrap();
+    let ix = set_limits_ix(&admin.pubkey(), &accounts, 0, 0, 0, 0);
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+}
+
+#[test]
+fn test_transfer_admin_rejects_other_signers() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let new_admin = funded_keypair(&mut svm);
+    let attacker = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "admin_guard", 2, DEFAULT_RESOLVE_AT);
+
+    // only the current admin may propose a transfer
+    let ix = transfer_admin_ix(&attacker.pubkey(), &accounts, &attacker.pubkey());
+    let result = send(&mut svm, &[ix], &attacker, &[&attacker]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    // with nothing pending, nobody can accept
+    let ix = accept_admin_ix(&attacker.pubkey(), &accounts);
+    let result = send(&mut svm, &[ix], &attacker, &[&attacker]);
+    assert_error(result, 0, ErrorCode::NotPendingAdmin);
+
+    // only the proposed key may accept
+    let ix = transfer_admin_ix(&admin.pubkey(), &accounts, &new_admin.pubkey());
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    let ix = accept_admin_ix(&attacker.pubkey(), &accounts);
+    let result = send(&mut svm, &[ix], &attacker, &[&attacker]);
+    assert_error(result, 0, ErrorCode::NotPendingAdmin);
+
+    // proposing the default key cancels the transfer
+    let ix = transfer_admin_ix(&admin.pubkey(), &accounts, &Pubkey::default());
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    let ix = accept_admin_ix(&new_admin.pubkey(), &accounts);
+    let result = send(&mut svm, &[ix], &new_admin, &[&new_admin]);
+    assert_error(result, 0, ErrorCode::NotPendingAdmin);
+    assert_eq!(load_market(&svm, &accounts.market).admin, admin.pubkey());
+}
@@ -0,0 +1,140 @@
+mod helpers;
+
+use common::errors::ErrorCode;
+use gamma::events::SellExecuted;
+use gamma::types::MarketConfig;
+use helpers::*;
+use litesvm::LiteSVM;
+use litesvm_token::{CreateAssociatedTokenAccount, CreateMint, MintTo};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signer::{keypair::Keypair, Signer},
+};
+
+const USDC_DECIMALS: u8 = 6;
+
+/// Create a mock USDC mint with `admin` as its mint authority
+fn create_usdc(svm: &mut LiteSVM, admin: &Keypair) -> Pubkey {
+    CreateMint::new(svm, admin)
+        .decimals(USDC_DECIMALS)
+        .send()
+        .unwrap()
+}
+
+/// Create `owner`'s USDC account and mint `amount` into it
+fn fund_usdc(svm: &mut LiteSVM, admin: &Keypair, usdc: &Pubkey, owner: &Pubkey, amount: u64) {
+    let account = CreateAssociatedTokenAccount::new(svm, admin, usdc)
+        .owner(owner)
+        .send()
+        .unwrap();
+    MintTo::new(svm, admin, usdc, &account, amount)
+        .send()
+        .unwrap();
+}
+
+#[test]
+fn test_usdc_market_trades_in_tokens() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let usdc = create_usdc(&mut svm, &admin);
+    fund_usdc(&mut svm, &admin, &usdc, &admin.pubkey(), 1_000_000_000);
+    fund_usdc(&mut svm, &admin, &usdc, &user.pubkey(), 1_000_000_000);
+
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "usdc",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            collateral_mint: Some(usdc),
+            ..open_config()
+        },
+    );
+    let collateral_vault = accounts.collateral_vault().unwrap();
+    let user_usdc = accounts.collateral_account(&user.pubkey()).unwrap();
+    assert_eq!(
+        load_market(&svm, &accounts.market).collateral_mint(),
+        Some(usdc)
+    );
+    assert_eq!(token_balance(&svm, &collateral_vault), 0);
+
+    let vault_lamports = svm.get_balance(&accounts.market_vault).unwrap();
+    buy(&mut svm, &admin, &accounts, 1, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    // the deposits moved USDC into the collateral vault and left the SOL vault untouched
+    assert_eq!(token_balance(&svm, &user_usdc), 900_000_000);
+    assert_eq!(token_balance(&svm, &collateral_vault), 200_000_000);
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        vault_lamports
+    );
+    assert!(token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 0)) > 0);
+
+    let meta = sell(&mut svm, &user, &accounts, 0, 40_000_000).unwrap();
+    let event = find_event::<SellExecuted>(&meta.logs).unwrap();
+    assert!(event.net_payout > 0);
+    assert_eq!(
+        token_balance(&svm, &user_usdc),
+        900_000_000 + event.net_payout
+    );
+}
+
+#[test]
+fn test_usdc_market_rejects_sol_accounts() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let usdc = create_usdc(&mut svm, &admin);
+    fund_usdc(&mut svm, &admin, &usdc, &user.pubkey(), 1_000_000_000);
+
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "usdc_only",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            collateral_mint: Some(usdc),
+            ..open_config()
+        },
+    );
+
+    // buying with lamports, i.e. without the collateral accounts, is rejected
+    let sol_accounts = MarketAccounts::derive("usdc_only", 2);
+    let result = buy(&mut svm, &user, &sol_accounts, 0, 100_000_000);
+    assert_error(result, 1, ErrorCode::InvalidCollateral);
+
+    // lamport funding can't back a token vault
+    let result = send(
+        &mut svm,
+        &[init_market_ix(
+            &admin.pubkey(),
+            &MarketAccounts {
+                collateral_mint: Some(usdc),
+                ..MarketAccounts::derive("usdc_funded", 2)
+            },
+            DEFAULT_SCALE,
+            DEFAULT_RESOLVE_AT,
+            MarketConfig {
+                collateral_mint: Some(usdc),
+                min_vault_funding: 1_000_000,
+                ..open_config()
+            },
+        )],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::InvalidCollateral);
+
+    // SOL markets keep trading lamports next to it
+    let sol_market = init_market(&mut svm, &admin, "sol", 2, DEFAULT_RESOLVE_AT);
+    buy(&mut svm, &user, &sol_market, 0, 100_000_000).unwrap();
+    assert_eq!(
+        token_balance(&svm, &accounts.collateral_vault().unwrap()),
+        0
+    );
+    assert!(svm.get_balance(&sol_market.market_vault).unwrap() > 100_000_000);
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "dto")]
+
+use common::constants::NO_OUTCOME;
+use gamma::dto::MarketDto;
+use gamma::state::Market;
+use gamma::types::FixedSizeString;
+
+#[test]
+fn test_market_dto_serializes_key_fields() {
+    let mut market = Market {
+        num_outcomes: 2,
+        void_outcome: NO_OUTCOME,
+        label: FixedSizeString::new("BTC above 100k"),
+        scale: 100_000,
+        ..Default::default()
+    };
+    market.reserves[0] = 300_000_000;
+    market.reserves[1] = 100_000_000;
+    market.supplies[0] = 150_000_000;
+    market.supplies[1] = 100_000_000;
+    market.recompute_invariant().unwrap();
+
+    let dto = MarketDto::from(&market);
+    assert_eq!(dto.label, "BTC above 100k");
+    assert_eq!(dto.reserves, vec![300_000_000, 100_000_000]);
+    assert_eq!(dto.prices, vec![2_000_000_000, 1_000_000_000]);
+    assert_eq!(dto.probabilities_bps, vec![7_500, 2_500]);
+    assert_eq!(dto.void_outcome, None);
+    assert_eq!(dto.winning_outcome, None);
+
+    let json: serde_json::Value = serde_json::to_value(&dto).unwrap();
+    assert_eq!(json["label"], "BTC above 100k");
+    assert_eq!(json["invariant"], "30000000000000000");
+    assert_eq!(json["supplies"][0], 150_000_000);
+    assert!(json["winning_outcome"].is_null());
+}
@@ -0,0 +1,425 @@
+mod helpers;
+
+use anchor_lang::{solana_program::instruction::Instruction, InstructionData, ToAccountMetas};
+use common::errors::ErrorCode;
+use gamma::events::SellExecuted;
+use gamma::types::MarketConfig;
+use helpers::*;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+#[test]
+fn test_claim_fees_only_moves_fee_portion() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    // without a configured fee recipient the fees go to the admin
+    let accounts = init_market(&mut svm, &admin, "claim_fee_portion", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    let fees = load_market(&svm, &accounts.market).undistributed_fees;
+    assert!(fees > 0);
+
+    // only the admin may claim
+    let result = send(
+        &mut svm,
+        &[claim_fees_ix(&user.pubkey(), &accounts, &admin.pubkey())],
+        &user,
+        &[&user],
+    );
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    let admin_before = svm.get_balance(&admin.pubkey()).unwrap();
+    send(
+        &mut svm,
+        &[claim_fees_ix(&admin.pubkey(), &accounts, &admin.pubkey())],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+    let admin_after = svm.get_balance(&admin.pubkey()).unwrap();
+    // admin receives the fees minus 5000 lamports for the tx fee
+    assert_eq!(admin_after + 5000 - admin_before, fees);
+
+    // the vault is left holding exactly the reserves plus rent
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.undistributed_fees, 0);
+    let vault_lamports = svm.get_balance(&accounts.market_vault).unwrap();
+    assert_eq!(
+        vault_lamports as u128,
+        market.total_reserves_u128().unwrap() + svm.minimum_balance_for_rent_exemption(0) as u128
+    );
+}
+
+#[test]
+fn test_claim_fees_rejects_unbacked_fees() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "unbacked_fees", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    // leave the vault one lamport short of backing both the reserves and the recorded fees
+    let market = load_market(&svm, &accounts.market);
+    let mut vault = svm.get_account(&accounts.market_vault).unwrap();
+    vault.lamports = market.total_reserves_u128().unwrap() as u64
+        + market.undistributed_fees
+        + svm.minimum_balance_for_rent_exemption(0)
+        - 1;
+    svm.set_account(accounts.market_vault, vault).unwrap();
+
+    let result = send(
+        &mut svm,
+        &[claim_fees_ix(&admin.pubkey(), &accounts, &admin.pubkey())],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::WouldDrainReserves);
+}
+
+#[test]
+fn test_lifetime_fees_survive_claim() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "lifetime_fees", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+
+    sell(&mut svm, &user, &accounts, 0, 40_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 1, 30_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 0, 20_000_000).unwrap();
+
+    let before = get_snapshot(&mut svm, &user, &accounts);
+    assert!(before.undistributed_fees > 0);
+    assert_eq!(before.lifetime_fees, before.undistributed_fees);
+
+    send(
+        &mut svm,
+        &[claim_fees_ix(&admin.pubkey(), &accounts, &admin.pubkey())],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+
+    let after = get_snapshot(&mut svm, &user, &accounts);
+    assert_eq!(after.undistributed_fees, 0);
+    assert_eq!(after.lifetime_fees, before.lifetime_fees);
+
+    // later fees keep adding to the lifetime total
+    sell(&mut svm, &user, &accounts, 1, 30_000_000).unwrap();
+    let last = get_snapshot(&mut svm, &user, &accounts);
+    assert_eq!(
+        last.lifetime_fees,
+        before.lifetime_fees + last.undistributed_fees
+    );
+}
+
+#[test]
+fn test_donate_fees_moves_fees_into_reserves() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "donate_fees", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 0, 50_000_000).unwrap();
+
+    let before = load_market(&svm, &accounts.market);
+    assert!(before.undistributed_fees > 0);
+    let vault_before = svm.get_balance(&accounts.market_vault).unwrap();
+
+    let ix = Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::DonateFees {}.data(),
+        gamma::accounts::DonateFees {
+            admin: admin.pubkey(),
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    );
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+
+    let after = load_market(&svm, &accounts.market);
+    assert_eq!(after.undistributed_fees, 0);
+    assert_eq!(
+        after.total_reserves_u128().unwrap(),
+        before.total_reserves_u128().unwrap() + before.undistributed_fees as u128
+    );
+    // no lamports move, the vault already held the fees
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        vault_before
+    );
+}
+
+#[test]
+fn test_claim_fees_pays_fee_recipient_once() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let recipient = Pubkey::new_unique();
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "claim_fees",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            fee_recipient: Some(recipient),
+            ..open_config()
+        },
+    );
+    assert_eq!(load_market(&svm, &accounts.market).fee_recipient, recipient);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 0, 40_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 1, 30_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 0, 20_000_000).unwrap();
+
+    let fees = load_market(&svm, &accounts.market).undistributed_fees;
+    assert!(fees > 0);
+
+    // fees can only go to the configured recipient
+    let result = send(
+        &mut svm,
+        &[claim_fees_ix(&admin.pubkey(), &accounts, &admin.pubkey())],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::InvalidFeeRecipient);
+
+    // the recipient starts empty, so it is funded with rent plus the fees
+    svm.airdrop(&recipient, svm.minimum_balance_for_rent_exemption(0))
+        .unwrap();
+    let recipient_before = svm.get_balance(&recipient).unwrap();
+    send(
+        &mut svm,
+        &[claim_fees_ix(&admin.pubkey(), &accounts, &recipient)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+    assert_eq!(
+        svm.get_balance(&recipient).unwrap(),
+        recipient_before + fees
+    );
+    assert_eq!(load_market(&svm, &accounts.market).undistributed_fees, 0);
+
+    // a second claim has nothing left to move
+    svm.expire_blockhash();
+    send(
+        &mut svm,
+        &[claim_fees_ix(&admin.pubkey(), &accounts, &recipient)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+    assert_eq!(
+        svm.get_balance(&recipient).unwrap(),
+        recipient_before + fees
+    );
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap() as u128,
+        market.total_reserves_u128().unwrap() + svm.minimum_balance_for_rent_exemption(0) as u128
+    );
+}
+
+#[test]
+fn test_admin_cannot_redirect_fees() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let recipient = Pubkey::new_unique();
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "redirect_fees",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            fee_recipient: Some(recipient),
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 0, 40_000_000).unwrap();
+    let fees = load_market(&svm, &accounts.market).undistributed_fees;
+    assert!(fees > 0);
+
+    // the admin signs, but neither their own account nor any other one can take the fees
+    for target in [admin.pubkey(), user.pubkey()] {
+        let result = send(
+            &mut svm,
+            &[claim_fees_ix(&admin.pubkey(), &accounts, &target)],
+            &admin,
+            &[&admin],
+        );
+        assert_error(result, 0, ErrorCode::InvalidFeeRecipient);
+    }
+    // the fees stay booked for the configured recipient
+    assert_eq!(load_market(&svm, &accounts.market).undistributed_fees, fees);
+}
+
+#[test]
+fn test_sweep_surplus_reclaims_stray_lamports() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "sweep_surplus",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            min_vault_funding: 5_000_000,
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+
+    // a fully backed vault holding only its backstop funding has nothing to sweep
+    let ix = sweep_surplus_ix(&admin.pubkey(), &accounts);
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    assert_eq!(load_market(&svm, &accounts.market).undistributed_fees, 0);
+
+    // only the admin may sweep
+    let ix = sweep_surplus_ix(&user.pubkey(), &accounts);
+    let result = send(&mut svm, &[ix], &user, &[&user]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    // SOL sent straight to the vault is booked as fees, leaving the funding in place
+    svm.airdrop(&accounts.market_vault, 42_000_000).unwrap();
+    let before = load_market(&svm, &accounts.market);
+    let vault_before = svm.get_balance(&accounts.market_vault).unwrap();
+    let ix = sweep_surplus_ix(&admin.pubkey(), &accounts);
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+
+    let after = load_market(&svm, &accounts.market);
+    assert_eq!(after.undistributed_fees, 42_000_000);
+    assert_eq!(after.reserves, before.reserves);
+    assert_eq!(after.vault_funding, 5_000_000);
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        vault_before
+    );
+
+    // and can then be claimed like any other fee
+    let admin_before = svm.get_balance(&admin.pubkey()).unwrap();
+    let ix = claim_fees_ix(&admin.pubkey(), &accounts, &admin.pubkey());
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    assert_eq!(
+        svm.get_balance(&admin.pubkey()).unwrap(),
+        admin_before + 42_000_000 - 5000
+    );
+}
+
+#[test]
+fn test_fee_bps_is_per_market() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+
+    // a fee over 100% is rejected
+    let result = send(
+        &mut svm,
+        &[init_market_ix(
+            &admin.pubkey(),
+            &MarketAccounts::derive("fee_too_high", 2),
+            DEFAULT_SCALE,
+            DEFAULT_RESOLVE_AT,
+            MarketConfig {
+                fee_bps: Some(10_001),
+                ..open_config()
+            },
+        )],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::InvalidFeeBps);
+
+    let mut fees = Vec::new();
+    for (label, fee_bps) in [("fee_default", None), ("fee_200", Some(200))] {
+        let accounts = init_market_with_config(
+            &mut svm,
+            &admin,
+            label,
+            2,
+            DEFAULT_RESOLVE_AT,
+            MarketConfig {
+                fee_bps,
+                ..open_config()
+            },
+        );
+        buy(&mut svm, &admin, &accounts, 1, 100_000_000).unwrap();
+        buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+        let meta = sell(&mut svm, &user, &accounts, 0, 40_000_000).unwrap();
+        let event = find_event::<SellExecuted>(&meta.logs).unwrap();
+        fees.push((event.fee, event.net_payout));
+    }
+
+    // identical trades, so the refunds match and only the fee rate differs: 0.1% vs 2%
+    let (default_fee, default_payout) = fees[0];
+    let (high_fee, high_payout) = fees[1];
+    let refund = default_fee + default_payout;
+    assert_eq!(high_fee + high_payout, refund);
+    assert_eq!(default_fee, refund * 10 / 10_000);
+    assert_eq!(high_fee, refund * 200 / 10_000);
+    assert!(high_payout < default_payout);
+}
+
+#[test]
+fn test_buy_fee_accrues_and_mints_on_net_input() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "buy_fee",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            buy_fee_bps: 50,
+            ..open_config()
+        },
+    );
+
+    let vault_before = svm.get_balance(&accounts.market_vault).unwrap();
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    // 0.5% stays in the vault as fees, the rest funds every outcome's opening reserve and
+    // buys outcome 0 at the opening price of 1/2
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.buy_fee_bps, 50);
+    assert_eq!(market.undistributed_fees, 500_000);
+    assert_eq!(market.reserves[0], 99_500_000 - DEFAULT_SCALE);
+    assert_eq!(market.reserves[1], DEFAULT_SCALE);
+    assert_eq!(
+        token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 0)),
+        2 * (99_500_000 - DEFAULT_SCALE)
+    );
+    assert_eq!(
+        token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 1)),
+        2 * DEFAULT_SCALE
+    );
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        vault_before + 100_000_000
+    );
+}
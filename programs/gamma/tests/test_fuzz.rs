@@ -0,0 +1,154 @@
+//! Randomized buys and sells checking the vault always backs the market's accounting:
+//!
+//! `vault_lamports == sum(reserves) + fees + rent`
+//!
+//! This holds exactly, with no rounding slack: buys add the same lamports to the vault and the
+//! reserves, the first buy included, and sells take the full refund from the reserve while the
+//! vault keeps the fee. Full exits are generated on purpose, so it also holds once a position or
+//! a whole outcome has been sold off.
+
+mod helpers;
+
+use helpers::*;
+use litesvm::LiteSVM;
+use solana_sdk::signer::{keypair::Keypair, Signer};
+
+const NUM_USERS: usize = 3;
+const NUM_OUTCOMES: u8 = 3;
+const STEPS: usize = 150;
+const SEEDS: [u64; 4] = [1, 42, 0xdead_beef, 7_777_777];
+
+/// xorshift64, so runs reproduce from their seed without pulling in a rand crate
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Buy {
+        user: usize,
+        outcome: u8,
+        amount: u64,
+    },
+    /// Sell `bps` of the user's current balance of the outcome
+    Sell { user: usize, outcome: u8, bps: u64 },
+}
+
+fn generate_ops(seed: u64, count: usize) -> Vec<Op> {
+    let mut rng = Rng(seed);
+    (0..count)
+        .map(|_| {
+            let user = rng.below(NUM_USERS as u64) as usize;
+            let outcome = rng.below(NUM_OUTCOMES as u64) as u8;
+            if rng.below(2) == 0 {
+                // span dust to multi-SOL buys
+                let digits = 1 + rng.below(10) as u32;
+                let amount = 1 + rng.below(10u64.pow(digits));
+                Op::Buy {
+                    user,
+                    outcome,
+                    amount,
+                }
+            } else {
+                // a quarter of sells exit the whole position, which can empty an outcome
+                let bps = if rng.below(4) == 0 {
+                    10_000
+                } else {
+                    1 + rng.below(10_000)
+                };
+                Op::Sell { user, outcome, bps }
+            }
+        })
+        .collect()
+}
+
+fn check_accounting(svm: &LiteSVM, accounts: &MarketAccounts) -> Result<(), String> {
+    let market = load_market(svm, &accounts.market);
+    let vault_lamports = svm.get_account(&accounts.market_vault).unwrap().lamports;
+    let rent_min = svm.minimum_balance_for_rent_exemption(0);
+
+    let owed = market.total_reserves_u128().unwrap()
+        + market.undistributed_fees as u128
+        + market.undistributed_settlement_fees as u128
+        + rent_min as u128;
+    if vault_lamports as u128 == owed {
+        Ok(())
+    } else {
+        Err(format!(
+            "vault {vault_lamports} != reserves {} + fees {} + rent {rent_min}",
+            market.total_reserves_u128().unwrap(),
+            market.undistributed_fees
+        ))
+    }
+}
+
+/// Replay `ops` on a fresh market, returning the index of the first op after which the
+/// accounting no longer holds. Rejected trades are fine, they must simply not change anything.
+fn run(ops: &[Op]) -> Result<(), (usize, String)> {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let users: Vec<Keypair> = (0..NUM_USERS).map(|_| funded_keypair(&mut svm)).collect();
+    let accounts = init_market(&mut svm, &admin, "fuzz", NUM_OUTCOMES, DEFAULT_RESOLVE_AT);
+
+    for (i, op) in ops.iter().enumerate() {
+        match *op {
+            Op::Buy {
+                user,
+                outcome,
+                amount,
+            } => {
+                let _ = buy(&mut svm, &users[user], &accounts, outcome, amount);
+            }
+            Op::Sell { user, outcome, bps } => {
+                let token_account = accounts.user_token_account(&users[user].pubkey(), outcome);
+                if svm.get_account(&token_account).is_some() {
+                    let amount = token_balance(&svm, &token_account) * bps / 10_000;
+                    if amount > 0 {
+                        let _ = sell(&mut svm, &users[user], &accounts, outcome, amount);
+                    }
+                }
+            }
+        }
+        check_accounting(&svm, &accounts).map_err(|err| (i, err))?;
+    }
+    Ok(())
+}
+
+/// Cut a failing sequence down to the failing prefix, then drop every op that isn't needed
+/// to reproduce the failure.
+fn shrink(ops: &[Op], failing_index: usize) -> Vec<Op> {
+    let mut ops = ops[..=failing_index].to_vec();
+    let mut i = 0;
+    while i < ops.len() {
+        let mut candidate = ops.clone();
+        candidate.remove(i);
+        if run(&candidate).is_err() {
+            ops = candidate;
+        } else {
+            i += 1;
+        }
+    }
+    ops
+}
+
+#[test]
+fn test_vault_backs_reserves_and_fees() {
+    for seed in SEEDS {
+        let ops = generate_ops(seed, STEPS);
+        if let Err((index, err)) = run(&ops) {
+            let minimal = shrink(&ops, index);
+            panic!("seed {seed} broke accounting at op {index}: {err}\nminimal ops: {minimal:#?}");
+        }
+    }
+}
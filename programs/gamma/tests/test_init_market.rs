@@ -0,0 +1,363 @@
+mod helpers;
+
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::spl_token::state::Mint;
+use common::errors::ErrorCode;
+use gamma::events::MarketInitialized;
+use gamma::state::ProgramConfig;
+use gamma::types::MarketConfig;
+use helpers::*;
+use litesvm::LiteSVM;
+use solana_sdk::{program_option::COption, program_pack::Pack, pubkey::Pubkey, signer::Signer};
+use spl_math::uint::U256;
+
+#[test]
+fn test_short_label_mint_authority_matches_market_pda() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+
+    // a single byte label is almost entirely padding
+    let accounts = init_market(&mut svm, &admin, "x", 2, DEFAULT_RESOLVE_AT);
+    assert_eq!(
+        accounts.market,
+        accounts.label.market_pda(0, &gamma::id()).0
+    );
+
+    for mint in &accounts.outcome_mints {
+        let mint_account = svm.get_account(mint).unwrap();
+        let mint = Mint::unpack(&mint_account.data).unwrap();
+        assert_eq!(mint.mint_authority, COption::Some(accounts.market));
+    }
+
+    // minting signs with the padded label seeds, so a buy proves they match the PDA
+    buy(&mut svm, &user, &accounts, 0, 10_000_000).unwrap();
+    let token_account = accounts.user_token_account(&user.pubkey(), 0);
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(token_balance(&svm, &token_account), market.supplies[0]);
+}
+
+#[test]
+fn test_init_requires_min_vault_funding() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+
+    // the admin can't cover the whole airdrop on top of the account rent
+    let accounts = MarketAccounts::derive("underfunded", 2);
+    let config = MarketConfig {
+        min_vault_funding: AIRDROP_LAMPORTS,
+        ..open_config()
+    };
+    let ix = init_market_ix(
+        &admin.pubkey(),
+        &accounts,
+        DEFAULT_SCALE,
+        DEFAULT_RESOLVE_AT,
+        config,
+    );
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::InsufficientFunds);
+
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "funded",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            min_vault_funding: 1_000_000_000,
+            ..open_config()
+        },
+    );
+    let vault = svm.get_account(&accounts.market_vault).unwrap();
+    assert_eq!(
+        vault.lamports,
+        svm.minimum_balance_for_rent_exemption(0) + 1_000_000_000
+    );
+}
+
+#[test]
+fn test_same_label_with_different_nonces() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+
+    let week_1 = init_market(&mut svm, &admin, "BTC price", 2, DEFAULT_RESOLVE_AT);
+    let week_2 = init_market_with_config(
+        &mut svm,
+        &admin,
+        "BTC price",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            nonce: 1,
+            ..open_config()
+        },
+    );
+    assert_ne!(week_1.market, week_2.market);
+    assert_eq!(load_market(&svm, &week_2.market).nonce, 1);
+
+    // the market signs mints with its nonce seed
+    buy(&mut svm, &user, &week_2, 0, 10_000_000).unwrap();
+    let token_account = week_2.user_token_account(&user.pubkey(), 0);
+    let market = load_market(&svm, &week_2.market);
+    assert_eq!(token_balance(&svm, &token_account), market.supplies[0]);
+}
+
+#[test]
+fn test_resolve_at_must_be_after_init() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    set_unix_timestamp(&mut svm, 500);
+
+    let accounts = MarketAccounts::derive("instant", 2);
+    let ix = init_market_ix(
+        &admin.pubkey(),
+        &accounts,
+        DEFAULT_SCALE,
+        500,
+        open_config(),
+    );
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::InvalidResolveTime);
+
+    let accounts = init_market(&mut svm, &admin, "later", 2, DEFAULT_RESOLVE_AT);
+    assert_eq!(load_market(&svm, &accounts.market).initialized_at, 500);
+}
+
+#[test]
+fn test_init_requires_two_outcomes() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+
+    for num_outcomes in [0, 1] {
+        let accounts = MarketAccounts::derive("single", num_outcomes);
+        let ix = init_market_ix(
+            &admin.pubkey(),
+            &accounts,
+            DEFAULT_SCALE,
+            DEFAULT_RESOLVE_AT,
+            open_config(),
+        );
+        let result = send(&mut svm, &[ix], &admin, &[&admin]);
+        assert_error(result, 0, ErrorCode::TooFewOutcomes);
+    }
+
+    init_market(&mut svm, &admin, "binary", 2, DEFAULT_RESOLVE_AT);
+}
+
+#[test]
+fn test_init_records_created_slot() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    svm.warp_to_slot(1_234);
+
+    let accounts = init_market(&mut svm, &admin, "slot", 2, DEFAULT_RESOLVE_AT);
+    assert_eq!(load_market(&svm, &accounts.market).created_slot, 1_234);
+    assert_eq!(
+        get_snapshot(&mut svm, &admin, &accounts).created_slot,
+        1_234
+    );
+}
+
+#[test]
+fn test_init_rejects_invalid_labels() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+
+    for (label, error) in [
+        ("", ErrorCode::InvalidLabelLength),
+        ("   ", ErrorCode::InvalidLabelLength),
+        ("naïve", ErrorCode::InvalidLabelCharacters),
+        ("new\nline", ErrorCode::InvalidLabelCharacters),
+    ] {
+        let accounts = MarketAccounts::derive(label, 2);
+        let ix = init_market_ix(
+            &admin.pubkey(),
+            &accounts,
+            DEFAULT_SCALE,
+            DEFAULT_RESOLVE_AT,
+            open_config(),
+        );
+        let result = send(&mut svm, &[ix], &admin, &[&admin]);
+        assert_error(result, 0, error);
+    }
+
+    // the PDA already keeps labels unique
+    init_market(&mut svm, &admin, "unique", 2, DEFAULT_RESOLVE_AT);
+    let accounts = MarketAccounts::derive("unique", 2);
+    let ix = init_market_ix(
+        &admin.pubkey(),
+        &accounts,
+        DEFAULT_SCALE,
+        DEFAULT_RESOLVE_AT,
+        open_config(),
+    );
+    assert!(send(&mut svm, &[ix], &admin, &[&admin]).is_err());
+}
+
+#[test]
+fn test_only_upgrade_authority_inits_program_config() {
+    let mut svm = LiteSVM::new();
+    let upgrade_authority = funded_keypair(&mut svm);
+    let attacker = funded_keypair(&mut svm);
+    deploy_program(&mut svm, &upgrade_authority.pubkey());
+
+    let ix = init_program_config_ix(&attacker.pubkey());
+    let result = send(&mut svm, &[ix], &attacker, &[&attacker]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    // program data the attacker controls doesn't stand in for gamma's own
+    let fake_program_data = Pubkey::new_unique();
+    set_program_data(&mut svm, &fake_program_data, &attacker.pubkey(), &[]);
+    let ix = init_program_config_ix_with_program_data(&attacker.pubkey(), &fake_program_data);
+    let result = send(&mut svm, &[ix], &attacker, &[&attacker]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    let ix = init_program_config_ix(&upgrade_authority.pubkey());
+    send(&mut svm, &[ix], &upgrade_authority, &[&upgrade_authority]).unwrap();
+    let program_config = svm.get_account(&program_config_pda()).unwrap();
+    let program_config =
+        ProgramConfig::try_deserialize(&mut program_config.data.as_slice()).unwrap();
+    assert_eq!(program_config.admin, upgrade_authority.pubkey());
+}
+
+#[test]
+fn test_pausing_market_creation_leaves_existing_markets_trading() {
+    let (mut svm, program_admin) = setup_with_program_admin();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let existing = init_market(&mut svm, &admin, "before_pause", 2, DEFAULT_RESOLVE_AT);
+
+    // only the config admin may pause
+    let result = send(
+        &mut svm,
+        &[set_market_creation_paused_ix(&admin.pubkey(), true)],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    send(
+        &mut svm,
+        &[set_market_creation_paused_ix(&program_admin.pubkey(), true)],
+        &program_admin,
+        &[&program_admin],
+    )
+    .unwrap();
+
+    let accounts = MarketAccounts::derive("during_pause", 2);
+    let ix = init_market_ix(
+        &admin.pubkey(),
+        &accounts,
+        DEFAULT_SCALE,
+        DEFAULT_RESOLVE_AT,
+        open_config(),
+    );
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::MarketCreationPaused);
+
+    // existing markets keep trading while paused
+    buy(&mut svm, &user, &existing, 0, 10_000_000).unwrap();
+
+    send(
+        &mut svm,
+        &[set_market_creation_paused_ix(
+            &program_admin.pubkey(),
+            false,
+        )],
+        &program_admin,
+        &[&program_admin],
+    )
+    .unwrap();
+    init_market(&mut svm, &admin, "during_pause", 2, DEFAULT_RESOLVE_AT);
+}
+
+#[test]
+fn test_init_market_emits_market_initialized_event() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+
+    let accounts = MarketAccounts::derive("init_event", 3);
+    let ix = init_market_ix(
+        &admin.pubkey(),
+        &accounts,
+        DEFAULT_SCALE,
+        DEFAULT_RESOLVE_AT,
+        open_config(),
+    );
+    let meta = send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    let event =
+        find_event::<MarketInitialized>(&meta.logs).expect("MarketInitialized event in logs");
+
+    assert_eq!(event.market, accounts.market);
+    assert_eq!(event.admin, admin.pubkey());
+    assert_eq!(event.num_outcomes, 3);
+    assert_eq!(event.scale, DEFAULT_SCALE);
+    assert_eq!(event.resolve_at, DEFAULT_RESOLVE_AT);
+    assert_eq!(event.label.value, accounts.label.value);
+}
+
+#[test]
+fn test_init_market_batch_creates_every_market() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+
+    // a repeated label is rejected before anything is created
+    let duplicate = [
+        MarketAccounts::derive("game-1", 2),
+        MarketAccounts::derive("game-1", 2),
+    ];
+    let ix = init_market_batch_ix(
+        &admin.pubkey(),
+        &duplicate,
+        DEFAULT_SCALE,
+        DEFAULT_RESOLVE_AT,
+    );
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::DuplicateMarketLabel);
+    assert!(svm.get_account(&duplicate[0].market).is_none());
+
+    let markets = [
+        MarketAccounts::derive("game-1", 2),
+        MarketAccounts::derive("game-2", 3),
+        MarketAccounts::derive("game-3", 2),
+    ];
+    let ix = init_market_batch_ix(&admin.pubkey(), &markets, DEFAULT_SCALE, DEFAULT_RESOLVE_AT);
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+
+    for accounts in &markets {
+        let market = load_market(&svm, &accounts.market);
+        assert_eq!(market.admin, admin.pubkey());
+        assert_eq!(market.label.value, accounts.label.value);
+        assert_eq!(market.num_outcomes as usize, accounts.outcome_mints.len());
+        assert_eq!(market.resolve_at, DEFAULT_RESOLVE_AT);
+        assert!(svm.get_account(&accounts.market_vault).is_some());
+
+        for mint in &accounts.outcome_mints {
+            let mint_account = svm.get_account(mint).unwrap();
+            let mint = Mint::unpack(&mint_account.data).unwrap();
+            assert_eq!(mint.mint_authority, COption::Some(accounts.market));
+            assert_eq!(mint.decimals, market.decimals);
+        }
+    }
+
+    // the markets trade like any other once the admin makes the first buy
+    buy(&mut svm, &admin, &markets[1], 2, 10_000_000).unwrap();
+}
+
+#[test]
+fn test_init_market_sets_opening_invariant() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "opening", 3, DEFAULT_RESOLVE_AT);
+
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(
+        market.invariant_u256(),
+        U256::from(DEFAULT_SCALE).pow(U256::from(3u64))
+    );
+    for i in 0..3 {
+        assert_eq!(market.required_delta(i).unwrap(), DEFAULT_SCALE);
+    }
+}
@@ -1,17 +1,19 @@
 // LiteSVM docs: https://www.anchor-lang.com/docs/testing/litesvm
 // Example LiteSVM test: https://github.com/brimigs/anchor-escrow-with-litesvm/blob/main/tests/litesvm-tests.rs
 
+mod helpers;
+
 use anchor_lang::AccountDeserialize;
 use anchor_spl::associated_token::{get_associated_token_address, spl_associated_token_account};
 use common::constants::D9_U128;
-use gamma::types::FixedSizeString;
+use gamma::types::{FixedSizeString, InitializationMode, MarketConfig};
 use litesvm::LiteSVM;
 use {
     anchor_lang::{
         prelude::AccountMeta, solana_program::instruction::Instruction, system_program,
         InstructionData, ToAccountMetas,
     },
-    common::constants::{MARKET_SEED, OUTCOME_MINT_SEED, VAULT_SEED},
+    common::constants::{MARKET_SEED, OUTCOME_MINT_SEED, PROGRAM_CONFIG_SEED, VAULT_SEED},
     solana_sdk::{
         program_pack::Pack,
         pubkey::Pubkey,
@@ -24,18 +26,17 @@ use {
 fn test_market() {
     let program_id = gamma::id();
     let mut svm = LiteSVM::new();
-    let bytes = include_bytes!("../../../target/deploy/gamma.so");
-    svm.add_program(program_id, bytes);
-
     let admin = Keypair::new();
+    helpers::deploy_program(&mut svm, &admin.pubkey());
+
     let user = Keypair::new();
     let label = FixedSizeString::new("test_market");
-    let market = Pubkey::find_program_address(&[&MARKET_SEED, label.as_bytes()], &program_id).0;
-    let market_vault = Pubkey::find_program_address(&[&VAULT_SEED, market.as_ref()], &program_id).0;
+    let market = Pubkey::find_program_address(&[MARKET_SEED, label.as_bytes()], &program_id).0;
+    let market_vault = Pubkey::find_program_address(&[VAULT_SEED, market.as_ref()], &program_id).0;
     let outcome_mint_a =
-        Pubkey::find_program_address(&[&OUTCOME_MINT_SEED, market.as_ref(), &[0]], &program_id).0;
+        Pubkey::find_program_address(&[OUTCOME_MINT_SEED, market.as_ref(), &[0]], &program_id).0;
     let outcome_mint_b =
-        Pubkey::find_program_address(&[&OUTCOME_MINT_SEED, market.as_ref(), &[1]], &program_id).0;
+        Pubkey::find_program_address(&[OUTCOME_MINT_SEED, market.as_ref(), &[1]], &program_id).0;
 
     let airdrop_lamports_amount = 100_000_000_000;
     svm.airdrop(&admin.pubkey(), airdrop_lamports_amount)
@@ -51,6 +52,31 @@ fn test_market() {
     let deposit_amount = 100_000_000;
     let resolve_at = std::time::Instant::now().elapsed().as_secs() as i64 + 10;
 
+    let program_config = Pubkey::find_program_address(&[PROGRAM_CONFIG_SEED], &program_id).0;
+
+    // init_program_config
+    {
+        let ix = Instruction::new_with_bytes(
+            program_id,
+            &gamma::instruction::InitProgramConfig {}.data(),
+            gamma::accounts::InitProgramConfig {
+                admin: admin.pubkey(),
+                program: program_id,
+                program_data: helpers::program_data_address(),
+                program_config,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&admin.pubkey()),
+            &[&admin],
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(tx).unwrap();
+    }
+
     // init_market
     {
         let mut accounts_ctx = gamma::accounts::InitMarket {
@@ -58,8 +84,11 @@ fn test_market() {
             rent: anchor_lang::solana_program::sysvar::rent::ID,
             token_program: anchor_spl::token::ID,
             admin: admin.pubkey(),
+            program_config,
             market,
             market_vault,
+            collateral_mint: None,
+            collateral_vault: None,
         }
         .to_account_metas(None);
         accounts_ctx.push(AccountMeta {
@@ -79,6 +108,10 @@ fn test_market() {
                 scale: 100_000,
                 resolve_at,
                 label,
+                config: MarketConfig {
+                    initialization_mode: InitializationMode::Open,
+                    ..Default::default()
+                },
             }
             .data(),
             accounts_ctx,
@@ -108,11 +141,13 @@ fn test_market() {
         );
     }
 
-    // buy outcome A
+    // buy outcome A, opening the market
     {
         let user_outcome_a_token_pda =
             get_associated_token_address(&user.pubkey(), &outcome_mint_a);
-        let accounts_ctx = gamma::accounts::Buy {
+        let user_outcome_b_token_pda =
+            get_associated_token_address(&user.pubkey(), &outcome_mint_b);
+        let mut accounts_ctx = gamma::accounts::Buy {
             user: user.pubkey(),
             market,
             market_vault,
@@ -120,27 +155,34 @@ fn test_market() {
             user_outcome_token_account: user_outcome_a_token_pda,
             token_program: anchor_spl::token::ID,
             system_program: system_program::ID,
+            collateral_vault: None,
+            user_collateral_account: None,
         }
         .to_account_metas(None);
-        let create_ata_ix =
+        // the opening buy also mints the opening supply of outcome B
+        accounts_ctx.push(AccountMeta::new(outcome_mint_b, false));
+        accounts_ctx.push(AccountMeta::new(user_outcome_b_token_pda, false));
+        let create_ata_ixs = [outcome_mint_a, outcome_mint_b].map(|mint| {
             spl_associated_token_account::instruction::create_associated_token_account(
                 &user.pubkey(),
                 &user.pubkey(),
-                &outcome_mint_a,
+                &mint,
                 &spl_token::ID,
-            );
+            )
+        });
         let buy_ix = Instruction::new_with_bytes(
             program_id,
             &gamma::instruction::Buy {
                 outcome_index: 0,
                 amount_in: deposit_amount,
+                min_amount_out: 0,
             }
             .data(),
             accounts_ctx,
         );
 
         let tx = Transaction::new_signed_with_payer(
-            &[create_ata_ix, buy_ix],
+            &[create_ata_ixs[0].clone(), create_ata_ixs[1].clone(), buy_ix],
             Some(&user.pubkey()),
             &[&user],
             svm.latest_blockhash(),
@@ -162,7 +204,7 @@ fn test_market() {
         let user_lamports = svm.get_balance(&user.pubkey()).unwrap();
         assert!(user_lamports < airdrop_lamports_amount);
         let spent_lamports = airdrop_lamports_amount - user_lamports;
-        assert_eq!(spent_lamports, deposit_amount + 2044280);
+        assert_eq!(spent_lamports, deposit_amount + 2 * 2039280 + 5000);
 
         let market_account = svm.get_account(&market).unwrap();
         let market =
@@ -172,8 +214,9 @@ fn test_market() {
             "outcome_a_price after buying A: {}",
             outcome_a_price as f64 / D9_U128 as f64
         );
+        // outcome B opens at 1/2 too, held by the user
         let outcome_b_price = market.outcome_price(1).unwrap();
-        assert_eq!(outcome_b_price, 0);
+        assert_eq!(outcome_b_price, D9_U128 as u64 / 2);
     }
 
     // buy outcome B
@@ -188,10 +231,12 @@ fn test_market() {
             user_outcome_token_account: user_outcome_b_token_pda,
             token_program: anchor_spl::token::ID,
             system_program: system_program::ID,
+            collateral_vault: None,
+            user_collateral_account: None,
         }
         .to_account_metas(None);
         let create_ata_ix =
-            spl_associated_token_account::instruction::create_associated_token_account(
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
                 &user.pubkey(),
                 &user.pubkey(),
                 &outcome_mint_b,
@@ -202,6 +247,7 @@ fn test_market() {
             &gamma::instruction::Buy {
                 outcome_index: 1,
                 amount_in: deposit_amount,
+                min_amount_out: 0,
             }
             .data(),
             accounts_ctx,
@@ -268,6 +314,8 @@ fn test_market() {
             user_outcome_token_account: user_outcome_a_token_pda,
             token_program: anchor_spl::token::ID,
             system_program: system_program::ID,
+            collateral_vault: None,
+            user_collateral_account: None,
         }
         .to_account_metas(None);
         let sell_ix = Instruction::new_with_bytes(
@@ -275,6 +323,7 @@ fn test_market() {
             &gamma::instruction::Sell {
                 outcome_index: 0,
                 burn_amount: user_outcome_a_balance,
+                min_payout: 0,
             }
             .data(),
             accounts_ctx,
@@ -345,6 +394,8 @@ fn test_market() {
             user_outcome_token_account: user_outcome_b_token_pda,
             token_program: anchor_spl::token::ID,
             system_program: system_program::ID,
+            collateral_vault: None,
+            user_collateral_account: None,
         }
         .to_account_metas(None);
         let sell_ix = Instruction::new_with_bytes(
@@ -352,6 +403,7 @@ fn test_market() {
             &gamma::instruction::Sell {
                 outcome_index: 1,
                 burn_amount: user_outcome_b_balance,
+                min_payout: 0,
             }
             .data(),
             accounts_ctx,
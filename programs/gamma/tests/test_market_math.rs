@@ -0,0 +1,1879 @@
+use anchor_lang::prelude::Result;
+use common::constants::{
+    CLOSE_DUST_SUPPLY, D18_U128, D9_U128, FEE_BPS, NO_OUTCOME, OUTCOME_MINT_DECIMALS,
+};
+use common::errors::ErrorCode;
+use gamma::state::Market;
+use gamma::types::{FeeRounding, InitializationMode, MarketPhase};
+use spl_math::uint::U256;
+
+/// Build a market with the given active reserves and matching supplies
+fn market_with_reserves(reserves: &[u64]) -> Market {
+    let mut market = Market {
+        num_outcomes: reserves.len() as u8,
+        void_outcome: NO_OUTCOME,
+        fee_bps: FEE_BPS as u16,
+        decimals: OUTCOME_MINT_DECIMALS,
+        ..Default::default()
+    };
+    for (i, reserve) in reserves.iter().enumerate() {
+        market.reserves[i] = *reserve;
+        market.supplies[i] = *reserve;
+    }
+    market
+}
+
+fn assert_market_error<T: std::fmt::Debug>(result: Result<T>, error: ErrorCode) {
+    assert_eq!(result.unwrap_err(), error.into());
+}
+
+#[test]
+fn test_total_reserves_shared_by_pricing_helpers() {
+    let market = market_with_reserves(&[300_000_000, 500_000_000, 200_000_000]);
+
+    let total = market.total_reserves_u128().unwrap();
+    assert_eq!(total, 1_000_000_000);
+
+    // liquidity_percentages must be each reserve over the same shared total
+    let percentages = market.liquidity_percentages().unwrap();
+    for (percentage, reserve) in percentages.iter().zip(market.reserves.iter()).take(3) {
+        assert_eq!(*percentage as u128, *reserve as u128 * D9_U128 / total);
+    }
+    assert_eq!(percentages[0], 300_000_000);
+
+    // probabilities are the same shares expressed in bps
+    let probabilities = market.probabilities_bps().unwrap();
+    assert_eq!(&probabilities[..3], &[3_000, 5_000, 2_000]);
+
+    // inactive outcomes are excluded from the total
+    let mut market = market;
+    market.reserves[5] = 1_000_000_000;
+    assert_eq!(market.total_reserves_u128().unwrap(), total);
+}
+
+#[test]
+fn test_resolve_requires_min_liquidity() {
+    let mut market = market_with_reserves(&[400_000_000, 500_000_000]);
+
+    // disabled by default
+    market.check_resolve_liquidity().unwrap();
+
+    market.min_resolve_liquidity = 1_000_000_000;
+    assert_market_error(
+        market.check_resolve_liquidity(),
+        ErrorCode::InsufficientResolveLiquidity,
+    );
+
+    // exactly at the threshold is enough
+    market.reserves[0] = 500_000_000;
+    market.check_resolve_liquidity().unwrap();
+}
+
+#[test]
+fn test_void_outcome_refunds_all_holders() {
+    let mut market = market_with_reserves(&[600_000_000, 300_000_000, 100_000_000]);
+    market.supplies[0] = 400_000_000;
+    let pot = 1_000_000_000;
+
+    // without a void outcome the winner takes the whole pot
+    assert_eq!(market.void_outcome(), None);
+    assert_eq!(
+        market.settlement_payout(1, 1, 300_000_000, pot).unwrap(),
+        pot
+    );
+    assert_eq!(market.settlement_payout(1, 0, 400_000_000, pot).unwrap(), 0);
+
+    // when the void outcome wins, every holder gets their liquidation value back
+    market.void_outcome = 2;
+    assert_eq!(market.void_outcome(), Some(2));
+    assert_eq!(
+        market.settlement_payout(2, 0, 200_000_000, pot).unwrap(),
+        300_000_000
+    );
+    assert_eq!(
+        market.settlement_payout(2, 1, 300_000_000, pot).unwrap(),
+        300_000_000
+    );
+    assert_eq!(
+        market.settlement_payout(2, 2, 50_000_000, pot).unwrap(),
+        50_000_000
+    );
+}
+
+#[test]
+fn test_twap_matches_hand_computation() {
+    // outcome 0 priced at 2.0, outcome 1 at 1.0
+    let mut market = market_with_reserves(&[200_000_000, 100_000_000]);
+    market.supplies[0] = 100_000_000;
+    market.last_trade_at = 100;
+
+    let start_cumulative = market.price_cumulative_at(0, 100).unwrap();
+    assert_eq!(start_cumulative, 0);
+
+    // a trade at t=110 moves outcome 0 to 3.0 after 10s at 2.0
+    market.accumulate_prices(110).unwrap();
+    market.reserves[0] = 300_000_000;
+    assert_eq!(market.last_trade_at, 110);
+    assert_eq!(market.price_cumulative(0).unwrap(), 2 * D9_U128 * 10);
+    assert_eq!(market.price_cumulative(1).unwrap(), D9_U128 * 10);
+
+    // 30s later at 3.0 with no further trades: (2.0 * 10 + 3.0 * 30) / 40 = 2.75
+    let twap = market.twap(0, start_cumulative, 100, 140).unwrap();
+    assert_eq!(twap, 2_750_000_000);
+
+    // the untouched outcome averages its constant price
+    assert_eq!(market.twap(1, 0, 100, 140).unwrap(), 1_000_000_000);
+
+    assert_market_error(
+        market.twap(0, start_cumulative, 140, 140),
+        ErrorCode::InvalidTwapWindow,
+    );
+}
+
+#[test]
+fn test_circulating_supply_tracks_trades() {
+    let mut market = market_with_reserves(&[100_000_000, 100_000_000]);
+    market.recompute_invariant().unwrap();
+
+    let minted = market.buy_outcome(0, 50_000_000).unwrap();
+    assert_eq!(market.circulating_supply(0).unwrap(), 100_000_000 + minted);
+
+    let vault_lamports = market.total_reserves_u128().unwrap() as u64;
+    market
+        .sell_outcome(1, 40_000_000, vault_lamports, 0)
+        .unwrap();
+    assert_eq!(market.circulating_supply(1).unwrap(), 60_000_000);
+
+    let snapshot = market.snapshot().unwrap();
+    assert_eq!(
+        snapshot.supplies,
+        vec![
+            market.circulating_supply(0).unwrap(),
+            market.circulating_supply(1).unwrap()
+        ]
+    );
+
+    assert_market_error(market.circulating_supply(2), ErrorCode::InvalidOutcomeIndex);
+}
+
+#[test]
+fn test_settlement_fee_accumulates_across_claims() {
+    let mut market = market_with_reserves(&[100_000_000, 100_000_000]);
+
+    // disabled by default
+    assert_eq!(market.apply_settlement_fee(1_000_000).unwrap(), 1_000_000);
+    assert_eq!(market.undistributed_settlement_fees, 0);
+
+    // 2.5%
+    market.settlement_fee_bps = 250;
+    assert_eq!(market.apply_settlement_fee(1_000_000).unwrap(), 975_000);
+    assert_eq!(market.apply_settlement_fee(400_000).unwrap(), 390_000);
+    // fee floors to zero on dust payouts
+    assert_eq!(market.apply_settlement_fee(39).unwrap(), 39);
+
+    assert_eq!(market.undistributed_settlement_fees, 25_000 + 10_000);
+}
+
+#[test]
+fn test_min_trade_fraction_rejects_dust_buys() {
+    // 1_000 SOL of liquidity
+    let mut market = market_with_reserves(&[600_000_000_000, 400_000_000_000]);
+    market.recompute_invariant().unwrap();
+
+    // disabled by default
+    market.buy_outcome(0, 1).unwrap();
+
+    // 0.01% of total reserves
+    market.min_trade_fraction_bps = 1;
+    let min_amount = market.min_trade_amount().unwrap();
+    assert_eq!(min_amount, 100_000_000);
+
+    assert_market_error(
+        market.buy_outcome(0, min_amount - 1),
+        ErrorCode::TradeTooSmall,
+    );
+    market.buy_outcome(0, min_amount).unwrap();
+}
+
+#[test]
+fn test_withdrawable_fees_never_touch_reserves() {
+    let mut market = market_with_reserves(&[100_000_000, 100_000_000]);
+    market.undistributed_fees = 1_000;
+    market.undistributed_settlement_fees = 500;
+    let rent_min = 890_880;
+    let backed = 200_000_000 + 1_000 + 500 + rent_min;
+
+    // trading and settlement fees are paid together
+    assert_eq!(market.withdrawable_fees(backed, rent_min).unwrap(), 1_500);
+
+    // a single lamport short means the recorded fees would come out of reserves
+    assert_market_error(
+        market.withdrawable_fees(backed - 1, rent_min),
+        ErrorCode::WouldDrainReserves,
+    );
+    assert_market_error(
+        market.withdrawable_fees(999, rent_min),
+        ErrorCode::WouldDrainReserves,
+    );
+}
+
+#[test]
+fn test_withdrawable_fees_after_opening_buy() {
+    let mut market = market_with_reserves(&[0, 0, 0]);
+    market.scale = 1_000;
+    let opening = market.opening_invariant().unwrap();
+    market.set_invariant_u256(opening);
+
+    // the vault holds only what was deposited: the opening buy, the fee and rent
+    market.buy_outcome(0, 10_000).unwrap();
+    market.undistributed_fees = 250;
+    let rent_min = 890_880;
+    let vault = 10_000 + 250 + rent_min;
+
+    assert_eq!(market.total_reserves_u128().unwrap(), 10_000);
+    assert_eq!(market.withdrawable_fees(vault, rent_min).unwrap(), 250);
+}
+
+#[test]
+fn test_all_marginal_prices_match_per_outcome_prices() {
+    let mut market = market_with_reserves(&[300_000_000, 500_000_000, 200_000_000, 0]);
+    market.supplies[0] = 150_000_000;
+    market.supplies[1] = 333_333_333;
+
+    let prices = market.all_marginal_prices().unwrap();
+    for (i, price) in prices.iter().enumerate().take(4) {
+        assert_eq!(*price, market.outcome_price(i).unwrap());
+    }
+    assert_eq!(prices[0], 2_000_000_000);
+    assert_eq!(prices[3], 0);
+
+    // prices are per outcome; the shares of the market are what sum to 1e9, less floor rounding
+    let total: u64 = market.liquidity_percentages().unwrap().iter().sum();
+    assert!(total <= 1_000_000_000 && total > 1_000_000_000 - 4);
+
+    // inactive outcomes are always 0
+    market.reserves[4] = 100_000_000;
+    market.supplies[4] = 100_000_000;
+    let prices = market.all_marginal_prices().unwrap();
+    assert!(prices[4..].iter().all(|price| *price == 0));
+}
+
+#[test]
+fn test_reconcile_supplies_reports_and_repairs() {
+    let mut market = market_with_reserves(&[100_000_000, 200_000_000, 300_000_000]);
+    let mint_supplies = [100_000_000, 150_000_000, 300_000_000];
+
+    // report only leaves the records untouched
+    let mismatches = market.reconcile_supplies(&mint_supplies, false).unwrap();
+    assert_eq!(mismatches, vec![1]);
+    assert_eq!(market.supplies[1], 200_000_000);
+
+    let mismatches = market.reconcile_supplies(&mint_supplies, true).unwrap();
+    assert_eq!(mismatches, vec![1]);
+    assert_eq!(&market.supplies[..3], &mint_supplies);
+    assert!(market
+        .reconcile_supplies(&mint_supplies, false)
+        .unwrap()
+        .is_empty());
+
+    assert_market_error(
+        market.reconcile_supplies(&mint_supplies[..2], true),
+        ErrorCode::InvalidMintCount,
+    );
+}
+
+#[test]
+fn test_sell_deadline_includes_post_expiry_grace() {
+    let mut market = market_with_reserves(&[100_000_000, 100_000_000]);
+    market.resolve_at = 1_000;
+
+    // no grace closes sells with trading
+    assert_eq!(market.sell_deadline().unwrap(), 1_000);
+
+    market.post_expiry_sell_grace = 3_600;
+    assert_eq!(market.sell_deadline().unwrap(), 4_600);
+
+    market.resolve_at = i64::MAX;
+    assert_market_error(market.sell_deadline(), ErrorCode::MathOverflow);
+}
+
+#[test]
+fn test_resolution_pnl_for_each_hypothetical_winner() {
+    // 1 SOL pot, holder owns half of outcome 0's supply and paid 0.2 SOL for it
+    let mut market = market_with_reserves(&[400_000_000, 600_000_000]);
+    let tokens_held = 200_000_000;
+    let cost_basis = 200_000_000;
+
+    // winning takes half the pot
+    assert_eq!(
+        market
+            .resolution_pnl(0, tokens_held, cost_basis, 0)
+            .unwrap(),
+        300_000_000
+    );
+    // losing forfeits the whole cost basis
+    assert_eq!(
+        market
+            .resolution_pnl(0, tokens_held, cost_basis, 1)
+            .unwrap(),
+        -200_000_000
+    );
+
+    // the settlement fee comes out of the winning payout, 1% of 0.5 SOL
+    market.settlement_fee_bps = 100;
+    assert_eq!(
+        market
+            .resolution_pnl(0, tokens_held, cost_basis, 0)
+            .unwrap(),
+        295_000_000
+    );
+    // read only: nothing accrues
+    assert_eq!(market.undistributed_settlement_fees, 0);
+
+    assert_market_error(
+        market.resolution_pnl(0, tokens_held, cost_basis, 2),
+        ErrorCode::InvalidOutcomeIndex,
+    );
+}
+
+#[test]
+fn test_dust_burn_without_refund_is_rejected() {
+    // outcome 0 priced at 0.5, so burning 1 token rounds the refund down to 0
+    let mut market = market_with_reserves(&[100_000_000, 100_000_000]);
+    market.supplies[0] = 200_000_000;
+    market.recompute_invariant().unwrap();
+    let vault_lamports = market.total_reserves_u128().unwrap() as u64;
+
+    assert_market_error(
+        market.sell_outcome(0, 1, vault_lamports, 0),
+        ErrorCode::BurnProducesNoRefund,
+    );
+    assert_eq!(market.supplies[0], 200_000_000);
+
+    // the smallest burn worth a lamport goes through
+    market.sell_outcome(0, 2, vault_lamports, 0).unwrap();
+    assert_eq!(market.supplies[0], 199_999_998);
+}
+
+#[test]
+fn test_can_buy_outcome() {
+    let mut market = market_with_reserves(&[100_000_000, 100_000_000]);
+    market.resolve_at = 1_000;
+
+    assert!(market.can_buy_outcome(0, 999));
+    assert!(market.can_buy_outcome(1, 0));
+
+    // expired
+    assert!(!market.can_buy_outcome(0, 1_000));
+    // inactive outcome
+    assert!(!market.can_buy_outcome(2, 999));
+
+    // the sell grace does not reopen buys
+    market.post_expiry_sell_grace = 600;
+    assert!(!market.can_buy_outcome(0, 1_200));
+
+    // a paused market reopens once unpaused
+    market.paused = 1;
+    assert!(!market.can_buy_outcome(0, 999));
+    assert_market_error(market.check_not_paused(), ErrorCode::MarketPaused);
+    market.paused = 0;
+    assert!(market.can_buy_outcome(0, 999));
+
+    // a market marked as awaiting resolution stays closed to buys
+    let mut awaiting = market;
+    awaiting.awaiting_resolution = 1;
+    assert!(!awaiting.can_buy_outcome(0, 999));
+    assert_market_error(
+        awaiting.check_buy_allowed(0, 999, true),
+        ErrorCode::MarketExpired,
+    );
+
+    // a full cap refuses even the smallest buy
+    let mut capped = market;
+    capped.max_reserve = 100_000_000;
+    assert!(!capped.can_buy_outcome(0, 999));
+    capped.max_reserve = 0;
+    capped.max_total_reserves = 200_000_000;
+    assert!(!capped.can_buy_outcome(1, 999));
+    capped.max_total_reserves = 0;
+    capped.max_supply = 100_000_000;
+    assert!(!capped.can_buy_outcome(0, 999));
+
+    // so does a minimum deposit with no room left under a cap
+    capped.max_supply = 0;
+    capped.max_total_reserves = 250_000_000;
+    assert!(capped.can_buy_outcome(0, 999));
+    capped.min_deposit = 60_000_000;
+    assert!(!capped.can_buy_outcome(0, 999));
+
+    // and an outcome already priced past the buyable limit
+    let mut pricey = market;
+    pricey.max_buyable_price = 400_000_000;
+    assert!(!pricey.can_buy_outcome(0, 999));
+
+    // an unseeded market in seed mode only takes the admin's buy
+    let mut unseeded = market_with_reserves(&[0, 0]);
+    unseeded.resolve_at = 1_000;
+    unseeded.scale = 1_000;
+    unseeded.initialization_mode = InitializationMode::RequireSeed as u8;
+    assert!(!unseeded.can_buy_outcome(0, 999));
+    unseeded.check_buy_allowed(0, 999, true).unwrap();
+    assert_eq!(unseeded.min_buy_amount().unwrap(), 2_000);
+}
+
+#[test]
+fn test_full_set_value_stable_across_trades() {
+    // every outcome priced at 1.0, so a full set of one token each is worth 3 SOL
+    let mut market = market_with_reserves(&[2_000_000_000, 3_000_000_000, 4_000_000_000]);
+    market.recompute_invariant().unwrap();
+    let value = market.full_set_value().unwrap();
+    assert_eq!(value, 3_000_000_000);
+
+    market.buy_outcome(0, 700_000_000).unwrap();
+    market.buy_outcome(2, 123_456_789).unwrap();
+    let vault_lamports = market.total_reserves_u128().unwrap() as u64;
+    market
+        .sell_outcome(1, 1_000_000_000, vault_lamports, 0)
+        .unwrap();
+
+    // trades only move the value by rounding, and never up
+    let after = market.full_set_value().unwrap();
+    assert!(after <= value && value - after <= 3);
+
+    // a thin outcome is capped at its reserve
+    let mut market = market_with_reserves(&[2_000_000_000, 500_000]);
+    assert_eq!(market.full_set_value().unwrap(), 1_000_000_000 + 500_000);
+
+    // a whole token is sized by the market's own decimals
+    market.decimals = 3;
+    assert_eq!(market.full_set_value().unwrap(), 1_000 + 1_000);
+}
+
+#[test]
+fn test_supply_past_u64_is_rejected() {
+    let mut market = market_with_reserves(&[100_000_000, 100_000_000]);
+    market.recompute_invariant().unwrap();
+
+    // a buy that would mint past u64 is rejected instead of truncating
+    market.supplies[0] = u64::MAX - 10;
+    assert_market_error(
+        { market }.buy_outcome(0, 1_000_000),
+        ErrorCode::SupplyTooLarge,
+    );
+
+    // so is a seed minting num_outcomes tokens per lamport past u64
+    let mut market = market_with_reserves(&[0, 0, 0]);
+    assert_market_error(market.seed(u64::MAX / 2), ErrorCode::SupplyTooLarge);
+    market.seed(u64::MAX / 3).unwrap();
+}
+
+#[test]
+fn test_redeemable_lamports_switches_at_resolution() {
+    // outcome 0 priced at 2.0, outcome 1 at 1.0, 1 SOL total
+    let mut market = market_with_reserves(&[600_000_000, 400_000_000]);
+    market.supplies[0] = 300_000_000;
+    market.recompute_invariant().unwrap();
+
+    // before resolution: the curve value less the 0.1% trading fee, same as a sell
+    let value = market.redeemable_lamports(0, 100_000_000).unwrap();
+    assert_eq!(value, 200_000_000 - 200_000);
+    let mut sold = market;
+    let vault_lamports = sold.total_reserves_u128().unwrap() as u64;
+    assert_eq!(
+        sold.sell_outcome(0, 100_000_000, vault_lamports, 0)
+            .unwrap(),
+        value
+    );
+    assert_eq!(
+        market.redeemable_lamports(1, 100_000_000).unwrap(),
+        99_900_000
+    );
+
+    // after resolution: a flat share of the whole pot for winners, nothing for losers
+    market.resolved = 1;
+    market.winning_outcome = 0;
+    assert_eq!(market.winning_outcome(), Some(0));
+    assert_eq!(
+        market.redeemable_lamports(0, 100_000_000).unwrap(),
+        1_000_000_000 / 3
+    );
+    assert_eq!(market.redeemable_lamports(1, 100_000_000).unwrap(), 0);
+
+    assert_market_error(
+        market.redeemable_lamports(2, 1),
+        ErrorCode::InvalidOutcomeIndex,
+    );
+}
+
+#[test]
+fn test_resolution_target_must_have_holders() {
+    let mut market = market_with_reserves(&[100_000_000, 100_000_000, 0]);
+
+    market.check_resolution_target(0, false).unwrap();
+    assert_market_error(
+        market.check_resolution_target(2, false),
+        ErrorCode::NoWinnersForOutcome,
+    );
+    // explicitly resolving to an unheld outcome is allowed
+    market.check_resolution_target(2, true).unwrap();
+
+    // an unheld void outcome refunds everyone, so it never strands the pot
+    market.void_outcome = 2;
+    market.check_resolution_target(2, false).unwrap();
+
+    assert_market_error(
+        market.check_resolution_target(3, true),
+        ErrorCode::InvalidOutcomeIndex,
+    );
+}
+
+#[test]
+fn test_resolve_to_unheld_outcome_refunds_every_holder() {
+    let mut market = market_with_reserves(&[100_000_000, 300_000_000, 0]);
+
+    assert_market_error(
+        { market }.resolve(2, 0, false),
+        ErrorCode::NoWinnersForOutcome,
+    );
+    // a held outcome still takes the whole pot with the flag set
+    let mut held = market;
+    held.resolve(0, 0, true).unwrap();
+    assert!(!held.settles_as_void(0));
+    assert_eq!(
+        held.settlement_payout(0, 0, 100_000_000, 400_000_000)
+            .unwrap(),
+        400_000_000
+    );
+
+    let refund_0 = market.cancel_recovery(0, 50_000_000).unwrap();
+    let refund_1 = market.cancel_recovery(1, 300_000_000).unwrap();
+    market.resolve(2, 0, true).unwrap();
+    assert!(market.settles_as_void(2));
+    assert_eq!(market.phase(0).unwrap(), MarketPhase::Cancelled);
+
+    // every outcome redeems its own reserve, like a void resolution
+    assert_eq!(market.redeem(0, 50_000_000, u64::MAX, 0).unwrap(), refund_0);
+    assert_eq!(
+        market.redeem(1, 300_000_000, u64::MAX, 0).unwrap(),
+        refund_1
+    );
+    assert_eq!(market.reserves[1], 0);
+}
+
+#[test]
+fn test_unseeded_market_only_accepts_admin_buys() {
+    let mut market = market_with_reserves(&[0, 0]);
+
+    // restricted by default
+    assert_market_error(market.check_seeded(false), ErrorCode::MarketNotSeeded);
+    market.check_seeded(true).unwrap();
+
+    market.initialization_mode = InitializationMode::Open as u8;
+    market.check_seeded(false).unwrap();
+
+    // once seeded, anyone may buy in either mode
+    let market = market_with_reserves(&[100_000, 100_000]);
+    market.check_seeded(false).unwrap();
+}
+
+#[test]
+fn test_approx_tokens_out_tracks_buys() {
+    let mut market = market_with_reserves(&[700_000_000, 300_000_000]);
+    market.supplies[0] = 350_000_000;
+    market.recompute_invariant().unwrap();
+
+    for amount_in in [1_000, 1_000_000, 10_000_000] {
+        let approx = market.approx_tokens_out(0, amount_in);
+        let mut bought = market;
+        let exact = bought.buy_outcome(0, amount_in).unwrap();
+        // the curve keeps the price, so the estimate only differs by rounding
+        assert!(approx.abs_diff(exact) <= 1, "{approx} vs {exact}");
+    }
+    assert_eq!(market.approx_tokens_out(0, 2_000_000_000), 1_000_000_000);
+
+    // an outcome nobody holds can't be bought
+    market.supplies[1] = 0;
+    assert_eq!(market.approx_tokens_out(1, 5_000), 0);
+    assert_eq!(market.approx_tokens_out(2, 5_000), 0);
+
+    // the opening buy mints about num_outcomes tokens per lamport
+    let mut empty = market_with_reserves(&[0, 0]);
+    empty.scale = 100_000;
+    let approx = empty.approx_tokens_out(0, 100_000_000);
+    assert_eq!(approx, 200_000_000);
+    let exact = empty.buy_outcome(0, 100_000_000).unwrap();
+    assert!(approx.abs_diff(exact) * 100 < exact, "{approx} vs {exact}");
+}
+
+#[test]
+fn test_donate_fees_moves_fees_into_reserves() {
+    let mut market = market_with_reserves(&[300_000_000, 600_000_000, 100_000_000]);
+    market.recompute_invariant().unwrap();
+    market.undistributed_fees = 1_001;
+    let liabilities = market.liabilities().unwrap();
+
+    assert_eq!(market.donate_fees().unwrap(), 1_001);
+    assert_eq!(market.undistributed_fees, 0);
+    // pro-rata with the rounding remainder on the largest reserve
+    assert_eq!(
+        &market.reserves[..3],
+        &[300_000_300, 600_000_601, 100_000_100]
+    );
+    // the vault owes the same total, now to holders
+    assert_eq!(market.liabilities().unwrap(), liabilities);
+    assert_eq!(market.donate_fees().unwrap(), 0);
+}
+
+#[test]
+fn test_last_price_cached_after_trades() {
+    let mut market = market_with_reserves(&[0, 0, 0]);
+    market.scale = 100_000;
+
+    // the first buy opens every outcome, recording each opening price
+    market.buy_outcome(0, 100_000_000).unwrap();
+    assert_eq!(market.last_price[0], market.outcome_price(0).unwrap());
+    assert_eq!(market.last_price[1], market.outcome_price(1).unwrap());
+    assert_eq!(market.last_price[3], 0);
+
+    market.buy_outcome(1, 50_000_000).unwrap();
+    assert_eq!(market.last_price[1], market.outcome_price(1).unwrap());
+
+    let vault_lamports = market.total_reserves_u128().unwrap() as u64;
+    market
+        .sell_outcome(0, 30_000_000, vault_lamports, 0)
+        .unwrap();
+    assert_eq!(market.last_price[0], market.outcome_price(0).unwrap());
+    assert_eq!(market.last_price[2], 333_333_333);
+}
+
+#[test]
+fn test_sell_fee_rises_toward_resolve_at() {
+    let mut market = market_with_reserves(&[1_000_000_000, 1_000_000_000]);
+    market.recompute_invariant().unwrap();
+    market.resolve_at = 10_000;
+
+    // flat by default
+    assert_eq!(market.sell_fee_bps(9_999), 10);
+
+    // ramp from 10 bps to 200 bps over the last 1_000 seconds
+    market.sell_fee_ramp_secs = 1_000;
+    market.sell_fee_max_bps = 200;
+    assert_eq!(market.sell_fee_bps(8_000), 10);
+    assert_eq!(market.sell_fee_bps(9_000), 10);
+    assert_eq!(market.sell_fee_bps(9_500), 105);
+    assert_eq!(market.sell_fee_bps(9_900), 181);
+    assert_eq!(market.sell_fee_bps(10_000), 200);
+    // held at the max through any post-expiry grace
+    assert_eq!(market.sell_fee_bps(10_500), 200);
+
+    // the same sell pays more as the clock approaches resolve_at
+    let vault_lamports = market.total_reserves_u128().unwrap() as u64;
+    let mut early = market;
+    let mut late = market;
+    let early_payout = early
+        .sell_outcome(0, 100_000_000, vault_lamports, 9_000)
+        .unwrap();
+    let late_payout = late
+        .sell_outcome(0, 100_000_000, vault_lamports, 9_900)
+        .unwrap();
+    assert_eq!(early_payout, 100_000_000 - 100_000);
+    assert_eq!(late_payout, 100_000_000 - 1_810_000);
+    assert_eq!(late.undistributed_fees, 1_810_000);
+}
+
+#[test]
+fn test_market_phase_transitions() {
+    let mut market = market_with_reserves(&[0, 0]);
+    market.resolve_at = 1_000;
+    market.post_expiry_sell_grace = 100;
+
+    // waiting for the admin seed
+    assert_eq!(market.phase(0).unwrap(), MarketPhase::PreOpen);
+    market.initialization_mode = InitializationMode::Open as u8;
+    assert_eq!(market.phase(0).unwrap(), MarketPhase::Trading);
+    market.initialization_mode = InitializationMode::RequireSeed as u8;
+    market.reserves[0] = 100_000;
+    assert_eq!(market.phase(999).unwrap(), MarketPhase::Trading);
+
+    // buys close at resolve_at, sells close after the grace
+    assert_eq!(market.phase(1_000).unwrap(), MarketPhase::SellOnly);
+    assert_eq!(market.phase(1_099).unwrap(), MarketPhase::SellOnly);
+    assert_eq!(market.phase(1_100).unwrap(), MarketPhase::Resolving);
+
+    market.post_expiry_sell_grace = 0;
+    assert_eq!(market.phase(1_000).unwrap(), MarketPhase::Resolving);
+
+    // resolution wins over every clock reading
+    market.resolved = 1;
+    assert_eq!(market.phase(0).unwrap(), MarketPhase::Resolved);
+    assert_eq!(market.phase(5_000).unwrap(), MarketPhase::Resolved);
+
+    // resolving to the void outcome cancels the market instead
+    market.void_outcome = 1;
+    assert_eq!(market.phase(5_000).unwrap(), MarketPhase::Resolved);
+    market.winning_outcome = 1;
+    assert_eq!(market.phase(0).unwrap(), MarketPhase::Cancelled);
+    assert_eq!(market.phase(5_000).unwrap(), MarketPhase::Cancelled);
+}
+
+#[test]
+fn test_mark_awaiting_resolution_records_expiry() {
+    let mut market = market_with_reserves(&[100_000, 100_000]);
+    market.resolve_at = 1_000;
+    market.post_expiry_sell_grace = 100;
+
+    assert!(!market.mark_awaiting_resolution(999).unwrap());
+    assert_eq!(market.awaiting_resolution, 0);
+
+    // the first instruction past resolve_at records it, and the record sticks
+    assert!(market.mark_awaiting_resolution(1_000).unwrap());
+    assert!({ market }.mark_awaiting_resolution(0).unwrap());
+    assert_eq!(market.phase(999).unwrap(), MarketPhase::SellOnly);
+    assert_eq!(market.phase(1_100).unwrap(), MarketPhase::Resolving);
+
+    // postponing reopens trading
+    market.update_resolve_at(2_000, 1_500).unwrap();
+    assert_eq!(market.awaiting_resolution, 0);
+    assert_eq!(market.phase(1_500).unwrap(), MarketPhase::Trading);
+
+    market.resolved = 1;
+    assert_market_error(
+        market.mark_awaiting_resolution(2_000),
+        ErrorCode::MarketResolved,
+    );
+}
+
+#[test]
+fn test_estimated_cu_scales_linearly() {
+    let one = Market::estimated_cu(1);
+    let per_op = Market::estimated_cu(2) - one;
+    assert_eq!(per_op, Market::ESTIMATED_CU_PER_OP);
+    for num_ops in 0..10 {
+        assert_eq!(
+            Market::estimated_cu(num_ops),
+            Market::ESTIMATED_BASE_CU + num_ops * per_op
+        );
+    }
+
+    // never asks for more than a transaction may use
+    assert_eq!(Market::estimated_cu(u32::MAX), Market::MAX_TRANSACTION_CU);
+}
+
+#[test]
+fn test_cost_to_lead_makes_outcome_the_favorite() {
+    let mut market = market_with_reserves(&[500_000_000, 300_000_000, 200_000_000]);
+    market.recompute_invariant().unwrap();
+
+    assert_eq!(market.cost_to_lead(0).unwrap(), 0);
+
+    let cost = market.cost_to_lead(2).unwrap();
+    assert_eq!(cost, 300_000_001);
+
+    // one lamport less only ties the favorite
+    let mut short = market;
+    short.buy_outcome(2, cost - 1).unwrap();
+    let probabilities = short.probabilities_bps().unwrap();
+    assert_eq!(short.reserves[2], short.reserves[0]);
+    assert_eq!(probabilities[2], probabilities[0]);
+
+    market.buy_outcome(2, cost).unwrap();
+    assert!(market.reserves[2] > market.reserves[0]);
+    assert_eq!(market.cost_to_lead(2).unwrap(), 0);
+    assert!(market.cost_to_lead(0).unwrap() > 0);
+}
+
+#[test]
+fn test_buy_quotes_include_the_buy_fee() {
+    let mut market = market_with_reserves(&[500_000_000, 300_000_000, 200_000_000]);
+    market.recompute_invariant().unwrap();
+    market.buy_fee_bps = 250;
+
+    // the fee stays outside the reserves, so the quote covers it on top of the gap
+    let cost = market.cost_to_lead(2).unwrap();
+    assert!(cost > 300_000_001);
+    let mut short = market;
+    short.buy_outcome(2, cost - 1).unwrap();
+    assert!(short.reserves[2] <= short.reserves[0]);
+    let mut led = market;
+    led.buy_outcome(2, cost).unwrap();
+    assert!(led.reserves[2] > led.reserves[0]);
+    assert_eq!(led.cost_to_lead(2).unwrap(), 0);
+
+    // ceil rounding takes a lamport more, and the quote still leads
+    market.fee_rounding = FeeRounding::Ceil as u8;
+    let cost = market.cost_to_lead(2).unwrap();
+    let mut led = market;
+    led.buy_outcome(2, cost).unwrap();
+    assert!(led.reserves[2] > led.reserves[0]);
+
+    // the estimate prices only the net deposit
+    let approx = market.approx_tokens_out(0, 10_000_000);
+    let exact = market.quote_buy(0, 10_000_000).unwrap();
+    assert!(approx.abs_diff(exact) <= 1, "{approx} vs {exact}");
+
+    // an empty market is led by the first buy's surplus over the opening reserves
+    let mut empty = market_with_reserves(&[0, 0]);
+    empty.scale = 1_000;
+    empty.buy_fee_bps = 250;
+    let cost = empty.cost_to_lead(1).unwrap();
+    empty.buy_outcome(1, cost).unwrap();
+    assert!(empty.reserves[1] > empty.reserves[0]);
+}
+
+#[test]
+fn test_max_buy_within_price() {
+    // outcome 0 priced at 1.0, outcome 1 at 2.0
+    let mut market = market_with_reserves(&[1_000_000_000, 2_000_000_000]);
+    market.supplies[1] = 1_000_000_000;
+    market.recompute_invariant().unwrap();
+
+    // already above the bound
+    assert_eq!(market.max_buy_within_price(1, 1_999_999_999).unwrap(), 0);
+
+    let amount = market.max_buy_within_price(0, 1_000_000_000).unwrap();
+    assert!(amount > 0);
+
+    // buying the whole amount leaves the price at the bound
+    let mut bought = market;
+    bought.buy_outcome(0, amount).unwrap();
+    assert_eq!(bought.outcome_price(0).unwrap(), 1_000_000_000);
+
+    // the limit is reserve headroom: the reserve reaches u64::MAX
+    assert_eq!(bought.reserves[0], u64::MAX);
+    assert_eq!(bought.max_buy_within_price(0, 1_000_000_000).unwrap(), 0);
+
+    // with enough outcomes the invariant overflows first, and the bound stops short of it
+    let mut market = market_with_reserves(&[1_000_000_000_000_000; 5]);
+    market.recompute_invariant().unwrap();
+    let amount = market.max_buy_within_price(0, u64::MAX).unwrap();
+    assert!(amount < u64::MAX - market.reserves[0]);
+    let mut bought = market;
+    bought.buy_outcome(0, amount).unwrap();
+    assert_market_error(bought.buy_outcome(0, 1), ErrorCode::MathOverflow);
+
+    // the bound is on the deposit, so it pays the buy fee on top and still fits
+    market.buy_fee_bps = 100;
+    let with_fee = market.max_buy_within_price(0, u64::MAX).unwrap();
+    assert!(with_fee > amount);
+    let mut bought = market;
+    bought.buy_outcome(0, with_fee).unwrap();
+    assert_market_error(
+        { market }.buy_outcome(0, with_fee + 1),
+        ErrorCode::MathOverflow,
+    );
+}
+
+#[test]
+fn test_fee_conversion_at_u64_boundary() {
+    let mut market = market_with_reserves(&[u64::MAX, 1]);
+
+    // the largest refund converts back to u64 without truncating
+    assert_eq!(
+        market.trading_fee(u64::MAX).unwrap(),
+        (u64::MAX as u128 * 10 / 10_000) as u64
+    );
+    market.settlement_fee_bps = 10_000;
+    assert_eq!(market.settlement_fee(u64::MAX).unwrap(), u64::MAX);
+
+    // selling the whole supply refunds the whole u64::MAX reserve
+    assert_eq!(
+        market.redeemable_lamports(0, u64::MAX).unwrap(),
+        u64::MAX - market.trading_fee(u64::MAX).unwrap()
+    );
+    let payout = market.sell_outcome(0, u64::MAX, u64::MAX, 0).unwrap();
+    assert_eq!(payout, u64::MAX - market.trading_fee(u64::MAX).unwrap());
+}
+
+#[test]
+fn test_fee_rounding_direction() {
+    let floor = market_with_reserves(&[1_000_000, 1_000_000]);
+    let ceil = Market {
+        fee_rounding: FeeRounding::Ceil as u8,
+        ..floor
+    };
+
+    // 999 * 10 bps = 0.999 lamports: floor charges nothing, ceil charges one lamport
+    assert_eq!(floor.trading_fee(999).unwrap(), 0);
+    assert_eq!(ceil.trading_fee(999).unwrap(), 1);
+    // an exact multiple rounds the same either way
+    assert_eq!(floor.trading_fee(1_000).unwrap(), 1);
+    assert_eq!(ceil.trading_fee(1_000).unwrap(), 1);
+
+    // the seller's net payout differs by exactly that lamport and never goes negative
+    let mut floor_sell = floor;
+    let mut ceil_sell = ceil;
+    let floor_payout = floor_sell.sell_outcome(0, 999, u64::MAX, 0).unwrap();
+    let ceil_payout = ceil_sell.sell_outcome(0, 999, u64::MAX, 0).unwrap();
+    assert_eq!(floor_payout, ceil_payout + 1);
+    let mut tiny = Market {
+        settlement_fee_bps: 10_000,
+        ..ceil
+    };
+    assert_eq!(tiny.settlement_fee(1).unwrap(), 1);
+    assert_eq!(tiny.sell_outcome(0, 1, u64::MAX, 0).unwrap(), 0);
+}
+
+#[test]
+fn test_resolve_closes_trading() {
+    let mut market = market_with_reserves(&[1_000_000, 1_000_000]);
+    market.resolve_at = 100;
+
+    assert_market_error(market.resolve(0, 99, false), ErrorCode::MarketNotExpired);
+    assert_market_error(
+        market.resolve(2, 100, false),
+        ErrorCode::InvalidOutcomeIndex,
+    );
+    assert!(market.can_buy_outcome(0, 0));
+
+    market.resolve(1, 100, false).unwrap();
+    assert_eq!(market.winning_outcome(), Some(1));
+    assert!(!market.can_buy_outcome(0, 0));
+    assert_market_error(market.check_not_resolved(), ErrorCode::MarketResolved);
+    assert_market_error(market.resolve(0, 100, false), ErrorCode::MarketResolved);
+}
+
+#[test]
+fn test_vault_health_flags_shortfall() {
+    let mut market = market_with_reserves(&[600, 400]);
+    market.undistributed_fees = 50;
+
+    let health = market.vault_health(1_060, 10).unwrap();
+    assert_eq!(health.sum_reserves, 1_000);
+    assert!(health.is_solvent);
+
+    // one lamport short of reserves + fees + rent
+    assert!(!market.vault_health(1_059, 10).unwrap().is_solvent);
+}
+
+#[test]
+fn test_redeem_splits_pot_between_winners() {
+    let mut market = market_with_reserves(&[600, 400]);
+    market.resolve_at = 0;
+    assert_market_error(
+        market.redeem(0, 300, 1_000, 0),
+        ErrorCode::MarketNotResolved,
+    );
+
+    market.resolve(0, 0, false).unwrap();
+    assert_market_error(
+        market.redeem(1, 400, 1_000, 0),
+        ErrorCode::NotWinningOutcome,
+    );
+    assert_market_error(
+        market.redeem(0, 300, 499, 0),
+        ErrorCode::InsufficientVaultFunds,
+    );
+    // the rent-exempt minimum can't fund a payout
+    assert_market_error(
+        market.redeem(0, 300, 509, 10),
+        ErrorCode::InsufficientVaultFunds,
+    );
+
+    // two holders of half the winning supply each take half the pot
+    assert_eq!(market.redeem(0, 300, 1_000, 0).unwrap(), 500);
+    assert_eq!(market.total_reserves_u128().unwrap(), 500);
+    assert_eq!(market.redeem(0, 300, 500, 0).unwrap(), 500);
+    assert_eq!(market.total_reserves_u128().unwrap(), 0);
+    assert_eq!(market.supplies[0], 0);
+}
+
+#[test]
+fn test_redeem_void_refunds_from_own_reserve() {
+    let mut market = market_with_reserves(&[600, 400, 100]);
+    market.void_outcome = 2;
+    market.settlement_fee_bps = 1_000;
+    market.resolve(2, 0, false).unwrap();
+
+    // every outcome is refunded at liquidation value, less the settlement fee
+    assert_eq!(market.redeem(1, 200, 1_100, 0).unwrap(), 180);
+    assert_eq!(market.reserves[1], 200);
+    assert_eq!(market.reserves[0], 600);
+    assert_eq!(market.undistributed_settlement_fees, 20);
+}
+
+#[test]
+fn test_swap_outcome_keeps_refund_in_reserves() {
+    let mut market = market_with_reserves(&[1_000_000_000, 1_000_000_000]);
+    let total_before = market.total_reserves_u128().unwrap();
+
+    assert_market_error(
+        market.swap_outcome(0, 0, 100_000_000, 0, u64::MAX, 0),
+        ErrorCode::InvalidOutcomeIndex,
+    );
+    assert_market_error(
+        market.swap_outcome(0, 1, 100_000_000, u64::MAX, u64::MAX, 0),
+        ErrorCode::SlippageExceeded,
+    );
+
+    let fill = market
+        .swap_outcome(0, 1, 100_000_000, 0, u64::MAX, 0)
+        .unwrap();
+    assert!(fill.amount_out > 0);
+    assert_eq!(market.supplies[0], 900_000_000);
+    // 100M tokens of a 1:1 outcome refund 100M, split between the buy and the fee
+    assert_eq!(fill.net_refund + fill.sell_fee, 100_000_000);
+    assert_eq!(fill.sell_fee, market.undistributed_fees);
+    // only the sell fee moved out of the reserves
+    assert_eq!(
+        market.total_reserves_u128().unwrap() + market.undistributed_fees as u128,
+        total_before
+    );
+}
+
+#[test]
+fn test_cheap_positions_carry_more_leverage() {
+    // outcome 0 is a 10% underdog, outcome 1 the 90% favorite
+    let mut market = market_with_reserves(&[100_000_000, 900_000_000]);
+    market.recompute_invariant().unwrap();
+    let underdog_tokens = market.buy_outcome(0, 10_000_000).unwrap();
+    let favorite_tokens = market.buy_outcome(1, 10_000_000).unwrap();
+
+    let underdog = market
+        .position_leverage(0, underdog_tokens, 10_000_000)
+        .unwrap();
+    let favorite = market
+        .position_leverage(1, favorite_tokens, 10_000_000)
+        .unwrap();
+    assert!(favorite > D9_U128 as u64);
+    assert!(underdog > favorite);
+    // the underdog pays roughly 1 / 0.1 = 10x its cost if it wins
+    assert!(underdog > 9 * D9_U128 as u64 && underdog < 10 * D9_U128 as u64);
+
+    assert_market_error(
+        market.position_leverage(0, underdog_tokens, 0),
+        ErrorCode::DepositIsZero,
+    );
+}
+
+#[test]
+fn test_break_even_probability_tracks_price() {
+    // outcome 0 is a 10% underdog, outcome 1 the 90% favorite
+    let mut market = market_with_reserves(&[100_000_000, 900_000_000]);
+    market.recompute_invariant().unwrap();
+    let d9 = D9_U128 as u64;
+
+    let cheap = market.break_even_probability(0, 10_000_000).unwrap();
+    let expensive = market.break_even_probability(1, 10_000_000).unwrap();
+
+    // the buy itself moves the outcome's share of the pot, so break even sits near the
+    // post-trade probability: ~11% for the underdog and ~90% for the favorite
+    assert!(cheap > d9 / 10 && cheap < d9 / 8, "{cheap}");
+    assert!(expensive > 89 * d9 / 100 && expensive < d9, "{expensive}");
+
+    // a settlement fee raises the bar
+    market.settlement_fee_bps = 1_000;
+    assert!(market.break_even_probability(0, 10_000_000).unwrap() > cheap);
+
+    // quoting leaves the market untouched
+    assert_eq!(market.reserves[0], 100_000_000);
+    assert_eq!(market.supplies[0], 100_000_000);
+}
+
+#[test]
+fn test_quote_buy_matches_buy_without_mutating() {
+    let mut market = market_with_reserves(&[700_000_000, 300_000_000]);
+    market.supplies[0] = 350_000_000;
+    market.recompute_invariant().unwrap();
+
+    for amount_in in [1, 1_000, 1_000_000, 10_000_000, 1_000_000_000] {
+        let before = market;
+        let quote = market.quote_buy(0, amount_in).unwrap();
+        assert_eq!(market.reserves, before.reserves);
+        assert_eq!(market.supplies, before.supplies);
+        assert_eq!(market.invariant, before.invariant);
+
+        assert_eq!(market.buy_outcome(0, amount_in).unwrap(), quote);
+    }
+
+    assert_market_error(market.quote_buy(2, 1_000), ErrorCode::InvalidOutcomeIndex);
+}
+
+#[test]
+fn test_virtual_reserve_dampens_extreme_probabilities() {
+    // outcome 0 holds 99% of the reserves
+    let mut market = market_with_reserves(&[990_000_000, 10_000_000]);
+    assert_eq!(market.probabilities_bps().unwrap()[..2], [9_900, 100]);
+
+    market.virtual_reserve = 100_000_000;
+    // (990 + 100) / 1200 and (10 + 100) / 1200
+    assert_eq!(market.probabilities_bps().unwrap()[..2], [9_083, 917]);
+    assert_eq!(
+        market.liquidity_percentages().unwrap()[..2],
+        [908_333_333, 91_666_666]
+    );
+
+    // payouts still come from the real reserves
+    assert_eq!(market.total_reserves_u128().unwrap(), 1_000_000_000);
+    assert_eq!(
+        market
+            .settlement_payout(1, 1, 10_000_000, 1_000_000_000)
+            .unwrap(),
+        1_000_000_000
+    );
+
+    // an unseeded market still has no prices
+    let empty = Market {
+        virtual_reserve: 100_000_000,
+        ..market_with_reserves(&[0, 0])
+    };
+    assert_eq!(empty.probabilities_bps().unwrap()[..2], [0, 0]);
+}
+
+#[test]
+fn test_quote_sell_matches_sell_without_mutating() {
+    let mut market = market_with_reserves(&[700_000_000, 300_000_000]);
+    market.supplies[0] = 350_000_000;
+    market.recompute_invariant().unwrap();
+    market.resolve_at = 1_000;
+    market.sell_fee_ramp_secs = 100;
+    market.sell_fee_max_bps = 500;
+
+    // the quote follows the sell fee ramp
+    for (burn_amount, now) in [(1_000, 0), (10_000_000, 950), (100_000_000, 1_000)] {
+        let before = market;
+        let quote = market.quote_sell(0, burn_amount, now).unwrap();
+        assert_eq!(market.reserves, before.reserves);
+        assert_eq!(market.undistributed_fees, before.undistributed_fees);
+
+        assert_eq!(
+            market.sell_outcome(0, burn_amount, u64::MAX, now).unwrap(),
+            quote
+        );
+    }
+    assert_market_error(
+        market.quote_sell(0, u64::MAX, 0),
+        ErrorCode::BurnIsMoreThanSupply,
+    );
+}
+
+#[test]
+fn test_set_limits_rejects_caps_below_usage() {
+    let mut market = market_with_reserves(&[300_000_000, 500_000_000]);
+
+    assert_market_error(
+        market.set_limits(0, 499_999_999, 0, 0),
+        ErrorCode::InvalidLimits,
+    );
+    assert_market_error(
+        market.set_limits(0, 0, 799_999_999, 0),
+        ErrorCode::InvalidLimits,
+    );
+    assert_market_error(
+        market.set_limits(0, 0, 0, 499_999_999),
+        ErrorCode::InvalidLimits,
+    );
+
+    market
+        .set_limits(1_000, 500_000_000, 800_000_000, 500_000_000)
+        .unwrap();
+    assert_eq!(market.min_deposit, 1_000);
+    assert_eq!(market.max_reserve, 500_000_000);
+    assert_eq!(market.max_total_reserves, 800_000_000);
+    assert_eq!(market.max_supply, 500_000_000);
+
+    // zero clears every cap
+    market.set_limits(0, 0, 0, 0).unwrap();
+    assert_eq!(market.max_reserve, 0);
+}
+
+#[test]
+fn test_buy_respects_limits() {
+    let mut market = market_with_reserves(&[300_000_000, 500_000_000]);
+    market.recompute_invariant().unwrap();
+    market.set_limits(10_000_000, 0, 850_000_000, 0).unwrap();
+
+    // rejected buys run on copies, as a failed instruction would revert the account
+    assert_market_error(
+        { market }.buy_outcome(0, 9_999_999),
+        ErrorCode::TradeTooSmall,
+    );
+    assert_market_error(
+        { market }.buy_outcome(0, 60_000_000),
+        ErrorCode::MarketCapExceeded,
+    );
+    market.buy_outcome(0, 50_000_000).unwrap();
+    assert_eq!(market.total_reserves_u128().unwrap(), 850_000_000);
+}
+
+#[test]
+fn test_is_solvent_matches_assert_solvent() {
+    let mut market = market_with_reserves(&[600, 400]);
+    market.undistributed_fees = 30;
+    market.undistributed_settlement_fees = 20;
+    let rent_min = 10;
+
+    // a funded vault covers reserves, both fee pools and rent
+    assert!(market.is_solvent(1_060, rent_min));
+    assert!(market.is_solvent(5_000, rent_min));
+    market.assert_solvent(1_060, rent_min).unwrap();
+
+    // one lamport short flips it without erroring
+    assert!(!market.is_solvent(1_059, rent_min));
+    assert!(!market.is_solvent(0, rent_min));
+    assert_market_error(
+        market.assert_solvent(1_059, rent_min),
+        ErrorCode::MarketInsolvent,
+    );
+}
+
+#[test]
+fn test_sweep_surplus_keeps_liabilities_and_funding() {
+    let mut market = market_with_reserves(&[600, 400]);
+    market.undistributed_fees = 30;
+    market.undistributed_settlement_fees = 20;
+    market.vault_funding = 100;
+    let rent_min = 10;
+    // reserves + fees + rent + funding
+    let kept = 1_000 + 30 + 20 + 10 + 100;
+
+    // nothing beyond what is owed and kept
+    assert_eq!(market.sweep_surplus(kept, rent_min).unwrap(), 0);
+    assert_eq!(market.undistributed_fees, 30);
+
+    // a short vault sweeps nothing rather than erroring
+    assert_eq!(market.sweep_surplus(kept - 500, rent_min).unwrap(), 0);
+
+    assert_eq!(market.sweep_surplus(kept + 75, rent_min).unwrap(), 75);
+    assert_eq!(market.undistributed_fees, 105);
+    assert_eq!(market.reserves[..2], [600, 400]);
+
+    // the swept lamports are now owed as fees, so a second sweep finds nothing
+    assert_eq!(market.sweep_surplus(kept + 75, rent_min).unwrap(), 0);
+    assert!(market.is_solvent(kept + 75, rent_min));
+}
+
+#[test]
+fn test_fee_bps_sets_sell_fee() {
+    let cheap = market_with_reserves(&[300_000_000, 500_000_000]);
+    let mut pricey = cheap;
+    pricey.fee_bps = 250;
+
+    assert_eq!(cheap.sell_fee_bps(0), FEE_BPS);
+    assert_eq!(pricey.sell_fee_bps(0), 250);
+    assert_eq!(pricey.trading_fee(1_000_000).unwrap(), 25_000);
+
+    let vault_lamports = cheap.total_reserves_u128().unwrap() as u64;
+    let cheap_payout = { cheap }
+        .sell_outcome(0, 100_000_000, vault_lamports, 0)
+        .unwrap();
+    let pricey_payout = { pricey }
+        .sell_outcome(0, 100_000_000, vault_lamports, 0)
+        .unwrap();
+    // both refund the same 100_000_000 lamports before fees
+    assert_eq!(cheap_payout, 100_000_000 - 100_000);
+    assert_eq!(pricey_payout, 100_000_000 - 2_500_000);
+
+    // the sell fee ramp starts from the market's own base fee
+    pricey.resolve_at = 100;
+    pricey.sell_fee_ramp_secs = 100;
+    pricey.sell_fee_max_bps = 450;
+    assert_eq!(pricey.sell_fee_bps(50), 350);
+}
+
+#[test]
+fn test_buy_fee_reduces_effective_input() {
+    let mut free = market_with_reserves(&[300_000_000, 500_000_000]);
+    free.recompute_invariant().unwrap();
+    // 1%
+    let mut charged = free;
+    charged.buy_fee_bps = 100;
+
+    let minted = charged.buy_outcome(0, 10_000_000).unwrap();
+    assert_eq!(charged.undistributed_fees, 100_000);
+    assert_eq!(charged.lifetime_fees, 100_000);
+    assert_eq!(charged.reserves[0], 300_000_000 + 9_900_000);
+
+    // mints exactly what a fee-free buy of the post-fee amount would
+    assert_eq!(minted, free.buy_outcome(0, 9_900_000).unwrap());
+    assert_eq!(free.undistributed_fees, 0);
+
+    // a fee that leaves nothing to put on the curve rejects the buy
+    charged.buy_fee_bps = 10_000;
+    assert_market_error(charged.quote_buy(0, 10_000_000), ErrorCode::TradeTooSmall);
+}
+
+#[test]
+fn test_tokens_for_payout_inverts_quote_sell() {
+    let mut market = market_with_reserves(&[300_000_000, 500_000_000]);
+    market.supplies[0] = 123_456_789;
+    market.recompute_invariant().unwrap();
+
+    for rounding in [FeeRounding::Floor, FeeRounding::Ceil] {
+        market.fee_rounding = rounding as u8;
+        for desired in [1, 999, 1_000_000, 77_777_777, 250_000_000] {
+            let burn = market.tokens_for_payout(0, desired, 0).unwrap();
+            assert!(market.quote_sell(0, burn, 0).unwrap() >= desired);
+            // one token fewer never nets more than asked for
+            if burn > 1 {
+                assert!(market.quote_sell(0, burn - 1, 0).unwrap() <= desired);
+            }
+        }
+    }
+
+    // a ramped sell fee needs a larger burn for the same payout
+    market.resolve_at = 100;
+    market.sell_fee_ramp_secs = 100;
+    market.sell_fee_max_bps = 1_000;
+    let early = market.tokens_for_payout(0, 100_000_000, 0).unwrap();
+    let late = market.tokens_for_payout(0, 100_000_000, 100).unwrap();
+    assert!(late > early);
+    assert!(market.quote_sell(0, late, 100).unwrap() >= 100_000_000);
+
+    // the whole supply refunds the reserve less the fee, and no more
+    let max_net = market.quote_sell(0, 123_456_789, 0).unwrap();
+    assert_eq!(
+        market.tokens_for_payout(0, max_net, 0).unwrap(),
+        123_456_789
+    );
+    assert_market_error(
+        market.tokens_for_payout(0, max_net + 1, 0),
+        ErrorCode::PayoutUnreachable,
+    );
+    assert_eq!(market.tokens_for_payout(0, 0, 0).unwrap(), 0);
+}
+
+#[test]
+fn test_check_closable_ignores_losing_supply_and_dust() {
+    let mut market = market_with_reserves(&[600_000, 400_000, 100_000]);
+    assert_market_error(market.check_closable(), ErrorCode::MarketNotResolved);
+
+    market.resolve(0, 0, false).unwrap();
+    assert_market_error(market.check_closable(), ErrorCode::MarketNotEmpty);
+
+    // losing supplies can't redeem, and a dust winning supply is abandoned
+    market.supplies[0] = CLOSE_DUST_SUPPLY;
+    market.check_closable().unwrap();
+    market.supplies[0] = CLOSE_DUST_SUPPLY + 1;
+    assert_market_error(market.check_closable(), ErrorCode::MarketNotEmpty);
+
+    // unclaimed fees belong to the fee recipient
+    market.supplies[0] = 0;
+    market.undistributed_fees = 1;
+    assert_market_error(market.check_closable(), ErrorCode::MarketNotEmpty);
+    market.undistributed_fees = 0;
+    market.undistributed_settlement_fees = 1;
+    assert_market_error(market.check_closable(), ErrorCode::MarketNotEmpty);
+    market.undistributed_settlement_fees = 0;
+    market.check_closable().unwrap();
+
+    // after a void resolution every outcome can redeem
+    let mut market = market_with_reserves(&[600_000, 400_000, 100_000]);
+    market.void_outcome = 2;
+    market.resolve(2, 0, false).unwrap();
+    market.supplies[2] = 0;
+    assert_market_error(market.check_closable(), ErrorCode::MarketNotEmpty);
+    market.supplies[0] = 0;
+    market.supplies[1] = 0;
+    market.check_closable().unwrap();
+}
+
+#[test]
+fn test_simulate_resolution_pays_holders_the_pot() {
+    let mut market = market_with_reserves(&[600, 400, 100]);
+    market.supplies[2] = 0;
+
+    // a held winner takes every reserve, an unheld one leaves them unclaimed
+    assert_eq!(market.simulate_resolution(0).unwrap(), 1_100);
+    assert_eq!(market.simulate_resolution(1).unwrap(), 1_100);
+    assert_eq!(market.simulate_resolution(2).unwrap(), 0);
+    assert_market_error(
+        market.simulate_resolution(3),
+        ErrorCode::InvalidOutcomeIndex,
+    );
+
+    // a void resolution refunds each held outcome its own reserve
+    market.void_outcome = 2;
+    assert_eq!(market.simulate_resolution(2).unwrap(), 1_000);
+}
+
+#[test]
+fn test_resolve_requires_min_trades() {
+    let mut market = market_with_reserves(&[0, 0]);
+    market.scale = 1_000_000;
+    market.min_trades_before_resolve = 3;
+
+    market.buy_outcome(0, 100_000_000).unwrap();
+    market.buy_outcome(1, 100_000_000).unwrap();
+    assert_eq!(market.trade_count, 2);
+    assert_market_error(
+        { market }.resolve(0, 0, false),
+        ErrorCode::InsufficientResolveActivity,
+    );
+
+    // a swap is a sell and a buy
+    market
+        .swap_outcome(0, 1, 10_000_000, 0, u64::MAX, 0)
+        .unwrap();
+    assert_eq!(market.trade_count, 4);
+    market.resolve(0, 0, false).unwrap();
+}
+
+#[test]
+fn test_price_elasticity_matches_finite_difference() {
+    // implied probability of outcome 0 in D18, padded like the market pads it
+    fn share(market: &Market) -> u128 {
+        let n = market.num_outcomes as usize;
+        let virtual_reserve = market.virtual_reserve as u128;
+        let total: u128 = market.reserves[..n]
+            .iter()
+            .map(|r| *r as u128 + virtual_reserve)
+            .sum();
+        (market.reserves[0] as u128 + virtual_reserve) * D18_U128 / total
+    }
+
+    let mut plain = market_with_reserves(&[600_000_000, 400_000_000]);
+    plain.recompute_invariant().unwrap();
+    let mut padded = market_with_reserves(&[600_000_000, 400_000_000, 200_000_000]);
+    padded.virtual_reserve = 100_000_000;
+    padded.buy_fee_bps = 250;
+    padded.recompute_invariant().unwrap();
+
+    let delta = 10_000;
+    for market in [plain, padded] {
+        let mut bought = market;
+        bought.buy_outcome(0, delta).unwrap();
+        let estimate = (share(&bought) - share(&market)) / delta as u128;
+
+        let analytic = market.price_elasticity(0).unwrap() as u128;
+        assert!(analytic > 0);
+        // the secant over a small buy sits within 0.1% of the slope
+        assert!(analytic.abs_diff(estimate) * 1_000 < analytic);
+    }
+
+    assert_market_error(plain.price_elasticity(2), ErrorCode::InvalidOutcomeIndex);
+    assert_eq!(
+        market_with_reserves(&[0, 0]).price_elasticity(0).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn test_total_supply_sums_active_supplies() {
+    let mut market = market_with_reserves(&[0, 0, 0]);
+    market.scale = 1_000_000;
+    assert_eq!(market.total_supply().unwrap(), 0);
+
+    market.buy_outcome(0, 300_000_000).unwrap();
+    market.buy_outcome(1, 120_000_000).unwrap();
+    market.buy_outcome(0, 50_000_000).unwrap();
+    market.sell_outcome(0, 40_000_000, u64::MAX, 0).unwrap();
+    market
+        .swap_outcome(1, 2, 20_000_000, 0, u64::MAX, 0)
+        .unwrap();
+
+    let sum: u128 = market.supplies[..3].iter().map(|s| *s as u128).sum();
+    assert_eq!(market.total_supply().unwrap(), sum);
+    assert_eq!(market.snapshot().unwrap().total_supply, sum);
+
+    // inactive outcomes are not counted
+    market.supplies[3] = 1;
+    assert_eq!(market.total_supply().unwrap(), sum);
+}
+
+#[test]
+fn test_update_resolve_at() {
+    let mut market = market_with_reserves(&[100_000_000, 100_000_000]);
+    market.resolve_at = 1_000;
+
+    assert_market_error(
+        market.update_resolve_at(1_500, 1_500),
+        ErrorCode::InvalidResolveTime,
+    );
+    market.update_resolve_at(2_000, 1_500).unwrap();
+    assert_eq!(market.resolve_at, 2_000);
+    assert!(market.can_buy_outcome(0, 1_500));
+
+    market.resolve(0, 2_000, false).unwrap();
+    assert_market_error(
+        market.update_resolve_at(3_000, 2_000),
+        ErrorCode::MarketResolved,
+    );
+}
+
+#[test]
+fn test_buy_capped_at_max_buyable_price() {
+    let mut market = market_with_reserves(&[800_000_000, 200_000_000]);
+    market.recompute_invariant().unwrap();
+    market.max_buyable_price = 900_000_000;
+
+    // 800 + 1_000 of 2_000 total is 90% exactly
+    { market }.buy_outcome(0, 1_000_000_000).unwrap();
+    assert_market_error(
+        { market }.buy_outcome(0, 1_000_100_000),
+        ErrorCode::OutcomePriceTooHigh,
+    );
+
+    // the cap also counts virtual_reserve, like the implied probabilities
+    market.virtual_reserve = 100_000_000;
+    { market }.buy_outcome(0, 1_800_000_000).unwrap();
+    assert_market_error(
+        { market }.buy_outcome(0, 1_800_100_000),
+        ErrorCode::OutcomePriceTooHigh,
+    );
+
+    market.max_buyable_price = 0;
+    market.buy_outcome(0, 10_000_000_000).unwrap();
+
+    // the opening buy is measured once every outcome holds its opening reserve:
+    // 1_000 + 8_000 of 10_000 total is 90% exactly
+    let mut empty = market_with_reserves(&[0, 0]);
+    empty.scale = 1_000;
+    empty.max_buyable_price = 900_000_000;
+    assert_market_error(
+        { empty }.buy_outcome(0, 10_100),
+        ErrorCode::OutcomePriceTooHigh,
+    );
+    empty.buy_outcome(0, 10_000).unwrap();
+    assert_eq!(empty.liquidity_percentages().unwrap()[0], 900_000_000);
+}
+
+#[test]
+fn test_seed_opens_every_outcome_at_one_over_n() {
+    let mut market = market_with_reserves(&[0, 0, 0]);
+    market.scale = 1_000_000;
+
+    assert_market_error({ market }.seed(0), ErrorCode::DepositIsZero);
+    assert_eq!(market.seed(100_000_000).unwrap(), 300_000_000);
+    for i in 0..3 {
+        assert_eq!(market.outcome_price(i).unwrap(), D9_U128 as u64 / 3);
+        assert_eq!(market.last_price[i], D9_U128 as u64 / 3);
+    }
+    assert_eq!(market.total_reserves_u128().unwrap(), 300_000_000);
+    assert!(!market.invariant_u256().is_zero());
+    assert_market_error(market.seed(100_000_000), ErrorCode::MarketAlreadySeeded);
+
+    // later buys keep the outcome's price
+    market.buy_outcome(0, 50_000_000).unwrap();
+    assert_eq!(market.outcome_price(0).unwrap(), D9_U128 as u64 / 3);
+
+    // the seeder holds every token, so any resolution returns the whole deposit
+    let pot = market.total_reserves_u128().unwrap() as u64;
+    market.resolve(1, 0, false).unwrap();
+    assert_eq!(market.redeemable_lamports(1, 300_000_000).unwrap(), pot);
+}
+
+#[test]
+fn test_opening_buy_mints_every_outcome() {
+    let mut market = market_with_reserves(&[0, 0, 0]);
+    market.scale = 100_000;
+
+    // the opener holds every outcome's opening supply, plus what the rest of the deposit buys
+    let opening_tokens = market.opening_deposit().unwrap();
+    let amount_out = market.buy_outcome(0, 100_000_000).unwrap();
+    assert_eq!(
+        amount_out,
+        opening_tokens + 3 * (100_000_000 - opening_tokens)
+    );
+    assert_eq!(
+        &market.supplies[..3],
+        &[amount_out, opening_tokens, opening_tokens]
+    );
+    for i in 0..3 {
+        assert_eq!(market.outcome_price(i).unwrap(), D9_U128 as u64 / 3);
+    }
+
+    // buying an outcome the opener didn't pick and selling straight back returns at most the
+    // deposit, since the opening reserve stays with the opener's tokens
+    let deposit = 10_000_000;
+    let tokens = market.buy_outcome(1, deposit).unwrap();
+    let vault_lamports = market.total_reserves_u128().unwrap() as u64;
+    let payout = market.sell_outcome(1, tokens, vault_lamports, 0).unwrap();
+    assert!(payout <= deposit);
+    assert_eq!(market.reserves[1], 100_000);
+    assert_eq!(market.supplies[1], opening_tokens);
+
+    // a reserve nobody holds can't price a buy
+    market.supplies[2] = 0;
+    assert_market_error(market.buy_outcome(2, deposit), ErrorCode::SupplyIsZero);
+}
+
+#[test]
+fn test_seed_rejected_once_anyone_holds_tokens() {
+    // outcome 0 was sold down to nothing, so the market is no longer open
+    let mut market = market_with_reserves(&[0, 500_000, 300_000]);
+    market.recompute_invariant().unwrap();
+    assert!(!market.is_open());
+    assert!(!market.is_empty());
+
+    // seeding would overwrite the remaining holders' reserves and supplies
+    assert_market_error({ market }.seed(1_000_000), ErrorCode::MarketAlreadySeeded);
+
+    // a buy trades on the curve instead of reopening the market
+    market.buy_outcome(1, 100_000).unwrap();
+    assert_eq!(market.reserves[1], 600_000);
+    assert_eq!(market.reserves[2], 300_000);
+    assert_eq!(market.supplies[2], 300_000);
+    assert_market_error({ market }.buy_outcome(0, 100_000), ErrorCode::ReserveIsZero);
+
+    // leftover tokens alone also block a seed
+    let mut market = market_with_reserves(&[0, 0]);
+    market.supplies[1] = 1;
+    assert_market_error(market.seed(1_000_000), ErrorCode::MarketAlreadySeeded);
+}
+
+#[test]
+fn test_cancel_recovery_vs_sell_quote() {
+    let mut market = market_with_reserves(&[700_000_000, 300_000_000, 0]);
+    market.supplies[0] = 350_000_000;
+    market.recompute_invariant().unwrap();
+    market.resolve_at = 1_000;
+    market.sell_fee_ramp_secs = 100;
+    market.sell_fee_max_bps = 500;
+    market.settlement_fee_bps = 100;
+
+    // both start from the same pro-rata share of the reserve, only the fee differs
+    let gross = 200_000_000;
+    let recovery = market.cancel_recovery(0, 100_000_000).unwrap();
+    assert_eq!(recovery, gross - market.settlement_fee(gross).unwrap());
+    for now in [0, 950] {
+        let quote = market.quote_sell(0, 100_000_000, now).unwrap();
+        assert_eq!(quote, gross - market.sell_fee(gross, now).unwrap());
+        assert_eq!(
+            recovery as i128 - quote as i128,
+            market.sell_fee(gross, now).unwrap() as i128
+                - market.settlement_fee(gross).unwrap() as i128
+        );
+    }
+
+    assert_eq!(market.cancel_recovery(0, 0).unwrap(), 0);
+    assert_market_error(
+        market.cancel_recovery(0, 350_000_001),
+        ErrorCode::BurnIsMoreThanSupply,
+    );
+    assert_market_error(market.cancel_recovery(3, 1), ErrorCode::InvalidOutcomeIndex);
+
+    // and it is what the holding claims once the market is actually voided
+    market.void_outcome = 2;
+    market.resolved = 1;
+    market.winning_outcome = 2;
+    assert_eq!(
+        market.redeemable_lamports(0, 100_000_000).unwrap(),
+        recovery
+    );
+}
+
+#[test]
+fn test_max_payout_is_what_resolution_drains() {
+    let mut market = market_with_reserves(&[600_000_000, 400_000_000, 100_000_000]);
+    market.undistributed_fees = 50_000_000;
+    market.void_outcome = 2;
+
+    // a held winner drains the whole pot, fees stay in the vault on top of it
+    assert_eq!(market.max_payout().unwrap(), 1_100_000_000);
+    assert_eq!(
+        market.simulate_resolution(0).unwrap(),
+        market.max_payout().unwrap()
+    );
+    assert_eq!(market.snapshot().unwrap().max_payout, 1_100_000_000);
+
+    // no resolution pays more, the void refund included
+    for winner in 0..3 {
+        assert!(market.simulate_resolution(winner).unwrap() <= market.max_payout().unwrap());
+    }
+}
+
+#[test]
+fn test_price_residual_is_floor_rounding_dust() {
+    // sevenths don't divide 1e9, so every share is floored
+    let market = market_with_reserves(&[1, 2, 4]);
+    let sum: u64 = market.liquidity_percentages().unwrap().iter().sum();
+    let residual = market.price_residual().unwrap();
+    assert_eq!(residual, 1_000_000_000 - sum as i64);
+    assert_eq!(residual, 2);
+    assert!((0..3).contains(&residual));
+
+    let market = market_with_reserves(&[100_000_001, 200_000_003, 300_000_007, 1]);
+    let residual = market.price_residual().unwrap();
+    assert!((0..4).contains(&residual));
+
+    // even splits and empty markets leave nothing over
+    assert_eq!(
+        market_with_reserves(&[500, 500]).price_residual().unwrap(),
+        0
+    );
+    assert_eq!(market_with_reserves(&[0, 0]).price_residual().unwrap(), 0);
+}
+
+#[test]
+fn test_value_in_quote_at_sample_sol_price() {
+    let market = market_with_reserves(&[0, 0]);
+    let sol_price = 150_000_000_000; // 150 USD
+
+    assert_eq!(
+        market.value_in_quote(1_000_000_000, sol_price),
+        150_000_000_000
+    );
+    assert_eq!(market.value_in_quote(2_500_000, sol_price), 375_000_000);
+    // a lamport at this price is 150 nano-dollars
+    assert_eq!(market.value_in_quote(1, sol_price), 150);
+    assert_eq!(market.value_in_quote(0, sol_price), 0);
+    assert_eq!(market.value_in_quote(u64::MAX, u64::MAX), u64::MAX);
+}
+
+#[test]
+fn test_incremental_invariant_matches_full_recompute() {
+    let mut market = market_with_reserves(&[300_000_000, 500_000_000, 200_000_000, 1_000]);
+    market.recompute_invariant().unwrap();
+
+    let trades: [(usize, bool, u64); 6] = [
+        (0, true, 10_000_000),
+        (3, true, 7_777),
+        (1, false, 123_456_789),
+        (2, true, 1_000_000_001),
+        (0, false, 999),
+        (3, false, 8_000),
+    ];
+    for (idx, is_buy, amount) in trades {
+        if is_buy {
+            market.buy_outcome(idx, amount).unwrap();
+        } else {
+            market.sell_outcome(idx, amount, u64::MAX, 0).unwrap();
+        }
+        let mut full = market;
+        assert_eq!(market.invariant_u256(), full.recompute_invariant().unwrap());
+        market.check_invariant().unwrap();
+    }
+
+    // a zero reserve can't be divided out, so the update rebuilds the product
+    let mut market = market_with_reserves(&[0, 400]);
+    market.recompute_invariant().unwrap();
+    market.reserves[0] = 600;
+    assert_eq!(
+        market.update_invariant_after_change(0, 0).unwrap(),
+        U256::from(240_000u64)
+    );
+    assert_market_error(
+        market.update_invariant_after_change(2, 600),
+        ErrorCode::InvalidOutcomeIndex,
+    );
+}
+
+#[test]
+fn test_invariant_recheck_catches_drift() {
+    let mut market = market_with_reserves(&[300_000_000, 500_000_000]);
+    market.recompute_invariant().unwrap();
+    // a drifted invariant that's still a multiple of each reserve survives incremental updates
+    let drifted = market.invariant_u256() * U256::from(2u64);
+    market.set_invariant_u256(drifted);
+
+    // with the recheck off, trading carries on
+    { market }.buy_outcome(0, 1_000_000).unwrap();
+    { market }.buy_outcome(0, 1_000_000).unwrap();
+
+    // every third trade compares against the full product
+    market.invariant_recheck_interval = 3;
+    market.buy_outcome(0, 1_000_000).unwrap();
+    market.sell_outcome(1, 1_000_000, u64::MAX, 0).unwrap();
+    assert_market_error(
+        { market }.buy_outcome(0, 1_000_000),
+        ErrorCode::InvariantDrift,
+    );
+    assert_market_error(market.check_invariant(), ErrorCode::InvariantDrift);
+
+    market.recompute_invariant().unwrap();
+    market.buy_outcome(0, 1_000_000).unwrap();
+    assert_eq!(market.trade_count, 3);
+}
+
+#[test]
+fn test_required_delta_after_init() {
+    // a freshly initialized market: no reserves, invariant set to the opening product
+    let mut market = market_with_reserves(&[0, 0, 0]);
+    market.scale = 1_000;
+    let opening = market.opening_invariant().unwrap();
+    market.set_invariant_u256(opening);
+    assert_eq!(market.invariant_u256(), U256::from(1_000_000_000u64));
+    assert!(!market.is_open());
+
+    // every outcome is short exactly the reserve the first buy opens it at
+    for i in 0..3 {
+        assert_eq!(
+            market.required_reserve_for(i).unwrap(),
+            U256::from(1_000u64)
+        );
+        assert_eq!(market.required_delta(i).unwrap(), 1_000);
+    }
+
+    // the first buy funds every opening reserve, the rest lands on the bought outcome
+    assert_market_error({ market }.buy_outcome(0, 2_999), ErrorCode::TradeTooSmall);
+    market.buy_outcome(0, 3_500).unwrap();
+    assert_eq!(market.total_reserves_u128().unwrap(), 3_500);
+    assert!(market.is_open());
+    assert_eq!(
+        market.invariant_u256(),
+        U256::from(1_500u64 * 1_000 * 1_000)
+    );
+    for i in 0..3 {
+        assert_eq!(market.required_delta(i).unwrap(), 0);
+    }
+}
+
+#[test]
+fn test_liquidation_fee_is_fee_bps_of_full_refund() {
+    let mut market = market_with_reserves(&[700_000_000, 300_000_000]);
+    market.supplies[0] = 350_000_000;
+    market.recompute_invariant().unwrap();
+
+    // the whole position refunds the whole reserve
+    let fee = market.liquidation_fee(0, 350_000_000).unwrap();
+    assert_eq!(fee, 700_000_000 * FEE_BPS / 10_000);
+    assert_eq!(
+        market.redeemable_lamports(0, 350_000_000).unwrap(),
+        700_000_000 - fee
+    );
+    assert_eq!(
+        market.quote_sell(0, 350_000_000, 0).unwrap(),
+        700_000_000 - fee
+    );
+
+    assert_eq!(market.liquidation_fee(0, 0).unwrap(), 0);
+    assert_market_error(
+        market.liquidation_fee(0, 350_000_001),
+        ErrorCode::BurnIsMoreThanSupply,
+    );
+    assert_market_error(market.liquidation_fee(2, 1), ErrorCode::InvalidOutcomeIndex);
+}
+
+#[test]
+fn test_required_delta_rejects_delta_beyond_u64() {
+    // four large reserves, whose product only fits in U256
+    let mut market = market_with_reserves(&[1 << 62; 4]);
+    market.recompute_invariant().unwrap();
+    assert_eq!(market.required_delta(0).unwrap(), 0);
+
+    // an invariant 8x the product needs 8x the reserve on any single outcome
+    let inflated = market.invariant_u256() * U256::from(8u64);
+    market.set_invariant_u256(inflated);
+    assert_eq!(
+        market.required_reserve_for(0).unwrap(),
+        U256::from(1u64 << 62) * U256::from(8u64)
+    );
+    assert_market_error(market.required_delta(0), ErrorCode::DeltaTooLarge);
+
+    // right at the bound the delta still fits
+    market.reserves[0] = 1;
+    market.set_invariant_u256(market.product_except(0).unwrap() * U256::from(u64::MAX));
+    assert_eq!(market.required_delta(0).unwrap(), u64::MAX - 1);
+}
+
+#[cfg(feature = "max-outcomes-32")]
+#[test]
+fn test_high_outcome_market() {
+    use common::constants::MAX_OUTCOMES;
+
+    // the product of 32 reserves must fit the U256 invariant, so keep them small
+    let mut market = market_with_reserves(&[100; MAX_OUTCOMES]);
+    market.recompute_invariant().unwrap();
+
+    let last = MAX_OUTCOMES - 1;
+    market.buy_outcome(last, 50).unwrap();
+    assert_eq!(market.reserves[last], 150);
+
+    let probabilities = market.probabilities_bps().unwrap();
+    assert_eq!(probabilities.iter().map(|p| *p as u32).sum::<u32>(), 10_000);
+    assert_eq!(
+        Market::SIZE,
+        8 + std::mem::size_of::<Market>(),
+        "account size must track the larger arrays"
+    );
+}
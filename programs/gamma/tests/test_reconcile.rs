@@ -0,0 +1,91 @@
+mod helpers;
+
+use anchor_lang::{
+    prelude::AccountMeta, solana_program::instruction::Instruction, AnchorDeserialize,
+    InstructionData, ToAccountMetas,
+};
+use gamma::state::Market;
+use gamma::types::ReconcileReport;
+use helpers::*;
+use litesvm::LiteSVM;
+use solana_sdk::signer::{keypair::Keypair, Signer};
+
+fn verify_and_repair_ix(admin: &Keypair, accounts: &MarketAccounts, repair: bool) -> Instruction {
+    let mut accounts_ctx = gamma::accounts::VerifyAndRepair {
+        admin: admin.pubkey(),
+        market: accounts.market,
+        market_vault: accounts.market_vault,
+        collateral_vault: accounts.collateral_vault(),
+    }
+    .to_account_metas(None);
+    for mint in &accounts.outcome_mints {
+        accounts_ctx.push(AccountMeta::new_readonly(*mint, false));
+    }
+
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::VerifyAndRepair { repair }.data(),
+        accounts_ctx,
+    )
+}
+
+fn verify_and_repair(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    accounts: &MarketAccounts,
+    repair: bool,
+) -> ReconcileReport {
+    let ix = verify_and_repair_ix(admin, accounts, repair);
+    let meta = send(svm, &[ix], admin, &[admin]).unwrap();
+    ReconcileReport::try_from_slice(&meta.return_data.data).unwrap()
+}
+
+#[test]
+fn test_verify_and_repair_restores_supplies() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "reconcile", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 50_000_000).unwrap();
+
+    let report = verify_and_repair(&mut svm, &admin, &accounts, false);
+    assert!(report.supply_mismatches.is_empty());
+    // every reserve lamport was deposited, so the vault backs them exactly
+    assert_eq!(report.reserves_total, report.vault_backing);
+
+    // desync the recorded supply of outcome 1 from its mint
+    let mut market_account = svm.get_account(&accounts.market).unwrap();
+    let market = bytemuck::from_bytes_mut::<Market>(&mut market_account.data[8..]);
+    let real_supply = market.supplies[1];
+    market.supplies[1] = real_supply + 1_000;
+    svm.set_account(accounts.market, market_account).unwrap();
+
+    let report = verify_and_repair(&mut svm, &admin, &accounts, false);
+    assert_eq!(report.supply_mismatches, vec![1]);
+    assert_eq!(
+        load_market(&svm, &accounts.market).supplies[1],
+        real_supply + 1_000
+    );
+
+    let report = verify_and_repair(&mut svm, &admin, &accounts, true);
+    assert_eq!(report.supply_mismatches, vec![1]);
+    assert!(report.repaired);
+    assert_eq!(load_market(&svm, &accounts.market).supplies[1], real_supply);
+
+    let report = verify_and_repair(&mut svm, &admin, &accounts, false);
+    assert!(report.supply_mismatches.is_empty());
+}
+
+#[test]
+fn test_verify_and_repair_requires_admin() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "reconcile-auth", 2, DEFAULT_RESOLVE_AT);
+
+    let stranger = funded_keypair(&mut svm);
+    let ix = verify_and_repair_ix(&stranger, &accounts, true);
+    let result = send(&mut svm, &[ix], &stranger, &[&stranger]);
+    assert_error(result, 0, common::errors::ErrorCode::Unauthorized);
+}
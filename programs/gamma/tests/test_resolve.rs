@@ -0,0 +1,746 @@
+mod helpers;
+
+use common::constants::MAX_BATCH_CLAIMS;
+use common::errors::ErrorCode;
+use gamma::types::{MarketConfig, MarketPhase};
+use helpers::*;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn test_resolve_market_sets_winning_outcome() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "resolve", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+
+    // too early to resolve
+    let result = send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 1, None)],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::MarketNotExpired);
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+
+    // only the admin may resolve
+    let result = send(
+        &mut svm,
+        &[resolve_market_ix(&user.pubkey(), &accounts, 1, None)],
+        &user,
+        &[&user],
+    );
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    let result = send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 2, None)],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::InvalidOutcomeIndex);
+
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 1, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.winning_outcome(), Some(1));
+
+    // resolution is final
+    let result = send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::MarketResolved);
+}
+
+#[test]
+fn test_resolve_requires_min_trades() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "min_trades",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            min_trades_before_resolve: 3,
+            post_expiry_sell_grace: 3_600,
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+
+    // two trades are below the threshold
+    let result = send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::InsufficientResolveActivity);
+
+    // a sell in the grace period is the third trade
+    sell(&mut svm, &user, &accounts, 0, 10_000_000).unwrap();
+    assert_eq!(load_market(&svm, &accounts.market).trade_count, 3);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+    assert_eq!(
+        load_market(&svm, &accounts.market).winning_outcome(),
+        Some(0)
+    );
+}
+
+#[test]
+fn test_trading_rejected_after_resolution() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let config = MarketConfig {
+        post_expiry_sell_grace: 100,
+        ..open_config()
+    };
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "resolve_trading",
+        2,
+        DEFAULT_RESOLVE_AT,
+        config,
+    );
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+
+    // sells would still be inside the grace window, but resolution closes them
+    assert_error(
+        sell(&mut svm, &user, &accounts, 0, 10_000_000),
+        0,
+        ErrorCode::MarketResolved,
+    );
+    assert_error(
+        buy(&mut svm, &user, &accounts, 0, 10_000_000),
+        1,
+        ErrorCode::MarketResolved,
+    );
+}
+
+#[test]
+fn test_trades_after_resolve_in_same_transaction_rejected() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "resolve_same_tx",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            post_expiry_sell_grace: 100,
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+
+    // instructions run in order against the updated market, so a trade composed after the
+    // resolve sees it resolved and fails, reverting the resolution with it
+    let resolve = resolve_market_ix(&admin.pubkey(), &accounts, 0, None);
+    let result = send(
+        &mut svm,
+        &[
+            resolve.clone(),
+            sell_ix(&user.pubkey(), &accounts, 1, 10_000_000, 0),
+        ],
+        &admin,
+        &[&admin, &user],
+    );
+    assert_error(result, 1, ErrorCode::MarketResolved);
+    let result = send(
+        &mut svm,
+        &[
+            resolve.clone(),
+            buy_ix(&user.pubkey(), &accounts, 0, 10_000_000, 0),
+        ],
+        &admin,
+        &[&admin, &user],
+    );
+    assert_error(result, 1, ErrorCode::MarketResolved);
+    assert!(load_market(&svm, &accounts.market)
+        .winning_outcome()
+        .is_none());
+
+    // a grace-window sell placed before the resolve still trades on the live curve
+    send(
+        &mut svm,
+        &[
+            sell_ix(&user.pubkey(), &accounts, 1, 10_000_000, 0),
+            resolve,
+        ],
+        &admin,
+        &[&admin, &user],
+    )
+    .unwrap();
+    assert_eq!(
+        load_market(&svm, &accounts.market).winning_outcome(),
+        Some(0)
+    );
+}
+
+#[test]
+fn test_winner_redeems_the_vault() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let winner = funded_keypair(&mut svm);
+    let loser = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "redeem", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &winner, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &loser, &accounts, 1, 100_000_000).unwrap();
+
+    let winner_tokens = token_balance(&svm, &accounts.user_token_account(&winner.pubkey(), 0));
+    let loser_tokens = token_balance(&svm, &accounts.user_token_account(&loser.pubkey(), 1));
+
+    // nothing can be redeemed before resolution
+    let result = redeem(&mut svm, &winner, &accounts, 0, winner_tokens);
+    assert_error(result, 0, ErrorCode::MarketNotResolved);
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+
+    let result = redeem(&mut svm, &loser, &accounts, 1, loser_tokens);
+    assert_error(result, 0, ErrorCode::NotWinningOutcome);
+
+    let rent_min = svm.minimum_balance_for_rent_exemption(0);
+    let vault_before = svm.get_balance(&accounts.market_vault).unwrap();
+    let fees = load_market(&svm, &accounts.market).undistributed_fees;
+    let winner_before = svm.get_balance(&winner.pubkey()).unwrap();
+    redeem(&mut svm, &winner, &accounts, 0, winner_tokens).unwrap();
+    let winner_after = svm.get_balance(&winner.pubkey()).unwrap();
+
+    // the sole winner takes the whole vault less fees and rent, minus 5000 lamports tx fee
+    assert_eq!(
+        winner_after + 5000 - winner_before,
+        vault_before - fees - rent_min
+    );
+    assert_eq!(
+        token_balance(&svm, &accounts.user_token_account(&winner.pubkey(), 0)),
+        0
+    );
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.supplies[0], 0);
+    assert_eq!(market.total_reserves_u128().unwrap(), 0);
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        fees + rent_min
+    );
+}
+
+#[test]
+fn test_batch_claim_across_markets() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let first = init_market(&mut svm, &admin, "claim-a", 2, DEFAULT_RESOLVE_AT);
+    let second = init_market(&mut svm, &admin, "claim-b", 3, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &first, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &second, 2, 50_000_000).unwrap();
+
+    // an unresolved market rejects the whole batch
+    let result = send(
+        &mut svm,
+        &[batch_claim_ix(&user.pubkey(), &[(&first, 0), (&second, 2)])],
+        &user,
+        &[&user],
+    );
+    assert_error(result, 0, ErrorCode::MarketNotResolved);
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    for (accounts, winner) in [(&first, 0), (&second, 2)] {
+        send(
+            &mut svm,
+            &[resolve_market_ix(&admin.pubkey(), accounts, winner, None)],
+            &admin,
+            &[&admin],
+        )
+        .unwrap();
+    }
+
+    // at most MAX_BATCH_CLAIMS markets per instruction
+    let too_many = vec![(&first, 0); MAX_BATCH_CLAIMS + 1];
+    let result = send(
+        &mut svm,
+        &[batch_claim_ix(&user.pubkey(), &too_many)],
+        &user,
+        &[&user],
+    );
+    assert_error(result, 0, ErrorCode::InvalidBatchLength);
+
+    let first_vault_before = svm.get_balance(&first.market_vault).unwrap();
+    let second_vault_before = svm.get_balance(&second.market_vault).unwrap();
+    let user_before = svm.get_balance(&user.pubkey()).unwrap();
+    send(
+        &mut svm,
+        &[batch_claim_ix(&user.pubkey(), &[(&first, 0), (&second, 2)])],
+        &user,
+        &[&user],
+    )
+    .unwrap();
+
+    // both vaults paid out to the user, minus 5000 lamports tx fee
+    let paid = first_vault_before - svm.get_balance(&first.market_vault).unwrap()
+        + second_vault_before
+        - svm.get_balance(&second.market_vault).unwrap();
+    assert!(paid > 0);
+    assert_eq!(
+        svm.get_balance(&user.pubkey()).unwrap() + 5000 - user_before,
+        paid
+    );
+    assert_eq!(
+        token_balance(&svm, &first.user_token_account(&user.pubkey(), 0)),
+        0
+    );
+    assert_eq!(
+        token_balance(&svm, &second.user_token_account(&user.pubkey(), 2)),
+        0
+    );
+}
+
+#[test]
+fn test_vested_claims_stay_in_escrow_until_the_cliff() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "vesting",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            claim_vesting_secs: 500,
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    let tokens = token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 0));
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+
+    // winnings can't be paid out directly
+    let result = redeem(&mut svm, &user, &accounts, 0, tokens);
+    assert_error(result, 0, ErrorCode::ClaimMustBeEscrowed);
+
+    let vault_before = svm.get_balance(&accounts.market_vault).unwrap();
+    send(
+        &mut svm,
+        &[claim_to_escrow_ix(&user.pubkey(), &accounts, 0, tokens)],
+        &user,
+        &[&user],
+    )
+    .unwrap();
+    let payout = vault_before - svm.get_balance(&accounts.market_vault).unwrap();
+    assert!(payout > 0);
+    assert_eq!(
+        token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 0)),
+        0
+    );
+    let escrow = escrow_address(&accounts, &user.pubkey());
+    let escrow_rent = svm.minimum_balance_for_rent_exemption(gamma::state::ClaimEscrow::SIZE);
+    assert_eq!(svm.get_balance(&escrow).unwrap(), escrow_rent + payout);
+
+    // locked until resolve_at + claim_vesting_secs
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT + 499);
+    let result = send(
+        &mut svm,
+        &[release_escrow_ix(&user.pubkey(), &accounts)],
+        &user,
+        &[&user],
+    );
+    assert_error(result, 0, ErrorCode::EscrowLocked);
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT + 500);
+    let user_before = svm.get_balance(&user.pubkey()).unwrap();
+    send(
+        &mut svm,
+        &[release_escrow_ix(&user.pubkey(), &accounts)],
+        &user,
+        &[&user],
+    )
+    .unwrap();
+
+    // the payout and the escrow's rent go to the user, minus 5000 lamports tx fee
+    assert_eq!(
+        svm.get_balance(&user.pubkey()).unwrap() + 5000 - user_before,
+        payout + escrow_rent
+    );
+    assert_eq!(svm.get_balance(&escrow).unwrap_or(0), 0);
+}
+
+#[test]
+fn test_claim_to_escrow_requires_vesting() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "no_vesting", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    let tokens = token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 0));
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+
+    let result = send(
+        &mut svm,
+        &[claim_to_escrow_ix(&user.pubkey(), &accounts, 0, tokens)],
+        &user,
+        &[&user],
+    );
+    assert_error(result, 0, ErrorCode::EscrowNotEnabled);
+}
+
+#[test]
+fn test_winners_split_only_the_deposited_pot() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let first = funded_keypair(&mut svm);
+    let second = funded_keypair(&mut svm);
+    let loser = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "split_pot",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            settlement_fee_bps: 100,
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &first, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &loser, &accounts, 1, 100_000_000).unwrap();
+    buy(&mut svm, &second, &accounts, 0, 50_000_000).unwrap();
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+
+    let rent_min = svm.minimum_balance_for_rent_exemption(0);
+    let pot = load_market(&svm, &accounts.market)
+        .settlement_pot()
+        .unwrap();
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        pot + load_market(&svm, &accounts.market).undistributed_fees + rent_min
+    );
+
+    // both winners redeem in full, and the last one is still paid from deposited lamports
+    let mut paid = 0;
+    for holder in [&first, &second] {
+        let tokens = token_balance(&svm, &accounts.user_token_account(&holder.pubkey(), 0));
+        let before = svm.get_balance(&holder.pubkey()).unwrap();
+        redeem(&mut svm, holder, &accounts, 0, tokens).unwrap();
+        paid += svm.get_balance(&holder.pubkey()).unwrap() + 5000 - before;
+    }
+
+    // the pot went to the winners and the settlement fee, leaving fees and rent in the vault
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.total_reserves_u128().unwrap(), 0);
+    assert_eq!(paid + market.undistributed_settlement_fees, pot);
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        market.undistributed_fees + market.undistributed_settlement_fees + rent_min
+    );
+}
+
+#[test]
+fn test_resolution_proof_is_stored() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "resolution_proof", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    assert_eq!(
+        load_market(&svm, &accounts.market).resolution_proof,
+        [0u8; 32]
+    );
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    let proof = [7u8; 32];
+    send(
+        &mut svm,
+        &[resolve_market_ix(
+            &admin.pubkey(),
+            &accounts,
+            1,
+            Some(proof),
+        )],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.winning_outcome(), Some(1));
+    assert_eq!(market.resolution_proof, proof);
+}
+
+#[test]
+fn test_close_market_after_full_redemption() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let winner = funded_keypair(&mut svm);
+    let loser = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "close", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &winner, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &loser, &accounts, 1, 100_000_000).unwrap();
+    let winner_tokens = token_balance(&svm, &accounts.user_token_account(&winner.pubkey(), 0));
+
+    // an open market can't be closed
+    let ix = close_market_ix(&admin.pubkey(), &accounts);
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::MarketNotResolved);
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+
+    // nor one with winning tokens left to redeem
+    let ix = close_market_ix(&admin.pubkey(), &accounts);
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::MarketNotEmpty);
+
+    redeem(&mut svm, &winner, &accounts, 0, winner_tokens).unwrap();
+    let ix = claim_fees_ix(&admin.pubkey(), &accounts, &admin.pubkey());
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+
+    // only the worthless losing supply remains
+    assert!(load_market(&svm, &accounts.market).supplies[1] > 0);
+    let ix = close_market_ix(&loser.pubkey(), &accounts);
+    let result = send(&mut svm, &[ix], &loser, &[&loser]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    let reclaimed = svm.get_balance(&accounts.market).unwrap()
+        + svm.get_balance(&accounts.market_vault).unwrap();
+    let admin_before = svm.get_balance(&admin.pubkey()).unwrap();
+    let ix = close_market_ix(&admin.pubkey(), &accounts);
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+
+    // both accounts are gone and their lamports went to the admin, minus 5000 lamports tx fee
+    assert_eq!(
+        svm.get_balance(&admin.pubkey()).unwrap() + 5000 - admin_before,
+        reclaimed
+    );
+    assert_eq!(svm.get_balance(&accounts.market).unwrap_or_default(), 0);
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap_or_default(),
+        0
+    );
+}
+
+#[test]
+fn test_settlement_fees_are_claimed_before_close() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let winner = funded_keypair(&mut svm);
+    let loser = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "settlement_fees",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            settlement_fee_bps: 100,
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &winner, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &loser, &accounts, 1, 100_000_000).unwrap();
+    let winner_tokens = token_balance(&svm, &accounts.user_token_account(&winner.pubkey(), 0));
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+    redeem(&mut svm, &winner, &accounts, 0, winner_tokens).unwrap();
+
+    // the winner's claim left a settlement fee behind, which blocks the close
+    let market = load_market(&svm, &accounts.market);
+    let owed = market.undistributed_fees + market.undistributed_settlement_fees;
+    assert!(market.undistributed_settlement_fees > 0);
+    let ix = close_market_ix(&admin.pubkey(), &accounts);
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::MarketNotEmpty);
+
+    // claiming pays both fee buckets to the recipient
+    let admin_before = svm.get_balance(&admin.pubkey()).unwrap();
+    let ix = claim_fees_ix(&admin.pubkey(), &accounts, &admin.pubkey());
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    assert_eq!(
+        svm.get_balance(&admin.pubkey()).unwrap() + 5000 - admin_before,
+        owed
+    );
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.undistributed_fees, 0);
+    assert_eq!(market.undistributed_settlement_fees, 0);
+
+    svm.expire_blockhash();
+    let ix = close_market_ix(&admin.pubkey(), &accounts);
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    assert_eq!(svm.get_balance(&accounts.market).unwrap_or_default(), 0);
+}
+
+#[test]
+fn test_void_resolution_reports_cancelled() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "cancelled",
+        3,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            void_outcome: Some(2),
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 2, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+
+    // the void outcome refunds everyone, so the market reads as cancelled rather than resolved
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(
+        market.phase(DEFAULT_RESOLVE_AT).unwrap(),
+        MarketPhase::Cancelled
+    );
+}
+
+#[test]
+fn test_resolving_to_an_unheld_outcome_refunds_everyone() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "resolve_empty", 3, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    // the opener is the only holder of outcome 2 and sells out of it
+    let held = token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 2));
+    sell(&mut svm, &user, &accounts, 2, held).unwrap();
+    assert_eq!(load_market(&svm, &accounts.market).supplies[2], 0);
+
+    // winner-takes-all to outcome 2 would strand the pot, so it's rejected by default
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    let result = send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 2, None)],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::NoWinnersForOutcome);
+
+    let before = load_market(&svm, &accounts.market);
+    let ix = resolve_market_ix_with_allow_empty(&admin.pubkey(), &accounts, 2, None, true);
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+
+    // the market settles as void: every holder gets their own outcome's refund back
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.winning_outcome(), Some(2));
+    assert_eq!(
+        market.phase(DEFAULT_RESOLVE_AT).unwrap(),
+        MarketPhase::Cancelled
+    );
+    for outcome in 0..2u8 {
+        let token_account = accounts.user_token_account(&user.pubkey(), outcome);
+        let tokens = token_balance(&svm, &token_account);
+        let expected = before.cancel_recovery(outcome as usize, tokens).unwrap();
+        let user_before = svm.get_balance(&user.pubkey()).unwrap();
+        redeem(&mut svm, &user, &accounts, outcome, tokens).unwrap();
+        // user pays 5000 lamports for the tx fee
+        assert_eq!(
+            svm.get_balance(&user.pubkey()).unwrap() + 5000 - user_before,
+            expected
+        );
+    }
+}
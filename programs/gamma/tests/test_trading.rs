@@ -0,0 +1,795 @@
+mod helpers;
+
+use anchor_lang::AnchorDeserialize;
+use anchor_spl::associated_token::spl_associated_token_account;
+use common::errors::ErrorCode;
+use gamma::events::{BuyExecuted, LimitsUpdated, SellExecuted};
+use gamma::state::Market;
+use gamma::types::{BuyLeg, MarketConfig, MarketPhase};
+use helpers::*;
+use solana_sdk::{message::Message, signer::Signer, transaction::Transaction};
+use spl_token::solana_program::program_pack::Pack;
+use spl_token::state::Mint;
+
+#[test]
+fn test_insolvent_market_blocks_buys_but_not_sells() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "insolvent",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            enforce_solvency: true,
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+
+    // drain the vault out of band so it can no longer cover the reserves
+    let mut vault = svm.get_account(&accounts.market_vault).unwrap();
+    vault.lamports = svm.minimum_balance_for_rent_exemption(0) + 50_000_000;
+    svm.set_account(accounts.market_vault, vault).unwrap();
+
+    // ix 0 creates the token account, ix 1 is the buy
+    let result = buy(&mut svm, &user, &accounts, 0, 10_000_000);
+    assert_error(result, 1, ErrorCode::MarketInsolvent);
+
+    // a swap buys the other outcome, so it's blocked too
+    let result = swap_outcome(&mut svm, &user, &accounts, 0, 1, 10_000_000, 0);
+    assert_error(result, 1, ErrorCode::MarketInsolvent);
+
+    // holders can still exit
+    let token_account = accounts.user_token_account(&user.pubkey(), 1);
+    let held = token_balance(&svm, &token_account);
+    sell(&mut svm, &user, &accounts, 1, 10_000_000).unwrap();
+    assert_eq!(token_balance(&svm, &token_account), held - 10_000_000);
+}
+
+#[test]
+fn test_sell_in_post_expiry_grace_window() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "sell-grace",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            post_expiry_sell_grace: 600,
+            ..open_config()
+        },
+    );
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    // buys close at resolve_at, sells stay open for the grace window
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT + 300);
+    let result = buy(&mut svm, &user, &accounts, 0, 10_000_000);
+    assert_error(result, 1, ErrorCode::MarketExpired);
+    sell(&mut svm, &user, &accounts, 0, 10_000_000).unwrap();
+
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT + 600);
+    let result = sell(&mut svm, &user, &accounts, 0, 10_000_000);
+    assert_error(result, 0, ErrorCode::MarketExpired);
+}
+
+#[test]
+fn test_zero_refund_sell_keeps_tokens() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "zero-refund", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    // price outcome 0 below one lamport per token so a 1 token burn refunds nothing
+    let mut market_account = svm.get_account(&accounts.market).unwrap();
+    let market = bytemuck::from_bytes_mut::<Market>(&mut market_account.data[8..]);
+    market.reserves[0] = market.supplies[0] / 2;
+    svm.set_account(accounts.market, market_account).unwrap();
+
+    let token_account = accounts.user_token_account(&user.pubkey(), 0);
+    let balance_before = token_balance(&svm, &token_account);
+
+    let result = sell(&mut svm, &user, &accounts, 0, 1);
+    assert_error(result, 0, ErrorCode::BurnProducesNoRefund);
+    assert_eq!(token_balance(&svm, &token_account), balance_before);
+}
+
+#[test]
+fn test_mismatched_mint_decimals_rejected() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "decimals", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    // the mint no longer matches the decimals the market recorded at init
+    let mint_key = accounts.outcome_mints[0];
+    let mut mint_account = svm.get_account(&mint_key).unwrap();
+    let mut mint = Mint::unpack(&mint_account.data).unwrap();
+    mint.decimals += 1;
+    Mint::pack(mint, &mut mint_account.data).unwrap();
+    svm.set_account(mint_key, mint_account).unwrap();
+
+    let result = buy(&mut svm, &user, &accounts, 0, 10_000_000);
+    assert_error(result, 1, ErrorCode::DecimalsMismatch);
+    let result = sell(&mut svm, &user, &accounts, 0, 10_000_000);
+    assert_error(result, 0, ErrorCode::DecimalsMismatch);
+
+    // batch buys check every leg's mint the same way
+    let leg = BuyLeg {
+        outcome_index: 0,
+        amount_in: 10_000_000,
+        min_amount_out: 0,
+    };
+    let ix = batch_buy_ix(&user.pubkey(), &accounts, vec![leg]);
+    let result = send(&mut svm, &[ix], &user, &[&user]);
+    assert_error(result, 0, ErrorCode::DecimalsMismatch);
+}
+
+#[test]
+fn test_buy_and_sell_return_outcome_price() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "return-price", 2, DEFAULT_RESOLVE_AT);
+
+    let meta = buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    let price = u64::try_from_slice(&meta.return_data.data).unwrap();
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(price, market.outcome_price(0).unwrap());
+    assert!(meta
+        .logs
+        .contains(&format!("Program log: outcome_price: 0 {price}")));
+
+    let meta = sell(&mut svm, &user, &accounts, 0, 10_000_000).unwrap();
+    let price = u64::try_from_slice(&meta.return_data.data).unwrap();
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(price, market.outcome_price(0).unwrap());
+}
+
+#[test]
+fn test_user_buys_wait_for_admin_seed() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "seeded",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig::default(),
+    );
+
+    let result = buy(&mut svm, &user, &accounts, 0, 10_000_000);
+    assert_error(result, 1, ErrorCode::MarketNotSeeded);
+
+    // once the admin sets the opening prices anyone may trade
+    buy(&mut svm, &admin, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 0, 10_000_000).unwrap();
+}
+
+#[test]
+fn test_seed_market_opens_at_even_prices() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "seed_market",
+        4,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig::default(),
+    );
+
+    // only the admin may seed
+    let ix = seed_market_ix(&user.pubkey(), &accounts, 100_000_000);
+    let result = send(&mut svm, &[ix], &user, &[&user]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    let vault_before = svm.get_balance(&accounts.market_vault).unwrap();
+    seed_market(&mut svm, &admin, &accounts, 100_000_000).unwrap();
+
+    // every outcome opens at 1 / 4, fully backed by the deposit
+    let market = load_market(&svm, &accounts.market);
+    for i in 0..4 {
+        assert_eq!(market.outcome_price(i).unwrap(), 250_000_000);
+        assert_eq!(
+            token_balance(&svm, &accounts.user_token_account(&admin.pubkey(), i as u8)),
+            400_000_000
+        );
+    }
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        vault_before + 400_000_000
+    );
+
+    // the market can only be seeded once, and is open to everyone afterwards
+    let result = seed_market(&mut svm, &admin, &accounts, 100_000_000);
+    assert_error(result, 4, ErrorCode::MarketAlreadySeeded);
+    buy(&mut svm, &user, &accounts, 0, 10_000_000).unwrap();
+}
+
+#[test]
+fn test_opening_buy_mints_every_outcome_to_the_opener() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "opening_buy", 3, DEFAULT_RESOLVE_AT);
+
+    // the opening buy needs the opener's account for every other outcome
+    let create_ata_ix =
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &admin.pubkey(),
+            &admin.pubkey(),
+            &accounts.outcome_mints[0],
+            &anchor_spl::token::ID,
+        );
+    let result = send(
+        &mut svm,
+        &[
+            create_ata_ix,
+            buy_ix(&admin.pubkey(), &accounts, 0, 100_000_000, 0),
+        ],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 1, ErrorCode::InvalidMintCount);
+
+    // a batch can't open the market, its legs only carry their own outcome's accounts
+    let leg = BuyLeg {
+        outcome_index: 0,
+        amount_in: 100_000_000,
+        min_amount_out: 0,
+    };
+    let ix = batch_buy_ix(&admin.pubkey(), &accounts, vec![leg]);
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::MarketNotOpen);
+
+    buy(&mut svm, &admin, &accounts, 0, 100_000_000).unwrap();
+    let market = load_market(&svm, &accounts.market);
+    for i in 0..3 {
+        assert_eq!(
+            token_balance(&svm, &accounts.user_token_account(&admin.pubkey(), i)),
+            market.supplies[i as usize]
+        );
+    }
+    assert_eq!(market.supplies[1], market.opening_deposit().unwrap());
+
+    // buying an outcome the opener didn't pick and selling straight back returns at most the
+    // deposit, the opening reserve stays with the opener's tokens
+    let balance_before = svm.get_balance(&user.pubkey()).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 10_000_000).unwrap();
+    let held = token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 1));
+    sell(&mut svm, &user, &accounts, 1, held).unwrap();
+    assert!(svm.get_balance(&user.pubkey()).unwrap() < balance_before);
+    assert_eq!(
+        load_market(&svm, &accounts.market).reserves[1],
+        DEFAULT_SCALE
+    );
+}
+
+#[test]
+fn test_sell_rejects_vault_as_recipient() {
+    // the vault is a PDA and can't really sign, so skip signature checks to forge it
+    let mut svm = setup().with_sigverify(false);
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "vault-recipient", 2, DEFAULT_RESOLVE_AT);
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    let create_ata_ix =
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &user.pubkey(),
+            &accounts.market_vault,
+            &accounts.outcome_mints[0],
+            &spl_token::ID,
+        );
+    let sell_ix = sell_ix(&accounts.market_vault, &accounts, 0, 1_000_000, 0);
+    let mut tx = Transaction::new_unsigned(Message::new(
+        &[create_ata_ix, sell_ix],
+        Some(&user.pubkey()),
+    ));
+    tx.partial_sign(&[&user], svm.latest_blockhash());
+
+    let result = svm.send_transaction(tx);
+    assert_error(result, 1, ErrorCode::InvalidRecipient);
+}
+
+#[test]
+fn test_swap_outcome_moves_position_in_one_transaction() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "swap_outcome", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &admin, &accounts, 1, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    let from_account = accounts.user_token_account(&user.pubkey(), 0);
+    let to_account = accounts.user_token_account(&user.pubkey(), 1);
+    let held = token_balance(&svm, &from_account);
+    let vault_before = svm.get_balance(&accounts.market_vault).unwrap();
+
+    // a quote the curve can't meet is rejected
+    let mut expected = load_market(&svm, &accounts.market);
+    let fill = expected
+        .swap_outcome(0, 1, held, 0, vault_before, 0)
+        .unwrap();
+    let amount_out = fill.amount_out;
+    let result = swap_outcome(&mut svm, &user, &accounts, 0, 1, held, amount_out + 1);
+    assert_error(result, 1, ErrorCode::SlippageExceeded);
+
+    let meta = swap_outcome(&mut svm, &user, &accounts, 0, 1, held, amount_out).unwrap();
+    assert_eq!(token_balance(&svm, &from_account), 0);
+    assert_eq!(token_balance(&svm, &to_account), amount_out);
+
+    // indexers see the swap as a sell and a buy of its refund
+    let sold = find_event::<SellExecuted>(&meta.logs).expect("SellExecuted event in logs");
+    assert_eq!(sold.user, user.pubkey());
+    assert_eq!(sold.outcome_index, 0);
+    assert_eq!(sold.burn_amount, held);
+    assert_eq!(sold.net_payout, fill.net_refund);
+    assert_eq!(sold.fee, fill.sell_fee);
+    let bought = find_event::<BuyExecuted>(&meta.logs).expect("BuyExecuted event in logs");
+    assert_eq!(bought.user, user.pubkey());
+    assert_eq!(bought.outcome_index, 1);
+    assert_eq!(bought.amount_in, fill.net_refund);
+    assert_eq!(bought.amount_out, amount_out);
+    assert_eq!(bought.outcome_price, expected.outcome_price(1).unwrap());
+    assert!(!meta.logs.iter().any(|log| log.contains("amount_out:")));
+
+    // no lamports leave the vault, the sell fee stays behind as fees
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        vault_before
+    );
+    assert_eq!(market.undistributed_fees, expected.undistributed_fees);
+    assert_eq!(market.reserves, expected.reserves);
+}
+
+#[test]
+fn test_buy_rejects_output_below_min_amount_out() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "buy_slippage", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &admin, &accounts, 0, 100_000_000).unwrap();
+
+    let create_ata_ix =
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &user.pubkey(),
+            &user.pubkey(),
+            &accounts.outcome_mints[1],
+            &spl_token::ID,
+        );
+    let mut expected = load_market(&svm, &accounts.market);
+    let amount_out = expected.buy_outcome(1, 10_000_000).unwrap();
+
+    // asking for more than the curve gives fails without taking the deposit
+    let result = send(
+        &mut svm,
+        &[
+            create_ata_ix.clone(),
+            buy_ix(&user.pubkey(), &accounts, 1, 10_000_000, amount_out + 1),
+        ],
+        &user,
+        &[&user],
+    );
+    assert_error(result, 1, ErrorCode::SlippageExceeded);
+
+    send(
+        &mut svm,
+        &[
+            create_ata_ix,
+            buy_ix(&user.pubkey(), &accounts, 1, 10_000_000, amount_out),
+        ],
+        &user,
+        &[&user],
+    )
+    .unwrap();
+    assert_eq!(
+        token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 1)),
+        amount_out
+    );
+}
+
+#[test]
+fn test_sell_rejects_payout_below_min_payout() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "sell_slippage", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &admin, &accounts, 1, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    let token_account = accounts.user_token_account(&user.pubkey(), 0);
+    let held = token_balance(&svm, &token_account);
+    let supply = load_market(&svm, &accounts.market).supplies[0];
+
+    // a payout above the whole refund is never achievable once fees apply
+    let result = send(
+        &mut svm,
+        &[sell_ix(&user.pubkey(), &accounts, 0, held, u64::MAX)],
+        &user,
+        &[&user],
+    );
+    assert_error(result, 0, ErrorCode::SlippageExceeded);
+    assert_eq!(token_balance(&svm, &token_account), held);
+    assert_eq!(load_market(&svm, &accounts.market).supplies[0], supply);
+}
+
+#[test]
+fn test_buy_emits_buy_executed_event() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "buy_event", 2, DEFAULT_RESOLVE_AT);
+
+    let meta = buy(&mut svm, &user, &accounts, 1, 10_000_000).unwrap();
+
+    let event = find_event::<BuyExecuted>(&meta.logs).expect("BuyExecuted event in logs");
+
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(event.market, accounts.market);
+    assert_eq!(event.user, user.pubkey());
+    assert_eq!(event.outcome_index, 1);
+    assert_eq!(event.amount_in, 10_000_000);
+    assert_eq!(
+        event.amount_out,
+        token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 1))
+    );
+    assert_eq!(event.outcome_price, market.outcome_price(1).unwrap());
+    assert!(!meta.logs.iter().any(|log| log.contains("amount_out:")));
+}
+
+#[test]
+fn test_sell_emits_sell_executed_event() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "sell_event", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &admin, &accounts, 1, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    let user_before = svm.get_balance(&user.pubkey()).unwrap();
+    let meta = sell(&mut svm, &user, &accounts, 0, 40_000_000).unwrap();
+    let event = find_event::<SellExecuted>(&meta.logs).expect("SellExecuted event in logs");
+
+    assert_eq!(event.market, accounts.market);
+    assert_eq!(event.user, user.pubkey());
+    assert_eq!(event.outcome_index, 0);
+    assert_eq!(event.burn_amount, 40_000_000);
+    // user pays 5000 lamports for the tx fee
+    assert_eq!(
+        event.net_payout,
+        svm.get_balance(&user.pubkey()).unwrap() + 5000 - user_before
+    );
+    assert!(event.fee > 0);
+    assert_eq!(
+        event.fee,
+        load_market(&svm, &accounts.market).undistributed_fees
+    );
+}
+
+#[test]
+fn test_quote_buy_matches_minted_tokens() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "quote_buy", 3, DEFAULT_RESOLVE_AT);
+    let token_account = accounts.user_token_account(&user.pubkey(), 2);
+
+    buy(&mut svm, &admin, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 2, 1_000_000).unwrap();
+    for amount_in in [1_000_000, 7_777_777, 50_000_000] {
+        let quote = load_market(&svm, &accounts.market)
+            .quote_buy(2, amount_in)
+            .unwrap();
+        let before = token_balance(&svm, &token_account);
+        buy(&mut svm, &user, &accounts, 2, amount_in).unwrap();
+        assert_eq!(token_balance(&svm, &token_account) - before, quote);
+    }
+}
+
+#[test]
+fn test_quote_sell_matches_net_payout() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "quote_sell", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &admin, &accounts, 1, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    let quote = load_market(&svm, &accounts.market)
+        .quote_sell(0, 40_000_000, 0)
+        .unwrap();
+    let meta = sell(&mut svm, &user, &accounts, 0, 40_000_000).unwrap();
+    let event = find_event::<SellExecuted>(&meta.logs).unwrap();
+    assert_eq!(event.net_payout, quote);
+}
+
+#[test]
+fn test_set_limits_applies_to_later_buys() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "set_limits", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &admin, &accounts, 0, 100_000_000).unwrap();
+    let reserve_0 = load_market(&svm, &accounts.market).reserves[0];
+
+    // caps below what the market already holds are rejected
+    let ix = set_limits_ix(&admin.pubkey(), &accounts, 0, reserve_0 - 1, 0, 0);
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::InvalidLimits);
+
+    // only the market admin may change limits
+    let ix = set_limits_ix(&user.pubkey(), &accounts, 0, 0, 0, 0);
+    let result = send(&mut svm, &[ix], &user, &[&user]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    let max_reserve = reserve_0 + 20_000_000;
+    let ix = set_limits_ix(&admin.pubkey(), &accounts, 5_000_000, max_reserve, 0, 0);
+    let meta = send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    let event = find_event::<LimitsUpdated>(&meta.logs).expect("LimitsUpdated event in logs");
+    assert_eq!(event.market, accounts.market);
+    assert_eq!(event.min_deposit, 5_000_000);
+    assert_eq!(event.max_reserve, max_reserve);
+
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.min_deposit, 5_000_000);
+    assert_eq!(market.max_reserve, max_reserve);
+
+    let result = buy(&mut svm, &user, &accounts, 0, 4_999_999);
+    assert_error(result, 1, ErrorCode::TradeTooSmall);
+    let result = buy(&mut svm, &user, &accounts, 0, 30_000_000);
+    assert_error(result, 1, ErrorCode::MarketCapExceeded);
+    buy(&mut svm, &user, &accounts, 0, 10_000_000).unwrap();
+    assert!(load_market(&svm, &accounts.market).reserves[0] <= max_reserve);
+}
+
+#[test]
+fn test_paused_market_rejects_trades_until_unpaused() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "paused", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    // only the admin may pause
+    let ix = pause_market_ix(&user.pubkey(), &accounts, true);
+    let result = send(&mut svm, &[ix], &user, &[&user]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+
+    let ix = pause_market_ix(&admin.pubkey(), &accounts, true);
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    assert!(!load_market(&svm, &accounts.market).can_buy_outcome(1, 0));
+
+    let result = buy(&mut svm, &user, &accounts, 1, 100_000_000);
+    assert_error(result, 1, ErrorCode::MarketPaused);
+    let result = sell(&mut svm, &user, &accounts, 0, 10_000_000);
+    assert_error(result, 0, ErrorCode::MarketPaused);
+    let result = swap_outcome(&mut svm, &user, &accounts, 0, 1, 10_000_000, 0);
+    assert_error(result, 1, ErrorCode::MarketPaused);
+
+    let ix = pause_market_ix(&admin.pubkey(), &accounts, false);
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 0, 10_000_000).unwrap();
+}
+
+#[test]
+fn test_expiry_is_recorded_once_resolve_at_passes() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "awaiting", 2, DEFAULT_RESOLVE_AT);
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    let result = send(
+        &mut svm,
+        &[mark_awaiting_resolution_ix(&accounts)],
+        &user,
+        &[&user],
+    );
+    assert_error(result, 0, ErrorCode::MarketNotExpired);
+    assert_eq!(load_market(&svm, &accounts.market).awaiting_resolution, 0);
+
+    // anyone may record the flip once the clock crosses resolve_at
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    send(
+        &mut svm,
+        &[mark_awaiting_resolution_ix(&accounts)],
+        &user,
+        &[&user],
+    )
+    .unwrap();
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(market.awaiting_resolution, 1);
+    assert_eq!(
+        market.phase(DEFAULT_RESOLVE_AT).unwrap(),
+        MarketPhase::Resolving
+    );
+}
+
+#[test]
+fn test_update_resolve_at_reopens_trading() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "postponed", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    set_unix_timestamp(&mut svm, DEFAULT_RESOLVE_AT);
+    let result = buy(&mut svm, &user, &accounts, 1, 100_000_000);
+    assert_error(result, 1, ErrorCode::MarketExpired);
+
+    // only the admin may move it, and only into the future
+    let postponed = DEFAULT_RESOLVE_AT + 3_600;
+    let ix = update_resolve_at_ix(&user.pubkey(), &accounts, postponed);
+    let result = send(&mut svm, &[ix], &user, &[&user]);
+    assert_error(result, 0, ErrorCode::Unauthorized);
+    let ix = update_resolve_at_ix(&admin.pubkey(), &accounts, DEFAULT_RESOLVE_AT);
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::InvalidResolveTime);
+
+    let ix = update_resolve_at_ix(&admin.pubkey(), &accounts, postponed);
+    send(&mut svm, &[ix], &admin, &[&admin]).unwrap();
+    assert_eq!(load_market(&svm, &accounts.market).resolve_at, postponed);
+
+    // the rejected buy now goes through, and resolution waits for the new time
+    buy(&mut svm, &user, &accounts, 1, 100_000_000).unwrap();
+    let result = send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    );
+    assert_error(result, 0, ErrorCode::MarketNotExpired);
+
+    set_unix_timestamp(&mut svm, postponed);
+    send(
+        &mut svm,
+        &[resolve_market_ix(&admin.pubkey(), &accounts, 0, None)],
+        &admin,
+        &[&admin],
+    )
+    .unwrap();
+    let ix = update_resolve_at_ix(&admin.pubkey(), &accounts, postponed + 3_600);
+    let result = send(&mut svm, &[ix], &admin, &[&admin]);
+    assert_error(result, 0, ErrorCode::MarketResolved);
+}
+
+#[test]
+fn test_buy_rejected_past_max_buyable_price() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market_with_config(
+        &mut svm,
+        &admin,
+        "price_cap",
+        2,
+        DEFAULT_RESOLVE_AT,
+        MarketConfig {
+            max_buyable_price: 900_000_000,
+            ..open_config()
+        },
+    );
+
+    // the opening buy counts too: 0.1 SOL on top of the 2 * DEFAULT_SCALE opening reserves
+    // leaves outcome 1 at ~99.8%
+    let result = buy(&mut svm, &user, &accounts, 1, 100_000_000);
+    assert_error(result, 1, ErrorCode::OutcomePriceTooHigh);
+    buy(&mut svm, &user, &accounts, 1, 500_000).unwrap();
+    buy(&mut svm, &user, &accounts, 0, 3_000_000).unwrap();
+
+    // outcome 0 already holds ~88.6% of the reserves, this buy would take it past 90%
+    let result = buy(&mut svm, &user, &accounts, 0, 1_000_000);
+    assert_error(result, 1, ErrorCode::OutcomePriceTooHigh);
+
+    // smaller buys below the cap, and buys into the other outcome, still go through
+    buy(&mut svm, &user, &accounts, 0, 100_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 3_000_000).unwrap();
+}
+
+#[test]
+fn test_batch_buy_fills_both_outcomes_in_one_instruction() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "batch_buy", 2, DEFAULT_RESOLVE_AT);
+    buy(&mut svm, &admin, &accounts, 0, 100_000_000).unwrap();
+
+    let leg = |outcome_index, amount_in| BuyLeg {
+        outcome_index,
+        amount_in,
+        min_amount_out: 0,
+    };
+    let create_atas: Vec<_> = accounts
+        .outcome_mints
+        .iter()
+        .map(|mint| {
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &user.pubkey(),
+                &user.pubkey(),
+                mint,
+                &anchor_spl::token::ID,
+            )
+        })
+        .collect();
+
+    // empty and oversized batches are rejected
+    let ix = batch_buy_ix(&user.pubkey(), &accounts, vec![]);
+    let result = send(&mut svm, &[ix], &user, &[&user]);
+    assert_error(result, 0, ErrorCode::InvalidBatchLength);
+    let ix = batch_buy_ix(&user.pubkey(), &accounts, vec![leg(0, 1_000_000); 9]);
+    let result = send(
+        &mut svm,
+        &[create_atas.clone(), vec![ix]].concat(),
+        &user,
+        &[&user],
+    );
+    assert_error(result, 2, ErrorCode::InvalidBatchLength);
+
+    let mut expected = load_market(&svm, &accounts.market);
+    let out_0 = expected.buy_outcome(0, 50_000_000).unwrap();
+    let out_1 = expected.buy_outcome(1, 80_000_000).unwrap();
+    let vault_before = svm.get_balance(&accounts.market_vault).unwrap();
+
+    // a leg short of its minimum fails the whole batch
+    let mut legs = vec![leg(0, 50_000_000), leg(1, 80_000_000)];
+    legs[1].min_amount_out = out_1 + 1;
+    let ix = batch_buy_ix(&user.pubkey(), &accounts, legs);
+    let result = send(
+        &mut svm,
+        &[create_atas.clone(), vec![ix]].concat(),
+        &user,
+        &[&user],
+    );
+    assert_error(result, 2, ErrorCode::SlippageExceeded);
+
+    let ix = batch_buy_ix(
+        &user.pubkey(),
+        &accounts,
+        vec![leg(0, 50_000_000), leg(1, 80_000_000)],
+    );
+    send(&mut svm, &[create_atas, vec![ix]].concat(), &user, &[&user]).unwrap();
+
+    assert_eq!(
+        token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 0)),
+        out_0
+    );
+    assert_eq!(
+        token_balance(&svm, &accounts.user_token_account(&user.pubkey(), 1)),
+        out_1
+    );
+    assert_eq!(
+        svm.get_balance(&accounts.market_vault).unwrap(),
+        vault_before + 130_000_000
+    );
+    assert_eq!(
+        load_market(&svm, &accounts.market).reserves,
+        expected.reserves
+    );
+}
@@ -0,0 +1,87 @@
+use common::constants::{MARKET_SEED, MAX_OUTCOMES, NO_OUTCOME};
+use common::errors::ErrorCode;
+use gamma::types::{outcome_index_u8, FixedSizeString, MAX_PADDED_STRING_LENGTH};
+use solana_sdk::pubkey::Pubkey;
+
+#[test]
+fn test_market_pda_uses_padded_label_bytes() {
+    let program_id = gamma::id();
+    let label = "BTC above 100k";
+    let fixed = FixedSizeString::new(label);
+
+    let mut padded = [0u8; MAX_PADDED_STRING_LENGTH];
+    padded[..label.len()].copy_from_slice(label.as_bytes());
+    let expected = Pubkey::find_program_address(&[MARKET_SEED, &padded], &program_id);
+
+    assert_eq!(fixed.market_pda(0, &program_id), expected);
+    assert_eq!(
+        FixedSizeString::new(label).market_pda(0, &program_id),
+        fixed.market_pda(0, &program_id)
+    );
+
+    // seeding with the raw str derives a different address
+    let unpadded = Pubkey::find_program_address(&[MARKET_SEED, label.as_bytes()], &program_id);
+    assert_ne!(fixed.market_pda(0, &program_id).0, unpadded.0);
+}
+
+#[test]
+fn test_market_pda_nonce() {
+    let program_id = gamma::id();
+    let label = FixedSizeString::new("BTC price");
+
+    // nonce 0 keeps the address derived without a nonce
+    let legacy = Pubkey::find_program_address(&[MARKET_SEED, label.as_bytes()], &program_id);
+    assert_eq!(label.market_pda(0, &program_id), legacy);
+
+    let week_1 = label.market_pda(1, &program_id);
+    let week_2 = label.market_pda(2, &program_id);
+    assert_ne!(week_1.0, legacy.0);
+    assert_ne!(week_1.0, week_2.0);
+    assert_eq!(
+        week_1,
+        Pubkey::find_program_address(
+            &[MARKET_SEED, label.as_bytes(), &1u64.to_le_bytes()],
+            &program_id
+        )
+    );
+}
+
+#[test]
+fn test_outcome_index_u8_rejects_out_of_range() {
+    assert_eq!(outcome_index_u8(0).unwrap(), 0);
+    assert_eq!(
+        outcome_index_u8(MAX_OUTCOMES - 1).unwrap(),
+        (MAX_OUTCOMES - 1) as u8
+    );
+
+    // indices past MAX_OUTCOMES, including ones that would wrap a u8 cast, are rejected
+    for index in [MAX_OUTCOMES, NO_OUTCOME as usize, 256, 257] {
+        let err = outcome_index_u8(index).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidOutcomeIndex.into());
+    }
+}
+
+#[test]
+fn test_label_validation() {
+    FixedSizeString::new("BTC above 100k?").validate().unwrap();
+    FixedSizeString::new(&"x".repeat(MAX_PADDED_STRING_LENGTH))
+        .validate()
+        .unwrap();
+
+    for blank in ["", " ", "  \t "] {
+        let err = FixedSizeString::new(blank).validate().unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidLabelLength.into());
+    }
+
+    // control characters, non-ASCII text and bytes hidden behind the padding
+    let mut hidden = FixedSizeString::new("label");
+    hidden.value[MAX_PADDED_STRING_LENGTH - 1] = b'x';
+    for label in [
+        FixedSizeString::new("tab\tlabel"),
+        FixedSizeString::new("café"),
+        hidden,
+    ] {
+        let err = label.validate().unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidLabelCharacters.into());
+    }
+}
@@ -0,0 +1,141 @@
+mod helpers;
+
+use anchor_lang::{
+    solana_program::instruction::Instruction, AnchorDeserialize, InstructionData, ToAccountMetas,
+};
+use gamma::types::VaultHealth;
+use helpers::*;
+
+fn get_probabilities_ix(accounts: &MarketAccounts) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::GetProbabilities {}.data(),
+        gamma::accounts::GetProbabilities {
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+#[test]
+fn test_get_probabilities() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "probabilities", 3, DEFAULT_RESOLVE_AT);
+
+    // uneven deposits so the raw shares don't divide evenly into bps
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 33_333_333).unwrap();
+    buy(&mut svm, &user, &accounts, 2, 7_777_777).unwrap();
+
+    let meta = send(
+        &mut svm,
+        &[get_probabilities_ix(&accounts)],
+        &user,
+        &[&user],
+    )
+    .unwrap();
+    let probabilities = Vec::<u16>::try_from_slice(&meta.return_data.data).unwrap();
+
+    assert_eq!(probabilities.len(), 3);
+    assert_eq!(probabilities.iter().map(|p| *p as u32).sum::<u32>(), 10_000);
+    // outcome 0 holds the most reserves so it is the favorite
+    assert!(probabilities[0] > probabilities[1]);
+    assert!(probabilities[1] > probabilities[2]);
+}
+
+fn get_vault_health_ix(accounts: &MarketAccounts) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::GetVaultHealth {}.data(),
+        gamma::accounts::GetVaultHealth {
+            market: accounts.market,
+            market_vault: accounts.market_vault,
+            collateral_vault: accounts.collateral_vault(),
+        }
+        .to_account_metas(None),
+    )
+}
+
+#[test]
+fn test_get_vault_health() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "vault_health", 2, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+
+    // the opening buy funds every opening reserve, so the vault is healthy from the start
+    let meta = send(&mut svm, &[get_vault_health_ix(&accounts)], &user, &[&user]).unwrap();
+    let health = VaultHealth::try_from_slice(&meta.return_data.data).unwrap();
+    assert!(health.is_solvent);
+    assert_eq!(
+        health.vault_lamports,
+        health.sum_reserves + health.undistributed_fees + health.rent_min
+    );
+
+    buy(&mut svm, &user, &accounts, 1, 50_000_000).unwrap();
+    sell(&mut svm, &user, &accounts, 0, 40_000_000).unwrap();
+
+    svm.expire_blockhash();
+    let meta = send(&mut svm, &[get_vault_health_ix(&accounts)], &user, &[&user]).unwrap();
+    let health = VaultHealth::try_from_slice(&meta.return_data.data).unwrap();
+
+    assert!(health.is_solvent);
+    assert!(health.undistributed_fees > 0);
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(
+        health.sum_reserves as u128,
+        market.total_reserves_u128().unwrap()
+    );
+    assert_eq!(health.rent_min, svm.minimum_balance_for_rent_exemption(0));
+    // a fully backed vault holds exactly the reserves, fees and rent
+    assert_eq!(
+        health.vault_lamports,
+        svm.get_balance(&accounts.market_vault).unwrap()
+    );
+    assert_eq!(
+        health.vault_lamports,
+        health.sum_reserves
+            + health.undistributed_fees
+            + health.undistributed_settlement_fees
+            + health.rent_min
+    );
+}
+
+fn get_exposure_ix(accounts: &MarketAccounts) -> Instruction {
+    Instruction::new_with_bytes(
+        gamma::id(),
+        &gamma::instruction::GetExposure {}.data(),
+        gamma::accounts::GetExposure {
+            market: accounts.market,
+        }
+        .to_account_metas(None),
+    )
+}
+
+#[test]
+fn test_get_exposure() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let user = funded_keypair(&mut svm);
+    let accounts = init_market(&mut svm, &admin, "exposure", 3, DEFAULT_RESOLVE_AT);
+
+    buy(&mut svm, &user, &accounts, 0, 100_000_000).unwrap();
+    buy(&mut svm, &user, &accounts, 1, 50_000_000).unwrap();
+
+    let meta = send(&mut svm, &[get_exposure_ix(&accounts)], &user, &[&user]).unwrap();
+    let exposures = Vec::<u64>::try_from_slice(&meta.return_data.data).unwrap();
+
+    let market = load_market(&svm, &accounts.market);
+    assert_eq!(exposures.len(), 3);
+    for (winner, exposure) in exposures.iter().enumerate() {
+        assert_eq!(*exposure, market.simulate_resolution(winner).unwrap());
+    }
+    // the opener holds every outcome, so whichever wins takes the whole pot
+    for exposure in exposures {
+        assert_eq!(exposure as u128, market.total_reserves_u128().unwrap());
+    }
+}